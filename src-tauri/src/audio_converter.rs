@@ -1,27 +1,204 @@
 extern crate ffmpeg_next as ffmpeg;
 
+use crate::types::{AudioStreamInfo, Chapter, DownmixMode, MediaInfo, ResampleQuality};
+
 use anyhow::Result;
+use std::collections::VecDeque;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Bounds for `pre_gain_db`.
+const MAX_PRE_GAIN_DB: f32 = 20.0;
+/// Level, as a fraction of full scale, above which the limiter starts to act.
+const LIMITER_THRESHOLD: f32 = 0.9;
+/// Upper bound on waveform buckets, whatever the caller asks for.
+const MAX_WAVEFORM_BUCKETS: usize = 4000;
+/// Rate the audio is decoded at for waveforms; peaks don't need more.
+const WAVEFORM_SAMPLE_RATE: u32 = 8000;
+/// soxr precision in bits used for `ResampleQuality::High`, its "very high" preset.
+const SOXR_PRECISION: &str = "28";
 
-pub struct AudioConverter {}
+/// Codecs checked by `audio_decoders`, covering the usual audio and video inputs.
+const COMMON_AUDIO_CODECS: &[ffmpeg::codec::Id] = &[
+    ffmpeg::codec::Id::MP3,
+    ffmpeg::codec::Id::AAC,
+    ffmpeg::codec::Id::FLAC,
+    ffmpeg::codec::Id::VORBIS,
+    ffmpeg::codec::Id::OPUS,
+    ffmpeg::codec::Id::ALAC,
+    ffmpeg::codec::Id::AC3,
+    ffmpeg::codec::Id::EAC3,
+    ffmpeg::codec::Id::WMAV2,
+    ffmpeg::codec::Id::AMR_NB,
+    ffmpeg::codec::Id::PCM_S16LE,
+    ffmpeg::codec::Id::PCM_S24LE,
+    ffmpeg::codec::Id::PCM_F32LE,
+];
+
+/// Outcome of the one-time ffmpeg initialization, shared by every converter.
+static FFMPEG_INIT: OnceLock<Result<(), String>> = OnceLock::new();
+
+/// Cheap to create: ffmpeg itself is initialized once per process.
+#[derive(Debug, Clone, Copy)]
+pub struct AudioConverter {
+    /// Linear gain applied to the converted samples.
+    gain: f32,
+    /// Stream to convert instead of the best audio stream.
+    audio_stream: Option<usize>,
+    resample_quality: ResampleQuality,
+    downmix: DownmixMode,
+    /// Conversion fails once no audio has been decoded for this long.
+    stall_timeout: Option<Duration>,
+}
 
 impl AudioConverter {
-    pub fn new() -> Self {
-        ffmpeg::init().unwrap();
+    /// Fails if the ffmpeg libraries could not be initialized, so callers can
+    /// report the audio backend as unavailable instead of panicking.
+    pub fn new() -> Result<Self> {
+        Self::init()?;
 
-        Self {}
+        Ok(Self {
+            gain: 1.0,
+            audio_stream: None,
+            resample_quality: ResampleQuality::Fast,
+            downmix: DownmixMode::Average,
+            stall_timeout: None,
+        })
     }
 
-    pub fn convert_to_wav(&self, input_path: &Path, output_path: &Path) -> Result<()> {
-        let mut input = ffmpeg::format::input(&Path::new(input_path))?;
-        let mut output = ffmpeg::format::output(&Path::new(output_path))?;
+    /// Initializes ffmpeg on first use; later calls return the first outcome.
+    pub fn init() -> Result<()> {
+        FFMPEG_INIT
+            .get_or_init(|| ffmpeg::init().map_err(|e| e.to_string()))
+            .clone()
+            .map_err(|e| anyhow::anyhow!("Audio backend unavailable: {}", e))
+    }
+
+    /// Boosts or cuts the converted audio, clamped to +/-20 dB. A soft limiter
+    /// after the gain keeps boosted peaks from clipping.
+    pub fn with_pre_gain_db(mut self, gain_db: f32) -> Self {
+        let gain_db = gain_db.clamp(-MAX_PRE_GAIN_DB, MAX_PRE_GAIN_DB);
+        self.gain = 10f32.powf(gain_db / 20.0);
+        self
+    }
 
-        // Find the best audio stream
-        let input_stream = input
+    /// Converts the stream at `index` instead of the best audio stream.
+    pub fn with_audio_stream(mut self, index: Option<usize>) -> Self {
+        self.audio_stream = index;
+        self
+    }
+
+    pub fn with_resample_quality(mut self, quality: ResampleQuality) -> Self {
+        self.resample_quality = quality;
+        self
+    }
+
+    /// `LeftOnly` and `RightOnly` keep a single channel, picked before
+    /// resampling, instead of averaging all of them.
+    pub fn with_downmix(mut self, mode: DownmixMode) -> Self {
+        self.downmix = mode;
+        self
+    }
+
+    /// Gives up on inputs that stop producing audio, e.g. malformed files
+    /// ffmpeg keeps reading without decoding anything. Zero disables it.
+    pub fn with_stall_timeout(mut self, secs: u64) -> Self {
+        self.stall_timeout = (secs > 0).then(|| Duration::from_secs(secs));
+        self
+    }
+
+    /// Describes every audio stream of the file, so one can be picked for
+    /// multi-track media.
+    pub fn list_audio_streams(&self, input_path: &Path) -> Result<Vec<AudioStreamInfo>> {
+        let input = ffmpeg::format::input(&input_path)?;
+        Self::audio_streams(&input)
+    }
+
+    /// Container and audio stream details, for explaining slow or odd files.
+    pub fn media_info(&self, input_path: &Path) -> Result<MediaInfo> {
+        let input = ffmpeg::format::input(&input_path)?;
+
+        let duration_secs = input
             .streams()
             .best(ffmpeg::media::Type::Audio)
-            .ok_or_else(|| anyhow::anyhow!("No audio stream found"))?;
+            .map(|stream| Self::duration_secs(&input, &stream))
+            .filter(|secs| *secs > 0.0);
+        Ok(MediaInfo {
+            format: input.format().description().to_string(),
+            duration_secs,
+            bit_rate: (input.bit_rate() > 0).then_some(input.bit_rate() as usize),
+            audio_streams: Self::audio_streams(&input)?,
+        })
+    }
+
+    /// Chapter markers in the container, by start time. Untitled chapters are
+    /// numbered.
+    pub fn chapters(&self, input_path: &Path) -> Result<Vec<Chapter>> {
+        let input = ffmpeg::format::input(&input_path)?;
+        let mut chapters: Vec<Chapter> = input
+            .chapters()
+            .enumerate()
+            .map(|(index, chapter)| {
+                let ms_per_tick = f64::from(chapter.time_base()) * 1000.0;
+                let to_ms = |time: i64| (time as f64 * ms_per_tick) as i64;
+                let title = chapter
+                    .metadata()
+                    .get("title")
+                    .map(str::trim)
+                    .filter(|title| !title.is_empty())
+                    .map_or_else(|| format!("Chapter {}", index + 1), str::to_string);
+                Chapter {
+                    title,
+                    start_ms: to_ms(chapter.start()),
+                    end_ms: to_ms(chapter.end()),
+                }
+            })
+            .collect();
+        chapters.sort_by_key(|chapter| chapter.start_ms);
+        Ok(chapters)
+    }
+
+    fn audio_streams(input: &ffmpeg::format::context::Input) -> Result<Vec<AudioStreamInfo>> {
+        let mut streams = Vec::new();
+        for stream in input.streams() {
+            let parameters = stream.parameters();
+            if parameters.medium() != ffmpeg::media::Type::Audio {
+                continue;
+            }
+            let decoder = ffmpeg::codec::context::Context::from_parameters(parameters.clone())?
+                .decoder()
+                .audio()?;
+            streams.push(AudioStreamInfo {
+                index: stream.index(),
+                codec: parameters.id().name().to_string(),
+                language: stream.metadata().get("language").map(str::to_string),
+                channels: decoder.channels(),
+                sample_rate: decoder.rate(),
+                bit_rate: (decoder.bit_rate() > 0).then_some(decoder.bit_rate()),
+            });
+        }
+        Ok(streams)
+    }
+
+    /// Converts to 16 kHz mono WAV, reporting progress in percent. Setting
+    /// `cancel`, or hitting the stall timeout, stops between packets and
+    /// removes the partial output.
+    pub fn convert_to_wav(
+        &self,
+        input_path: &Path,
+        output_path: &Path,
+        cancel: &AtomicBool,
+        mut on_progress: impl FnMut(f32),
+    ) -> Result<()> {
+        let mut input = ffmpeg::format::input(&Path::new(input_path))?;
+        let mut output = ffmpeg::format::output(&Path::new(output_path))?;
+
+        let input_stream = self.select_audio_stream(&input)?;
         let stream_index = input_stream.index();
+        let time_base = input_stream.time_base();
+        let total_secs = Self::duration_secs(&input, &input_stream);
 
         // Get decoder for the input audio stream
         let context_decoder =
@@ -56,24 +233,38 @@ impl AudioConverter {
         // Write header
         output.write_header()?;
 
-        // Create resampler for format conversion
-        let mut resampler = ffmpeg::software::resampling::context::Context::get(
-            decoder.format(),
-            decoder.channel_layout(),
-            decoder.rate(),
-            ffmpeg::format::Sample::I16(ffmpeg::format::sample::Type::Packed),
-            ffmpeg::util::channel_layout::ChannelLayout::MONO,
-            16000,
-        )?;
+        let mut channel_picker = self.channel_picker(&decoder)?;
+        let mut resampler = self.wav_resampler(&decoder, channel_picker.is_some())?;
 
         let mut frame_index = 0;
+        let mut last_frame_index = 0;
+        let mut last_progress_at = Instant::now();
+        let mut stalled = false;
 
         // Process packets
         for (stream, packet) in input.packets() {
+            if cancel.load(Ordering::SeqCst) {
+                break;
+            }
+            if frame_index != last_frame_index {
+                last_frame_index = frame_index;
+                last_progress_at = Instant::now();
+            } else if self
+                .stall_timeout
+                .is_some_and(|timeout| last_progress_at.elapsed() >= timeout)
+            {
+                stalled = true;
+                break;
+            }
             if stream.index() == stream_index {
+                if let Some(pts) = packet.pts().filter(|_| total_secs > 0.0) {
+                    let position = pts as f64 * f64::from(time_base);
+                    on_progress((position / total_secs * 100.0).clamp(0.0, 100.0) as f32);
+                }
                 decoder.send_packet(&packet)?;
                 self.receive_and_process_frames(
                     &mut decoder,
+                    &mut channel_picker,
                     &mut resampler,
                     &mut encoder,
                     &mut output,
@@ -82,10 +273,25 @@ impl AudioConverter {
             }
         }
 
+        if cancel.load(Ordering::SeqCst) {
+            drop(output);
+            let _ = std::fs::remove_file(output_path);
+            return Err(anyhow::anyhow!("Conversion cancelled"));
+        }
+        if stalled {
+            drop(output);
+            let _ = std::fs::remove_file(output_path);
+            return Err(anyhow::anyhow!(
+                "conversion timed out after {} seconds without progress",
+                self.stall_timeout.unwrap_or_default().as_secs()
+            ));
+        }
+
         // Flush decoder
         decoder.send_eof()?;
         self.receive_and_process_frames(
             &mut decoder,
+            &mut channel_picker,
             &mut resampler,
             &mut encoder,
             &mut output,
@@ -94,13 +300,150 @@ impl AudioConverter {
 
         // Write trailer
         output.write_trailer()?;
+        on_progress(100.0);
 
         Ok(())
     }
 
+    /// Resampler to the 16 kHz mono s16 output format. The output format is the
+    /// same at either quality; `High` only changes how it's computed. Falls back
+    /// to the default resampler when ffmpeg was built without soxr. With
+    /// `picked_channel`, its input is the mono output of a `ChannelPicker`.
+    fn wav_resampler(
+        &self,
+        decoder: &ffmpeg::decoder::Audio,
+        picked_channel: bool,
+    ) -> Result<ffmpeg::software::resampling::context::Context> {
+        let (format, layout) = if picked_channel {
+            (
+                ffmpeg::format::Sample::F32(ffmpeg::format::sample::Type::Planar),
+                ffmpeg::util::channel_layout::ChannelLayout::MONO,
+            )
+        } else {
+            (decoder.format(), decoder.channel_layout())
+        };
+        let resampler = |options| {
+            ffmpeg::software::resampling::context::Context::get_with(
+                format,
+                layout,
+                decoder.rate(),
+                ffmpeg::format::Sample::I16(ffmpeg::format::sample::Type::Packed),
+                ffmpeg::util::channel_layout::ChannelLayout::MONO,
+                16000,
+                options,
+            )
+        };
+
+        if self.resample_quality == ResampleQuality::High {
+            let mut options = ffmpeg::Dictionary::new();
+            options.set("resampler", "soxr");
+            options.set("precision", SOXR_PRECISION);
+            match resampler(options) {
+                Ok(resampler) => return Ok(resampler),
+                Err(e) => log::warn!("soxr resampler unavailable, using the default: {}", e),
+            }
+        }
+        Ok(resampler(ffmpeg::Dictionary::new())?)
+    }
+
+    /// The picker for the chosen `downmix`, or `None` when averaging.
+    fn channel_picker(&self, decoder: &ffmpeg::decoder::Audio) -> Result<Option<ChannelPicker>> {
+        let channel = match self.downmix {
+            DownmixMode::Average => return Ok(None),
+            DownmixMode::LeftOnly => 0,
+            DownmixMode::RightOnly => 1,
+        };
+        let planar = ffmpeg::software::resampling::context::Context::get(
+            decoder.format(),
+            decoder.channel_layout(),
+            decoder.rate(),
+            ffmpeg::format::Sample::F32(ffmpeg::format::sample::Type::Planar),
+            decoder.channel_layout(),
+            decoder.rate(),
+        )?;
+        Ok(Some(ChannelPicker { planar, channel }))
+    }
+
+    /// Peak amplitude (0..1) of each of `buckets` equal slices of the audio,
+    /// computed in one decode pass without writing anything to disk.
+    pub fn waveform(&self, input_path: &Path, buckets: usize) -> Result<Vec<f32>> {
+        let mut input = ffmpeg::format::input(&input_path)?;
+        let input_stream = self.select_audio_stream(&input)?;
+        let stream_index = input_stream.index();
+        let total_secs = Self::duration_secs(&input, &input_stream);
+        if total_secs <= 0.0 {
+            return Err(anyhow::anyhow!("Unknown duration"));
+        }
+
+        let context_decoder =
+            ffmpeg::codec::context::Context::from_parameters(input_stream.parameters())?;
+        let mut decoder = context_decoder.decoder().audio()?;
+        let mut resampler = ffmpeg::software::resampling::context::Context::get(
+            decoder.format(),
+            decoder.channel_layout(),
+            decoder.rate(),
+            ffmpeg::format::Sample::F32(ffmpeg::format::sample::Type::Packed),
+            ffmpeg::util::channel_layout::ChannelLayout::MONO,
+            WAVEFORM_SAMPLE_RATE,
+        )?;
+
+        let total_samples = (total_secs * WAVEFORM_SAMPLE_RATE as f64).ceil() as usize;
+        let mut peaks = PeakBuckets::new(buckets.clamp(1, MAX_WAVEFORM_BUCKETS), total_samples);
+        let mut decoded = ffmpeg::util::frame::audio::Audio::empty();
+        let mut resampled = ffmpeg::util::frame::audio::Audio::empty();
+        let mut drain = |decoder: &mut ffmpeg::decoder::Audio| -> Result<()> {
+            while decoder.receive_frame(&mut decoded).is_ok() {
+                resampler.run(&decoded, &mut resampled)?;
+                peaks.push(resampled.plane::<f32>(0));
+            }
+            Ok(())
+        };
+
+        for (stream, packet) in input.packets() {
+            if stream.index() == stream_index {
+                decoder.send_packet(&packet)?;
+                drain(&mut decoder)?;
+            }
+        }
+        decoder.send_eof()?;
+        drain(&mut decoder)?;
+
+        Ok(peaks.peaks)
+    }
+
+    /// The stream chosen with `with_audio_stream`, or else the best audio stream.
+    fn select_audio_stream<'a>(
+        &self,
+        input: &'a ffmpeg::format::context::Input,
+    ) -> Result<ffmpeg::format::stream::Stream<'a>> {
+        match self.audio_stream {
+            Some(index) => input
+                .stream(index)
+                .filter(|stream| stream.parameters().medium() == ffmpeg::media::Type::Audio)
+                .ok_or_else(|| anyhow::anyhow!("Audio stream {} not found", index)),
+            None => input
+                .streams()
+                .best(ffmpeg::media::Type::Audio)
+                .ok_or_else(|| anyhow::anyhow!("No audio stream found")),
+        }
+    }
+
+    /// Duration in seconds from the container, or else the stream; 0 if unknown.
+    fn duration_secs(
+        input: &ffmpeg::format::context::Input,
+        stream: &ffmpeg::format::stream::Stream,
+    ) -> f64 {
+        if input.duration() > 0 {
+            input.duration() as f64 / f64::from(ffmpeg::ffi::AV_TIME_BASE)
+        } else {
+            (stream.duration() as f64 * f64::from(stream.time_base())).max(0.0)
+        }
+    }
+
     fn receive_and_process_frames(
         &self,
         decoder: &mut ffmpeg::decoder::Audio,
+        channel_picker: &mut Option<ChannelPicker>,
         resampler: &mut ffmpeg::software::resampling::context::Context,
         encoder: &mut ffmpeg::encoder::Audio,
         output: &mut ffmpeg::format::context::Output,
@@ -110,7 +453,14 @@ impl AudioConverter {
 
         while decoder.receive_frame(&mut decoded).is_ok() {
             let mut resampled = ffmpeg::util::frame::audio::Audio::empty();
-            resampler.run(&decoded, &mut resampled)?;
+            match channel_picker {
+                Some(picker) => resampler.run(&picker.pick(&decoded)?, &mut resampled)?,
+                None => resampler.run(&decoded, &mut resampled)?,
+            };
+
+            if self.gain != 1.0 {
+                Self::apply_gain(resampled.plane_mut::<i16>(0), self.gain);
+            }
 
             resampled.set_pts(Some(*frame_index as i64));
             *frame_index += resampled.samples();
@@ -134,7 +484,100 @@ impl AudioConverter {
         Ok(())
     }
 
-    pub fn is_audio_file(&self, path: &Path) -> bool {
+    /// Scales the samples and soft-limits anything above `LIMITER_THRESHOLD`,
+    /// so peaks approach full scale instead of clipping.
+    fn apply_gain(samples: &mut [i16], gain: f32) {
+        for sample in samples.iter_mut() {
+            let value = *sample as f32 / i16::MAX as f32 * gain;
+            let magnitude = value.abs();
+            let limited = if magnitude > LIMITER_THRESHOLD {
+                let headroom = 1.0 - LIMITER_THRESHOLD;
+                LIMITER_THRESHOLD + headroom * ((magnitude - LIMITER_THRESHOLD) / headroom).tanh()
+            } else {
+                magnitude
+            };
+            *sample = (limited.copysign(value) * i16::MAX as f32) as i16;
+        }
+    }
+
+    /// Returns the media duration in seconds from the container header.
+    pub fn probe_duration(&self, input_path: &Path) -> Result<f64> {
+        let input = ffmpeg::format::input(&input_path)?;
+
+        let duration = input.duration();
+        if duration > 0 {
+            return Ok(duration as f64 / f64::from(ffmpeg::ffi::AV_TIME_BASE));
+        }
+
+        // Some containers only report the duration on the stream
+        let stream = input
+            .streams()
+            .best(ffmpeg::media::Type::Audio)
+            .ok_or_else(|| anyhow::anyhow!("No audio stream found"))?;
+        if stream.duration() > 0 {
+            return Ok(stream.duration() as f64 * f64::from(stream.time_base()));
+        }
+
+        Err(anyhow::anyhow!("Unknown duration"))
+    }
+
+    /// The ffmpeg CLI invocation that `convert_to_wav` reproduces, for running a
+    /// conversion outside the app when reporting problems.
+    pub fn equivalent_ffmpeg_command(
+        input_path: &Path,
+        output_path: &Path,
+        quality: ResampleQuality,
+        downmix: DownmixMode,
+    ) -> String {
+        let mut filters = Vec::new();
+        match downmix {
+            DownmixMode::Average => {}
+            DownmixMode::LeftOnly => filters.push("pan=mono|c0=c0".to_string()),
+            DownmixMode::RightOnly => filters.push("pan=mono|c0=c1".to_string()),
+        }
+        if quality == ResampleQuality::High {
+            filters.push(format!(
+                "aresample=resampler=soxr:precision={}",
+                SOXR_PRECISION
+            ));
+        }
+        let filters = if filters.is_empty() {
+            String::new()
+        } else {
+            format!("-af \"{}\" ", filters.join(","))
+        };
+        format!(
+            "ffmpeg -i {} -map 0:a:0 -vn {}-ar 16000 -ac 1 -c:a pcm_s16le {}",
+            Self::shell_quote(input_path),
+            filters,
+            Self::shell_quote(output_path)
+        )
+    }
+
+    pub(crate) fn shell_quote(path: &Path) -> String {
+        let path = path.to_string_lossy();
+        let is_plain = path
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "/\\._-:".contains(c));
+        if is_plain && !path.is_empty() {
+            path.to_string()
+        } else if cfg!(target_os = "windows") {
+            format!("\"{}\"", path)
+        } else {
+            format!("'{}'", path.replace('\'', "'\\''"))
+        }
+    }
+
+    /// Names of the common audio codecs this ffmpeg build can decode.
+    pub fn audio_decoders(&self) -> Vec<String> {
+        COMMON_AUDIO_CODECS
+            .iter()
+            .filter_map(|id| ffmpeg::decoder::find(*id))
+            .map(|codec| codec.name().to_string())
+            .collect()
+    }
+
+    pub fn is_audio_file(path: &Path) -> bool {
         let audio_extensions = [
             "mp3", "wav", "flac", "m4a", "aac", "ogg", "wma", "opus", "mp4", "mkv", "avi", "mov",
             "wmv", "flv", "webm", "3gp",
@@ -147,6 +590,174 @@ impl AudioConverter {
         }
         false
     }
+
+    pub fn is_video_file(path: &Path) -> bool {
+        let video_extensions = ["mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "3gp"];
+
+        if let Some(extension) = path.extension() {
+            if let Some(ext_str) = extension.to_str() {
+                return video_extensions.contains(&ext_str.to_lowercase().as_str());
+            }
+        }
+        false
+    }
+
+    /// Writes a copy of `media_path` with the subtitle file added as an extra
+    /// track. Audio and video are stream-copied, so nothing is re-encoded.
+    pub fn mux_subtitles(
+        &self,
+        media_path: &Path,
+        subtitle_path: &Path,
+        output_path: &Path,
+    ) -> Result<()> {
+        let mut media = ffmpeg::format::input(&media_path)?;
+        let mut subtitles = ffmpeg::format::input(&subtitle_path)?;
+        let mut output = ffmpeg::format::output(&output_path)?;
+
+        // Map the audio and video streams of the source; its own subtitle and
+        // data streams are dropped since not every codec fits the new container
+        let mut stream_mapping = vec![None; media.nb_streams() as usize];
+        let mut input_time_bases = vec![ffmpeg::Rational(0, 1); media.nb_streams() as usize];
+        let mut output_count = 0;
+        for (index, stream) in media.streams().enumerate() {
+            let medium = stream.parameters().medium();
+            if medium != ffmpeg::media::Type::Audio && medium != ffmpeg::media::Type::Video {
+                continue;
+            }
+
+            let mut output_stream =
+                output.add_stream(ffmpeg::encoder::find(ffmpeg::codec::Id::None))?;
+            output_stream.set_parameters(stream.parameters());
+            // The source container's codec tag may not be valid in the new one
+            unsafe {
+                (*output_stream.parameters().as_mut_ptr()).codec_tag = 0;
+            }
+            stream_mapping[index] = Some(output_count);
+            input_time_bases[index] = stream.time_base();
+            output_count += 1;
+        }
+
+        let subtitle_stream = subtitles
+            .streams()
+            .best(ffmpeg::media::Type::Subtitle)
+            .ok_or_else(|| anyhow::anyhow!("No subtitle stream found"))?;
+        let subtitle_input_index = subtitle_stream.index();
+        let subtitle_time_base = subtitle_stream.time_base();
+        let mut output_stream =
+            output.add_stream(ffmpeg::encoder::find(ffmpeg::codec::Id::None))?;
+        output_stream.set_parameters(subtitle_stream.parameters());
+        unsafe {
+            (*output_stream.parameters().as_mut_ptr()).codec_tag = 0;
+        }
+        let subtitle_output_index = output_count;
+
+        output.set_metadata(media.metadata().to_owned());
+        output.write_header()?;
+
+        // Subtitle files are small, so read them up front and merge them in
+        // between the media packets by timestamp
+        let subtitle_output_time_base = output.stream(subtitle_output_index).unwrap().time_base();
+        let mut subtitle_packets: VecDeque<(f64, ffmpeg::Packet)> = VecDeque::new();
+        for (stream, mut packet) in subtitles.packets() {
+            if stream.index() != subtitle_input_index {
+                continue;
+            }
+            let start =
+                packet.pts().or(packet.dts()).unwrap_or(0) as f64 * f64::from(subtitle_time_base);
+            packet.rescale_ts(subtitle_time_base, subtitle_output_time_base);
+            packet.set_position(-1);
+            packet.set_stream(subtitle_output_index);
+            subtitle_packets.push_back((start, packet));
+        }
+
+        for (stream, mut packet) in media.packets() {
+            let Some(output_index) = stream_mapping[stream.index()] else {
+                continue;
+            };
+            let input_time_base = input_time_bases[stream.index()];
+            let position =
+                packet.dts().or(packet.pts()).unwrap_or(0) as f64 * f64::from(input_time_base);
+
+            while subtitle_packets
+                .front()
+                .is_some_and(|(start, _)| *start <= position)
+            {
+                let (_, subtitle_packet) = subtitle_packets.pop_front().unwrap();
+                subtitle_packet.write_interleaved(&mut output)?;
+            }
+
+            packet.rescale_ts(
+                input_time_base,
+                output.stream(output_index).unwrap().time_base(),
+            );
+            packet.set_position(-1);
+            packet.set_stream(output_index);
+            packet.write_interleaved(&mut output)?;
+        }
+
+        for (_, subtitle_packet) in subtitle_packets {
+            subtitle_packet.write_interleaved(&mut output)?;
+        }
+
+        output.write_trailer()?;
+
+        Ok(())
+    }
+}
+
+/// Running maximum of absolute sample values over equal slices of a stream of
+/// known length. Samples past the expected end land in the last bucket.
+struct PeakBuckets {
+    peaks: Vec<f32>,
+    samples_per_bucket: usize,
+    position: usize,
+}
+
+impl PeakBuckets {
+    fn new(buckets: usize, total_samples: usize) -> Self {
+        Self {
+            peaks: vec![0.0; buckets],
+            samples_per_bucket: total_samples.div_ceil(buckets).max(1),
+            position: 0,
+        }
+    }
+
+    fn push(&mut self, samples: &[f32]) {
+        let last = self.peaks.len() - 1;
+        for sample in samples {
+            let bucket = (self.position / self.samples_per_bucket).min(last);
+            self.peaks[bucket] = self.peaks[bucket].max(sample.abs().min(1.0));
+            self.position += 1;
+        }
+    }
+}
+
+/// Splits decoded audio into f32 planes at the source rate, so a single
+/// channel can be handed to the resampler. Mono input uses its only channel.
+struct ChannelPicker {
+    planar: ffmpeg::software::resampling::context::Context,
+    channel: usize,
+}
+
+impl ChannelPicker {
+    fn pick(
+        &mut self,
+        decoded: &ffmpeg::util::frame::audio::Audio,
+    ) -> Result<ffmpeg::util::frame::audio::Audio> {
+        let mut planar = ffmpeg::util::frame::audio::Audio::empty();
+        self.planar.run(decoded, &mut planar)?;
+
+        let mut mono = ffmpeg::util::frame::audio::Audio::new(
+            ffmpeg::format::Sample::F32(ffmpeg::format::sample::Type::Planar),
+            planar.samples(),
+            ffmpeg::util::channel_layout::ChannelLayout::MONO,
+        );
+        mono.set_rate(planar.rate());
+        let channel = self.channel.min(planar.planes().saturating_sub(1));
+        mono.plane_mut::<f32>(0)
+            .copy_from_slice(planar.plane::<f32>(channel));
+        Ok(mono)
+    }
 }
 
 #[cfg(test)]
@@ -155,20 +766,120 @@ mod tests {
     use std::fs;
     use std::path::Path;
 
+    #[test]
+    fn test_peak_buckets() {
+        let mut peaks = PeakBuckets::new(3, 6);
+        peaks.push(&[0.1, -0.5, 0.2]);
+        peaks.push(&[0.3, 0.0, -1.5, 0.4]);
+
+        // The overflowing sample goes to the last bucket, and peaks are capped at 1
+        assert_eq!(peaks.peaks, vec![0.5, 0.3, 1.0]);
+    }
+
+    #[test]
+    fn test_apply_gain_limits_peaks() {
+        let mut samples = [1000i16, -1000, 20000, -32000];
+        AudioConverter::apply_gain(&mut samples, 10f32.powf(12.0 / 20.0));
+
+        assert!((samples[0] - 3981).abs() <= 1);
+        assert!((samples[1] + 3981).abs() <= 1);
+        // Boosted far past full scale, but limited rather than wrapped or clipped
+        assert!(samples[2] > 29490 && samples[2] < i16::MAX);
+        assert!(samples[3] < -29490 && samples[3] > -i16::MAX);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test]
+    fn test_equivalent_ffmpeg_command_quotes_paths() {
+        let command = AudioConverter::equivalent_ffmpeg_command(
+            Path::new("/media/Bob's talk.mp4"),
+            Path::new("/media/talk.wav"),
+            ResampleQuality::Fast,
+            DownmixMode::Average,
+        );
+        assert_eq!(
+            command,
+            "ffmpeg -i '/media/Bob'\\''s talk.mp4' -map 0:a:0 -vn -ar 16000 -ac 1 -c:a pcm_s16le /media/talk.wav"
+        );
+
+        let command = AudioConverter::equivalent_ffmpeg_command(
+            Path::new("/media/talk.mp4"),
+            Path::new("/media/talk.wav"),
+            ResampleQuality::High,
+            DownmixMode::RightOnly,
+        );
+        assert!(command
+            .contains("-af \"pan=mono|c0=c1,aresample=resampler=soxr:precision=28\" -ar 16000"));
+    }
+
     #[test]
     fn test_convert_to_wav() {
-        let converter = AudioConverter::new();
+        let converter = AudioConverter::new().unwrap();
         let input = Path::new("testing.mp4");
         let output = Path::new("testing.wav");
         if output.exists() {
             fs::remove_file(output).unwrap();
         }
         converter
-            .convert_to_wav(input, output)
+            .convert_to_wav(input, output, &AtomicBool::new(false), |_| {})
             .expect("Conversion failed");
         assert!(output.exists(), "Output WAV file was not created");
         // Optionally, check file size or header
         let metadata = fs::metadata(output).unwrap();
         assert!(metadata.len() > 44, "WAV file too small");
     }
+
+    #[test]
+    fn test_convert_to_wav_format_is_same_at_any_quality() {
+        for (quality, output) in [
+            (ResampleQuality::Fast, "testing-fast.wav"),
+            (ResampleQuality::High, "testing-high.wav"),
+        ] {
+            let converter = AudioConverter::new()
+                .unwrap()
+                .with_resample_quality(quality);
+            let output = Path::new(output);
+            converter
+                .convert_to_wav(
+                    Path::new("testing.mp4"),
+                    output,
+                    &AtomicBool::new(false),
+                    |_| {},
+                )
+                .expect("Conversion failed");
+
+            let spec = hound::WavReader::open(output).unwrap().spec();
+            assert_eq!(spec.sample_rate, 16000);
+            assert_eq!(spec.channels, 1);
+            assert_eq!(spec.bits_per_sample, 16);
+            assert_eq!(spec.sample_format, hound::SampleFormat::Int);
+            fs::remove_file(output).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_media_info() {
+        let converter = AudioConverter::new().unwrap();
+        let info = converter.media_info(Path::new("testing.mp4")).unwrap();
+        assert!(info.duration_secs.unwrap_or_default() > 0.0);
+        assert!(!info.audio_streams.is_empty());
+        assert!(info.audio_streams[0].sample_rate > 0);
+    }
+
+    #[test]
+    fn test_audio_decoders_include_pcm() {
+        let decoders = AudioConverter::new().unwrap().audio_decoders();
+        assert!(decoders.iter().any(|name| name == "pcm_s16le"));
+    }
+
+    #[test]
+    fn test_convert_to_wav_cancelled_removes_output() {
+        let converter = AudioConverter::new().unwrap();
+        let input = Path::new("testing.mp4");
+        let output = Path::new("testing-cancelled.wav");
+
+        let result = converter.convert_to_wav(input, output, &AtomicBool::new(true), |_| {});
+        assert!(result.is_err(), "Cancelled conversion reported success");
+        assert!(!output.exists(), "Partial WAV was left on disk");
+    }
 }
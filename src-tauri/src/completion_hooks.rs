@@ -0,0 +1,184 @@
+use crate::audio_converter::AudioConverter;
+use crate::types::{FileStatus, OutputMode, TranscriptionJob, TranscriptionSettings};
+
+use std::cmp::Reverse;
+use std::path::{Path, PathBuf};
+
+/// Runs the on-complete command and webhook configured for a finished job.
+/// Both run detached so a slow hook can't hold up the queue; failures are
+/// only logged.
+pub fn run(job: &TranscriptionJob) {
+    let status = format!("{:?}", job.status).to_lowercase();
+
+    if let Some(template) = job.settings.on_complete_command.as_deref() {
+        let command = expand_command(template, job, &status);
+        tokio::spawn(async move {
+            let mut shell = if cfg!(target_os = "windows") {
+                let mut shell = tokio::process::Command::new("cmd");
+                shell.arg("/C");
+                shell
+            } else {
+                let mut shell = tokio::process::Command::new("sh");
+                shell.arg("-c");
+                shell
+            };
+            match shell.arg(&command).status().await {
+                Ok(exit) if exit.success() => {}
+                Ok(exit) => log::error!("On-complete command exited with {}: {}", exit, command),
+                Err(e) => log::error!("Failed to run on-complete command '{}': {}", command, e),
+            }
+        });
+    }
+
+    if let Some(url) = job.settings.on_complete_webhook.clone() {
+        let body = serde_json::json!({
+            "input": job.file_path,
+            "output": job.output_path,
+            "status": status,
+            "error": job.error,
+        });
+        tokio::spawn(async move {
+            let sent = reqwest::Client::new()
+                .post(&url)
+                .json(&body)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status());
+            if let Err(e) = sent {
+                log::error!("On-complete webhook to {} failed: {}", url, e);
+            }
+        });
+    }
+}
+
+/// Reveals the batch's output folder when `open_folder_on_complete` is set.
+/// Nothing is opened if no job in the batch completed.
+pub fn run_batch(jobs: &[&TranscriptionJob], settings: &TranscriptionSettings) {
+    if !settings.open_folder_on_complete {
+        return;
+    }
+    let Some(dir) = batch_output_dir(jobs, settings) else {
+        return;
+    };
+    if let Err(e) = reveal_in_file_manager(&dir) {
+        log::error!("Failed to open output folder {:?}: {}", dir, e);
+    }
+}
+
+/// Opens `dir` in the platform's file manager.
+pub fn reveal_in_file_manager(dir: &Path) -> std::io::Result<()> {
+    let program = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    std::process::Command::new(program).arg(dir).spawn()?;
+    Ok(())
+}
+
+/// The folder of the completed jobs' outputs. When they went to several, the
+/// configured `output_dir`, else the one with the most outputs.
+fn batch_output_dir(
+    jobs: &[&TranscriptionJob],
+    settings: &TranscriptionSettings,
+) -> Option<PathBuf> {
+    let dirs: Vec<&Path> = jobs
+        .iter()
+        .filter(|job| matches!(job.status, FileStatus::Completed))
+        .filter_map(|job| job.output_path.as_deref()?.parent())
+        .collect();
+    let first = *dirs.first()?;
+    if dirs.iter().all(|dir| *dir == first) {
+        return Some(first.to_path_buf());
+    }
+    if let (OutputMode::FixedDir, Some(output_dir)) = (&settings.output_mode, &settings.output_dir)
+    {
+        return Some(output_dir.clone());
+    }
+    // Earliest in the batch on a tie
+    dirs.iter()
+        .enumerate()
+        .max_by_key(|(index, dir)| {
+            let count = dirs.iter().filter(|other| other == dir).count();
+            (count, Reverse(*index))
+        })
+        .map(|(_, dir)| dir.to_path_buf())
+}
+
+/// Substitutes `{input}`, `{output}` and `{status}`. Paths are shell-quoted so
+/// file names can't inject commands.
+fn expand_command(template: &str, job: &TranscriptionJob, status: &str) -> String {
+    let output = job.output_path.as_deref().unwrap_or(Path::new(""));
+    template
+        .replace("{input}", &AudioConverter::shell_quote(&job.file_path))
+        .replace("{output}", &AudioConverter::shell_quote(output))
+        .replace("{status}", status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(file_path: &str, status: FileStatus, output_path: Option<&str>) -> TranscriptionJob {
+        TranscriptionJob {
+            id: file_path.to_string(),
+            file_path: PathBuf::from(file_path),
+            settings: TranscriptionSettings::default(),
+            status,
+            progress: 100.0,
+            error: None,
+            output_path: output_path.map(PathBuf::from),
+            temp_wav_path: None,
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: Vec::new(),
+            timestamp_offset_ms: 0,
+            detected_language: None,
+            notice: None,
+        }
+    }
+
+    #[test]
+    #[cfg(not(target_os = "windows"))]
+    fn test_expand_command_quotes_paths() {
+        let job = job("/media/$(rm -rf ~).mp3", FileStatus::Completed, None);
+
+        assert_eq!(
+            expand_command("notify {input} {output} {status}", &job, "completed"),
+            "notify '/media/$(rm -rf ~).mp3' '' completed"
+        );
+    }
+
+    #[test]
+    fn test_batch_output_dir_prefers_most_outputs() {
+        let settings = TranscriptionSettings::default();
+        let a = job("/in/a.mp3", FileStatus::Completed, Some("/out/one/a.srt"));
+        let b = job("/in/b.mp3", FileStatus::Completed, Some("/out/two/b.srt"));
+        let c = job("/in/c.mp3", FileStatus::Completed, Some("/out/two/c.srt"));
+        let failed = job("/in/d.mp3", FileStatus::Error, None);
+
+        assert_eq!(
+            batch_output_dir(&[&a, &b, &c], &settings),
+            Some(PathBuf::from("/out/two"))
+        );
+        assert_eq!(
+            batch_output_dir(&[&a, &b], &settings),
+            Some(PathBuf::from("/out/one"))
+        );
+
+        let fixed = TranscriptionSettings {
+            output_mode: OutputMode::FixedDir,
+            output_dir: Some(PathBuf::from("/out")),
+            ..TranscriptionSettings::default()
+        };
+        assert_eq!(
+            batch_output_dir(&[&a, &b, &c], &fixed),
+            Some(PathBuf::from("/out"))
+        );
+        // A batch that failed entirely opens nothing
+        assert_eq!(batch_output_dir(&[&failed], &settings), None);
+    }
+}
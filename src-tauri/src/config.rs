@@ -1,23 +1,121 @@
 use crate::types::*;
 use anyhow::{anyhow, Result};
 use dirs;
+use serde::{Deserialize, Serialize};
 use serde_json;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Upgrade steps for `settings.json`, where entry `n` turns a version `n` file
+/// into version `n + 1`. Fields that only gained a default are handled by serde
+/// and need no step beyond the version bump.
+const SETTINGS_MIGRATIONS: &[fn(&mut serde_json::Value)] = &[
+    migrate_settings_v0_to_v1,
+    migrate_settings_v1_to_v2,
+    migrate_settings_v2_to_v3,
+    migrate_settings_v3_to_v4,
+];
+
+pub const SETTINGS_VERSION: u32 = SETTINGS_MIGRATIONS.len() as u32;
+
+/// Overrides where settings and models are kept.
+const CONFIG_DIR_ENV: &str = "WHISPER_TAURI_HOME";
+
+/// How long a fetched model catalog is used before it is fetched again.
+const CATALOG_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// The last catalog fetched, with where and when it came from.
+#[derive(Serialize, Deserialize)]
+struct CachedCatalog {
+    url: String,
+    fetched_at_secs: u64,
+    models: Vec<CatalogModel>,
+}
+
+/// Version 1 introduced the `version` field itself.
+fn migrate_settings_v0_to_v1(_settings: &mut serde_json::Value) {}
+
+/// Version 2 added `output_mode`; a set `output_dir` used to always apply.
+fn migrate_settings_v1_to_v2(settings: &mut serde_json::Value) {
+    if let Some(settings) = settings.as_object_mut() {
+        let has_output_dir = settings
+            .get("output_dir")
+            .is_some_and(|output_dir| !output_dir.is_null());
+        let output_mode = if has_output_dir {
+            "FixedDir"
+        } else {
+            "SameAsInput"
+        };
+        settings.insert("output_mode".to_string(), output_mode.into());
+    }
+}
+
+/// Version 3 split `parallel_jobs` into conversion and transcription limits;
+/// the old limit applies to transcription.
+fn migrate_settings_v2_to_v3(settings: &mut serde_json::Value) {
+    if let Some(settings) = settings.as_object_mut() {
+        if let Some(parallel_jobs) = settings.remove("parallel_jobs") {
+            settings.insert("transcription_jobs".to_string(), parallel_jobs);
+        }
+    }
+}
+
+/// Version 4 turned `output_format` into a list of formats to write.
+fn migrate_settings_v3_to_v4(settings: &mut serde_json::Value) {
+    if let Some(settings) = settings.as_object_mut() {
+        if let Some(output_format) = settings.remove("output_format") {
+            settings.insert("output_formats".to_string(), vec![output_format].into());
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct ConfigManager {
     config_dir: PathBuf,
     models_dir: PathBuf,
     settings_file: PathBuf,
+    rtf_file: PathBuf,
+    pinned_file: PathBuf,
+    segments_dir: PathBuf,
+    temp_wavs_file: PathBuf,
+    catalog_file: PathBuf,
+    profiles_dir: PathBuf,
+    /// Held while `temp_wavs_file` is read and rewritten, since parallel jobs
+    /// track their WAVs at the same time.
+    temp_wavs_lock: Arc<Mutex<()>>,
+    /// Held while `rtf_file` is read and rewritten, since parallel jobs
+    /// record their speed as they finish.
+    rtf_lock: Arc<Mutex<()>>,
 }
 
 impl ConfigManager {
     pub fn new() -> Result<Self> {
-        let home_dir = dirs::home_dir().ok_or_else(|| anyhow!("Unable to find home directory"))?;
-        let config_dir = home_dir.join(".whisper-tauri");
+        let config_dir = default_config_dir(
+            std::env::var_os(CONFIG_DIR_ENV).map(PathBuf::from),
+            dirs::home_dir(),
+            dirs::config_dir(),
+            dirs::data_dir(),
+        )
+        .ok_or_else(|| {
+            anyhow!(
+                "Unable to find a home, config or data directory; set {} to choose one",
+                CONFIG_DIR_ENV
+            )
+        })?;
+        Self::with_config_dir(config_dir)
+    }
+
+    pub fn with_config_dir(config_dir: PathBuf) -> Result<Self> {
         let models_dir = config_dir.join("models");
         let settings_file = config_dir.join("settings.json");
+        let rtf_file = config_dir.join("rtf_history.json");
+        let pinned_file = config_dir.join("pinned_models.json");
+        let segments_dir = config_dir.join("segments");
+        let temp_wavs_file = config_dir.join("temp_wavs.json");
+        let catalog_file = config_dir.join("model_catalog.json");
+        let profiles_dir = config_dir.join("model_profiles");
 
         // Create directories if they don't exist
         fs::create_dir_all(&config_dir)?;
@@ -27,6 +125,14 @@ impl ConfigManager {
             config_dir,
             models_dir,
             settings_file,
+            rtf_file,
+            pinned_file,
+            segments_dir,
+            temp_wavs_file,
+            catalog_file,
+            profiles_dir,
+            temp_wavs_lock: Arc::new(Mutex::new(())),
+            rtf_lock: Arc::new(Mutex::new(())),
         })
     }
 
@@ -34,12 +140,33 @@ impl ConfigManager {
         &self.models_dir
     }
 
+    /// Checks that the models folder and the output folder `settings` point
+    /// at can be written to.
+    pub fn check_writable_dirs(&self, settings: &TranscriptionSettings) -> Result<()> {
+        check_writable(&self.models_dir)?;
+        if let Some(output_dir) = &settings.output_dir {
+            check_writable(output_dir)?;
+        }
+        if let OutputMode::MirrorTree { root } = &settings.output_mode {
+            check_writable(root)?;
+        }
+        // Relative folders depend on each input, so only absolute ones are checked
+        for dir in settings.format_output_dirs.values() {
+            if dir.is_absolute() {
+                check_writable(dir)?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_config_dir(&self) -> &PathBuf {
         &self.config_dir
     }
 
     pub fn save_settings(&self, settings: &TranscriptionSettings) -> Result<()> {
-        let json = serde_json::to_string_pretty(settings)?;
+        let mut settings = settings.clone();
+        settings.version = SETTINGS_VERSION;
+        let json = serde_json::to_string_pretty(&settings)?;
         fs::write(&self.settings_file, json)?;
         Ok(())
     }
@@ -47,21 +174,313 @@ impl ConfigManager {
     pub fn load_settings(&self) -> Result<TranscriptionSettings> {
         if self.settings_file.exists() {
             let content = fs::read_to_string(&self.settings_file)?;
-            let settings: TranscriptionSettings = serde_json::from_str(&content)?;
+            let value: serde_json::Value = serde_json::from_str(&content)?;
+            let (settings, upgraded) = Self::settings_from_value(value)?;
+
+            // Persist the upgraded shape so the migration only runs once
+            if upgraded {
+                if let Err(e) = self.save_settings(&settings) {
+                    log::warn!("Failed to rewrite upgraded settings: {}", e);
+                }
+            }
+
             Ok(settings)
         } else {
             // Return default settings
-            Ok(TranscriptionSettings {
-                language: None,
-                model: "base".to_string(),
-                output_format: OutputFormat::Srt,
-                keep_wav: false,
-                output_dir: None,
-                parallel_jobs: 1,
-            })
+            Ok(TranscriptionSettings::default())
         }
     }
 
+    /// Saves `settings` as the defaults for `model`.
+    pub fn save_model_profile(&self, model: &str, settings: &TranscriptionSettings) -> Result<()> {
+        let path = self.model_profile_path(model)?;
+        let mut settings = settings.clone();
+        settings.version = SETTINGS_VERSION;
+        settings.model = model.to_string();
+        fs::create_dir_all(&self.profiles_dir)?;
+        fs::write(path, serde_json::to_string_pretty(&settings)?)?;
+        Ok(())
+    }
+
+    /// The settings saved for `model`, if any. Older profiles are migrated
+    /// like `settings.json`.
+    pub fn load_model_profile(&self, model: &str) -> Result<Option<TranscriptionSettings>> {
+        let path = self.model_profile_path(model)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let value: serde_json::Value = serde_json::from_str(&fs::read_to_string(path)?)?;
+        let (mut settings, _) = Self::settings_from_value(value)?;
+        settings.model = model.to_string();
+        Ok(Some(settings))
+    }
+
+    /// Model names come from the frontend, so anything that could leave the
+    /// profiles folder is refused.
+    fn model_profile_path(&self, model: &str) -> Result<PathBuf> {
+        let file_name = model_file_name(model);
+        let is_safe = file_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['-', '_', '.'].contains(&c))
+            && !file_name.contains("..");
+        if !is_safe {
+            return Err(anyhow!("Invalid model name: {}", model));
+        }
+        let stem = file_name.trim_end_matches(".bin");
+        Ok(self.profiles_dir.join(format!("{}.json", stem)))
+    }
+
+    /// Runs the migrations a settings document needs and deserializes it.
+    /// Returns whether the document was older than the current version.
+    fn settings_from_value(mut value: serde_json::Value) -> Result<(TranscriptionSettings, bool)> {
+        let version = value.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        if version > SETTINGS_VERSION {
+            log::warn!(
+                "Settings file version {} is newer than supported version {}",
+                version,
+                SETTINGS_VERSION
+            );
+        }
+
+        for migration in SETTINGS_MIGRATIONS.iter().skip(version as usize) {
+            migration(&mut value);
+        }
+
+        let mut settings: TranscriptionSettings = serde_json::from_value(value)?;
+        let upgraded = version < SETTINGS_VERSION;
+        if upgraded {
+            settings.version = SETTINGS_VERSION;
+        }
+
+        Ok((settings, upgraded))
+    }
+
+    /// Current settings wrapped with the app version, for moving to another machine.
+    pub fn export_settings(&self) -> Result<String> {
+        let export = serde_json::json!({
+            "app_version": env!("CARGO_PKG_VERSION"),
+            "settings": self.load_settings()?,
+        });
+        Ok(serde_json::to_string_pretty(&export)?)
+    }
+
+    /// Validates and saves settings produced by `export_settings`. A bare
+    /// settings document is accepted too.
+    pub fn import_settings(&self, json: &str) -> Result<TranscriptionSettings> {
+        let mut value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| anyhow!("Invalid settings file: {}", e))?;
+        if let Some(app_version) = value.get("app_version").and_then(|v| v.as_str()) {
+            log::info!("Importing settings exported by version {}", app_version);
+        }
+        if let Some(settings) = value.get_mut("settings") {
+            value = settings.take();
+        }
+
+        let (mut settings, _) = Self::settings_from_value(value)
+            .map_err(|e| anyhow!("Invalid settings file: {}", e))?;
+
+        if !self
+            .get_available_models()
+            .iter()
+            .any(|m| model_file_name(&m.name) == model_file_name(&settings.model))
+        {
+            return Err(anyhow!(
+                "Unknown model in imported settings: {}",
+                settings.model
+            ));
+        }
+
+        // Paths from another machine may not exist here
+        if let Some(output_dir) = &settings.output_dir {
+            if !output_dir.is_dir() {
+                log::warn!(
+                    "Imported output directory {:?} does not exist, using the input folder",
+                    output_dir
+                );
+                settings.output_dir = None;
+                if settings.output_mode == OutputMode::FixedDir {
+                    settings.output_mode = OutputMode::SameAsInput;
+                }
+            }
+        }
+        if let OutputMode::MirrorTree { root } = &settings.output_mode {
+            if !root.is_dir() {
+                log::warn!(
+                    "Imported mirror root {:?} does not exist, using the input folder",
+                    root
+                );
+                settings.output_mode = OutputMode::SameAsInput;
+            }
+        }
+
+        self.save_settings(&settings)?;
+        Ok(settings)
+    }
+
+    /// Measured real-time factors (processing time / audio duration) per model.
+    pub fn load_rtf_history(&self) -> HashMap<String, f64> {
+        fs::read_to_string(&self.rtf_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn record_rtf(&self, model_name: &str, rtf: f64) -> Result<()> {
+        let _guard = self
+            .rtf_lock
+            .lock()
+            .map_err(|_| anyhow!("Speed history is unavailable"))?;
+        let mut history = self.load_rtf_history();
+        // Moving average so a single unusual file doesn't dominate the estimate
+        let value = match history.get(model_name) {
+            Some(previous) => previous * 0.7 + rtf * 0.3,
+            None => rtf,
+        };
+        history.insert(model_name.to_string(), value);
+
+        let json = serde_json::to_string_pretty(&history)?;
+        fs::write(&self.rtf_file, json)?;
+        Ok(())
+    }
+
+    /// Names of the models the user pinned. Cleanup and bulk deletion must
+    /// leave these alone.
+    pub fn load_pinned_models(&self) -> HashSet<String> {
+        fs::read_to_string(&self.pinned_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn set_model_pinned(&self, model_name: &str, pinned: bool) -> Result<()> {
+        let mut pinned_models = self.load_pinned_models();
+        if pinned {
+            pinned_models.insert(model_name.to_string());
+        } else {
+            pinned_models.remove(model_name);
+        }
+
+        let mut names: Vec<&String> = pinned_models.iter().collect();
+        names.sort();
+        let json = serde_json::to_string_pretty(&names)?;
+        fs::write(&self.pinned_file, json)?;
+        Ok(())
+    }
+
+    fn load_catalog(&self) -> Option<CachedCatalog> {
+        fs::read_to_string(&self.catalog_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+    }
+
+    /// Whether the cached catalog came from `url` and is younger than
+    /// `CATALOG_TTL_SECS`.
+    pub fn catalog_is_fresh(&self, url: &str) -> bool {
+        self.load_catalog().is_some_and(|catalog| {
+            catalog.url == url
+                && unix_now().saturating_sub(catalog.fetched_at_secs) < CATALOG_TTL_SECS
+        })
+    }
+
+    /// Caches a catalog fetched from `url` after checking its entries.
+    pub fn save_catalog(&self, url: &str, models: Vec<CatalogModel>) -> Result<()> {
+        validate_catalog(&models)?;
+        let catalog = CachedCatalog {
+            url: url.to_string(),
+            fetched_at_secs: unix_now(),
+            models,
+        };
+        fs::write(&self.catalog_file, serde_json::to_string_pretty(&catalog)?)?;
+        Ok(())
+    }
+
+    /// Drops the cached catalog, e.g. once no catalog URL is set.
+    pub fn clear_catalog(&self) {
+        let _ = fs::remove_file(&self.catalog_file);
+    }
+
+    /// Remembers a temp WAV written beside an input, so it can be found and
+    /// deleted if the run is killed before removing it.
+    pub fn track_temp_wav(&self, path: &Path) -> Result<()> {
+        let _guard = self.lock_temp_wavs()?;
+        let mut paths = self.tracked_temp_wavs();
+        if !paths.iter().any(|tracked| tracked == path) {
+            paths.push(path.to_path_buf());
+            fs::write(&self.temp_wavs_file, serde_json::to_string_pretty(&paths)?)?;
+        }
+        Ok(())
+    }
+
+    /// Forgets a temp WAV once it is deleted, or kept at the user's request.
+    pub fn untrack_temp_wav(&self, path: &Path) -> Result<()> {
+        let _guard = self.lock_temp_wavs()?;
+        let mut paths = self.tracked_temp_wavs();
+        let count = paths.len();
+        paths.retain(|tracked| tracked != path);
+        if paths.len() != count {
+            fs::write(&self.temp_wavs_file, serde_json::to_string_pretty(&paths)?)?;
+        }
+        Ok(())
+    }
+
+    pub fn tracked_temp_wavs(&self) -> Vec<PathBuf> {
+        fs::read_to_string(&self.temp_wavs_file)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Forgets tracked WAVs that no longer exist.
+    pub fn prune_temp_wavs(&self) -> Result<()> {
+        let _guard = self.lock_temp_wavs()?;
+        let mut paths = self.tracked_temp_wavs();
+        paths.retain(|path| path.exists());
+        fs::write(&self.temp_wavs_file, serde_json::to_string_pretty(&paths)?)?;
+        Ok(())
+    }
+
+    fn lock_temp_wavs(&self) -> Result<MutexGuard<'_, ()>> {
+        self.temp_wavs_lock
+            .lock()
+            .map_err(|_| anyhow!("Temp WAV list is unavailable"))
+    }
+
+    /// Keeps a completed job's segments, so they can be shown after a restart.
+    pub fn save_job_segments(&self, job_id: &str, segments: &[Segment]) -> Result<()> {
+        let path = self.job_segments_path(job_id)?;
+        fs::create_dir_all(&self.segments_dir)?;
+        fs::write(path, serde_json::to_string(segments)?)?;
+        Ok(())
+    }
+
+    /// Segments saved by `save_job_segments`, if any.
+    pub fn load_job_segments(&self, job_id: &str) -> Result<Option<Vec<Segment>>> {
+        let path = self.job_segments_path(job_id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+    }
+
+    pub fn remove_job_segments(&self, job_id: &str) {
+        if let Ok(path) = self.job_segments_path(job_id) {
+            let _ = fs::remove_file(path);
+        }
+    }
+
+    /// Job ids come from the frontend, so anything that could leave the
+    /// segments folder is refused.
+    fn job_segments_path(&self, job_id: &str) -> Result<PathBuf> {
+        let is_safe = !job_id.is_empty()
+            && job_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !is_safe {
+            return Err(anyhow!("Invalid job id: {}", job_id));
+        }
+        Ok(self.segments_dir.join(format!("{}.json", job_id)))
+    }
+
     pub fn get_available_models(&self) -> Vec<WhisperModel> {
         let mut models = vec![
             WhisperModel {
@@ -72,6 +491,23 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::High),
+                quality_tier: Some(ModelTier::Low),
+                pinned: false,
+            },
+            WhisperModel {
+                name: "ggml-tiny.en.bin".to_string(),
+                size: "39 MB".to_string(),
+                url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.en.bin"
+                    .to_string(),
+                downloaded: false,
+                file_path: None,
+                progress: None,
+                multilingual: false,
+                speed_tier: Some(ModelTier::High),
+                quality_tier: Some(ModelTier::Low),
+                pinned: false,
             },
             WhisperModel {
                 name: "ggml-base.bin".to_string(),
@@ -81,6 +517,23 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::High),
+                quality_tier: Some(ModelTier::Low),
+                pinned: false,
+            },
+            WhisperModel {
+                name: "ggml-base.en.bin".to_string(),
+                size: "142 MB".to_string(),
+                url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.en.bin"
+                    .to_string(),
+                downloaded: false,
+                file_path: None,
+                progress: None,
+                multilingual: false,
+                speed_tier: Some(ModelTier::High),
+                quality_tier: Some(ModelTier::Low),
+                pinned: false,
             },
             WhisperModel {
                 name: "ggml-small.bin".to_string(),
@@ -90,6 +543,23 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::Medium),
+                quality_tier: Some(ModelTier::Medium),
+                pinned: false,
+            },
+            WhisperModel {
+                name: "ggml-small.en.bin".to_string(),
+                size: "466 MB".to_string(),
+                url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.en.bin"
+                    .to_string(),
+                downloaded: false,
+                file_path: None,
+                progress: None,
+                multilingual: false,
+                speed_tier: Some(ModelTier::Medium),
+                quality_tier: Some(ModelTier::Medium),
+                pinned: false,
             },
             WhisperModel {
                 name: "ggml-medium.bin".to_string(),
@@ -99,6 +569,23 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::Low),
+                quality_tier: Some(ModelTier::Medium),
+                pinned: false,
+            },
+            WhisperModel {
+                name: "ggml-medium.en.bin".to_string(),
+                size: "1.5 GB".to_string(),
+                url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.en.bin"
+                    .to_string(),
+                downloaded: false,
+                file_path: None,
+                progress: None,
+                multilingual: false,
+                speed_tier: Some(ModelTier::Low),
+                quality_tier: Some(ModelTier::Medium),
+                pinned: false,
             },
             WhisperModel {
                 name: "ggml-large-v1".to_string(),
@@ -108,6 +595,10 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::Low),
+                quality_tier: Some(ModelTier::High),
+                pinned: false,
             },
             WhisperModel {
                 name: "ggml-large-v2".to_string(),
@@ -117,6 +608,10 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::Low),
+                quality_tier: Some(ModelTier::High),
+                pinned: false,
             },
             WhisperModel {
                 name: "ggml-large-v3".to_string(),
@@ -126,6 +621,10 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::Low),
+                quality_tier: Some(ModelTier::High),
+                pinned: false,
             },
             WhisperModel {
                 name: "ggml-large-v3-turbo".to_string(),
@@ -135,22 +634,589 @@ impl ConfigManager {
                 downloaded: false,
                 file_path: None,
                 progress: None,
+                multilingual: true,
+                speed_tier: Some(ModelTier::Medium),
+                quality_tier: Some(ModelTier::High),
+                pinned: false,
             },
         ];
 
+        // Catalog models are offered after the built-in ones, which win on a
+        // name clash
+        let catalog_models = self.load_catalog().map(|catalog| catalog.models);
+        for entry in catalog_models.unwrap_or_default() {
+            let file_name = model_file_name(&entry.name);
+            if models
+                .iter()
+                .any(|model| model_file_name(&model.name) == file_name)
+            {
+                continue;
+            }
+            models.push(WhisperModel {
+                multilingual: !file_name.ends_with(".en.bin"),
+                name: entry.name,
+                size: entry.size,
+                url: entry.url,
+                downloaded: false,
+                file_path: None,
+                progress: None,
+                speed_tier: entry.speed_tier,
+                quality_tier: entry.quality_tier,
+                pinned: false,
+            });
+        }
+
         // Check which models are already downloaded
+        let pinned_models = self.load_pinned_models();
         for model in &mut models {
-            let model_path = self.models_dir.join(format!("{}", model.name));
+            let model_path = self.get_model_path(&model.name);
             if model_path.exists() {
                 model.downloaded = true;
                 model.file_path = Some(model_path);
             }
+            model.pinned = pinned_models.contains(&model.name);
         }
 
         models
     }
 
+    /// Where the model is stored, see `model_file_name`. Models saved under
+    /// their bare name by older versions are still found.
     pub fn get_model_path(&self, model_name: &str) -> PathBuf {
-        self.models_dir.join(format!("{}", model_name))
+        let path = self.models_dir.join(model_file_name(model_name));
+        let legacy_path = self.models_dir.join(model_name);
+        if !path.exists() && legacy_path.is_file() {
+            legacy_path
+        } else {
+            path
+        }
+    }
+}
+
+/// File name of a model, `ggml-<name>.bin`, matching the file name in its
+/// download URL. Names may already carry the prefix or extension, so
+/// `base.en-q5_1`, `ggml-large-v3` and `ggml-tiny.en.bin` all resolve.
+pub fn model_file_name(model_name: &str) -> String {
+    let name = model_name.strip_suffix(".bin").unwrap_or(model_name);
+    let name = name.strip_prefix("ggml-").unwrap_or(name);
+    format!("ggml-{}.bin", name)
+}
+
+/// Rejects a catalog with an entry the app couldn't download or store: names
+/// must be plain file names, URLs HTTPS and sizes like `466 MB`.
+pub fn validate_catalog(models: &[CatalogModel]) -> Result<()> {
+    for (index, model) in models.iter().enumerate() {
+        let valid_name = !model.name.is_empty()
+            && model
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+            && !model.name.starts_with('.');
+        if !valid_name {
+            return Err(anyhow!(
+                "Catalog entry {} has an invalid name '{}'",
+                index + 1,
+                model.name
+            ));
+        }
+        if !model.url.starts_with("https://") {
+            return Err(anyhow!(
+                "Catalog entry {} ({}) must use an HTTPS URL",
+                index + 1,
+                model.name
+            ));
+        }
+        if parse_model_size(&model.size).is_none() {
+            return Err(anyhow!(
+                "Catalog entry {} ({}) has an invalid size '{}'",
+                index + 1,
+                model.name,
+                model.size
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Fails unless a file can be created and removed in `dir`, so read-only or
+/// unmounted folders are caught before a job tries to write there.
+pub fn check_writable(dir: &Path) -> Result<()> {
+    if !dir.is_dir() {
+        return Err(anyhow!("Folder does not exist: {}", dir.display()));
+    }
+    let probe = dir.join(format!(".whisper-tauri-write-test-{}", std::process::id()));
+    fs::write(&probe, b"")
+        .and_then(|_| fs::remove_file(&probe))
+        .map_err(|e| anyhow!("Can't write to {}: {}", dir.display(), e))
+}
+
+/// Rough CPU real-time factor per model size, used until a model has history.
+pub fn builtin_rtf(model_name: &str) -> Option<f64> {
+    let rtf = if model_name.contains("turbo") {
+        0.4
+    } else if model_name.contains("tiny") {
+        0.05
+    } else if model_name.contains("base") {
+        0.1
+    } else if model_name.contains("small") {
+        0.3
+    } else if model_name.contains("medium") {
+        0.7
+    } else if model_name.contains("large") {
+        1.2
+    } else {
+        return None;
+    };
+    Some(rtf)
+}
+
+/// `WHISPER_TAURI_HOME` if set, else `~/.whisper-tauri`. Sandboxed and headless
+/// environments may have no home directory, so the OS config and data
+/// directories are tried next.
+fn default_config_dir(
+    env_override: Option<PathBuf>,
+    home_dir: Option<PathBuf>,
+    config_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+) -> Option<PathBuf> {
+    env_override
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .or_else(|| home_dir.map(|dir| dir.join(".whisper-tauri")))
+        .or_else(|| config_dir.map(|dir| dir.join("whisper-tauri")))
+        .or_else(|| data_dir.map(|dir| dir.join("whisper-tauri")))
+}
+
+/// Model size to suggest for a machine with `total_ram_bytes` of installed
+/// memory and `cpus` logical CPUs. A model needs roughly its file size plus
+/// about 1 GB while running, and larger models are slow without many cores:
+/// - under 4 GB of RAM: `base`
+/// - under 8 GB or fewer than 4 CPUs: `small`
+/// - under 16 GB or fewer than 8 CPUs: `medium`
+/// - otherwise: `large-v3-turbo`
+pub fn recommended_model_size(total_ram_bytes: u64, cpus: usize) -> &'static str {
+    const GB: u64 = 1024 * 1024 * 1024;
+    if total_ram_bytes < 4 * GB {
+        "base"
+    } else if total_ram_bytes < 8 * GB || cpus < 4 {
+        "small"
+    } else if total_ram_bytes < 16 * GB || cpus < 8 {
+        "medium"
+    } else {
+        "large-v3-turbo"
+    }
+}
+
+/// The downloaded model to run instead of one that isn't downloaded: the best
+/// quality, then the largest. English-only models are only picked when the
+/// language is set to English.
+pub fn fallback_model<'a>(
+    models: &'a [WhisperModel],
+    language: Option<&str>,
+) -> Option<&'a WhisperModel> {
+    let english = language == Some("en");
+    models
+        .iter()
+        .filter(|model| model.downloaded && (model.multilingual || english))
+        .max_by_key(|model| {
+            let quality = match model.quality_tier {
+                None => 0,
+                Some(ModelTier::Low) => 1,
+                Some(ModelTier::Medium) => 2,
+                Some(ModelTier::High) => 3,
+            };
+            (quality, parse_model_size(&model.size).unwrap_or(0))
+        })
+}
+
+/// The smallest downloaded model larger than `current`, to retry a
+/// transcription with. English-only models are only picked for English.
+pub fn larger_model<'a>(
+    models: &'a [WhisperModel],
+    current: &str,
+    language: Option<&str>,
+) -> Option<&'a WhisperModel> {
+    let english = language == Some("en");
+    let current_size = models
+        .iter()
+        .find(|model| model_file_name(&model.name) == model_file_name(current))
+        .and_then(|model| parse_model_size(&model.size))?;
+    models
+        .iter()
+        .filter(|model| model.downloaded && (model.multilingual || english))
+        .filter_map(|model| Some((parse_model_size(&model.size)?, model)))
+        .filter(|(size, _)| *size > current_size)
+        .min_by_key(|(size, _)| *size)
+        .map(|(_, model)| model)
+}
+
+/// Bytes in a model size label such as `"142 MB"` or `"1.5 GB"`.
+pub fn parse_model_size(size: &str) -> Option<u64> {
+    let (value, unit) = size.trim().split_once(' ')?;
+    let value: f64 = value.parse().ok()?;
+    let multiplier = match unit {
+        "KB" => 1_000.0,
+        "MB" => 1_000_000.0,
+        "GB" => 1_000_000_000.0,
+        _ => return None,
+    };
+    Some((value * multiplier) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config() -> ConfigManager {
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", uuid::Uuid::new_v4()));
+        ConfigManager::with_config_dir(dir).unwrap()
+    }
+
+    #[test]
+    fn test_load_settings_upgrades_old_shape() {
+        let config = temp_config();
+        let old_settings = r#"{
+            "language": "en",
+            "model": "ggml-small.bin",
+            "output_format": "Txt",
+            "keep_wav": true,
+            "output_dir": null,
+            "parallel_jobs": 2
+        }"#;
+        fs::write(&config.settings_file, old_settings).unwrap();
+
+        let settings = config.load_settings().expect("Old settings should upgrade");
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.language.as_deref(), Some("en"));
+        assert_eq!(settings.model, "ggml-small.bin");
+        assert_eq!(settings.output_formats, vec![OutputFormat::Txt]);
+        assert!(settings.keep_wav);
+        assert_eq!(settings.transcription_jobs, 2);
+        assert_eq!(settings.conversion_jobs, 1);
+        assert!(!settings.include_metadata_header);
+        assert_eq!(settings.output_mode, OutputMode::SameAsInput);
+
+        // The upgraded file is written back with the current version
+        let rewritten: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&config.settings_file).unwrap()).unwrap();
+        assert_eq!(rewritten["version"], SETTINGS_VERSION);
+
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_import_settings_validates_model_and_output_dir() {
+        let config = temp_config();
+
+        let exported = r#"{
+            "app_version": "0.1.0",
+            "settings": {
+                "version": 1,
+                "model": "ggml-base.bin",
+                "output_format": "Vtt",
+                "output_dir": "/nonexistent/whisper-tauri/output"
+            }
+        }"#;
+        let settings = config.import_settings(exported).unwrap();
+        assert_eq!(settings.model, "ggml-base.bin");
+        assert_eq!(settings.output_formats, vec![OutputFormat::Vtt]);
+        assert_eq!(settings.output_dir, None);
+        assert_eq!(settings.output_mode, OutputMode::SameAsInput);
+        assert_eq!(config.load_settings().unwrap().model, "ggml-base.bin");
+
+        let unknown_model = r#"{ "settings": { "model": "ggml-missing.bin" } }"#;
+        assert!(config.import_settings(unknown_model).is_err());
+        assert_eq!(config.load_settings().unwrap().model, "ggml-base.bin");
+
+        // Defaults name the model "base", not its file name
+        config
+            .save_settings(&TranscriptionSettings::default())
+            .unwrap();
+        let exported = config.export_settings().unwrap();
+        let settings = config.import_settings(&exported).unwrap();
+        assert_eq!(settings.model, TranscriptionSettings::default().model);
+
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_model_paths_match_download_urls() {
+        let config = temp_config();
+
+        for model in config.get_available_models() {
+            let url_file_name = model.url.rsplit('/').next().unwrap();
+            let path = config.get_model_path(&model.name);
+            assert_eq!(
+                path.file_name().unwrap().to_string_lossy(),
+                url_file_name,
+                "{} would be looked for under the wrong name",
+                model.name
+            );
+        }
+        assert_eq!(model_file_name("tiny.en"), "ggml-tiny.en.bin");
+        assert_eq!(model_file_name("base.en-q5_1"), "ggml-base.en-q5_1.bin");
+        assert_eq!(model_file_name("ggml-large-v3"), "ggml-large-v3.bin");
+
+        // A model saved without the extension is still detected
+        fs::write(config.models_dir.join("ggml-large-v2"), b"lmgg").unwrap();
+        let large_v2 = config
+            .get_available_models()
+            .into_iter()
+            .find(|model| model.name == "ggml-large-v2")
+            .unwrap();
+        assert!(large_v2.downloaded);
+
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_default_config_dir_fallbacks() {
+        let home = Some(PathBuf::from("/home/user"));
+        let config = Some(PathBuf::from("/etc/xdg"));
+        let data = Some(PathBuf::from("/var/lib"));
+
+        assert_eq!(
+            default_config_dir(
+                Some(PathBuf::from("/srv/whisper")),
+                home.clone(),
+                config.clone(),
+                data.clone()
+            ),
+            Some(PathBuf::from("/srv/whisper"))
+        );
+        // An empty override is treated as unset
+        assert_eq!(
+            default_config_dir(Some(PathBuf::new()), home, config.clone(), data.clone()),
+            Some(PathBuf::from("/home/user/.whisper-tauri"))
+        );
+        assert_eq!(
+            default_config_dir(None, None, config, data.clone()),
+            Some(PathBuf::from("/etc/xdg/whisper-tauri"))
+        );
+        assert_eq!(
+            default_config_dir(None, None, None, data),
+            Some(PathBuf::from("/var/lib/whisper-tauri"))
+        );
+        assert_eq!(
+            default_config_dir(Some(PathBuf::from("/srv/whisper")), None, None, None),
+            Some(PathBuf::from("/srv/whisper"))
+        );
+        assert_eq!(default_config_dir(None, None, None, None), None);
+    }
+
+    #[test]
+    fn test_parallel_rtf_records_are_all_kept() {
+        let config = temp_config();
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let config = config.clone();
+                std::thread::spawn(move || config.record_rtf(&format!("model-{}", i), 0.5).unwrap())
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(config.load_rtf_history().len(), 8);
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_check_writable_requires_existing_folder() {
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", uuid::Uuid::new_v4()));
+        assert!(check_writable(&dir).is_err());
+
+        fs::create_dir_all(&dir).unwrap();
+        check_writable(&dir).unwrap();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_catalog_models_merge_with_builtin() {
+        let config = temp_config();
+        let entry = |name: &str, url: &str, size: &str| CatalogModel {
+            name: name.to_string(),
+            size: size.to_string(),
+            url: url.to_string(),
+            speed_tier: None,
+            quality_tier: None,
+        };
+        let url = "https://example.com/catalog.json";
+
+        let invalid = vec![entry("../evil", "https://example.com/evil.bin", "1 GB")];
+        assert!(config.save_catalog(url, invalid).is_err());
+        let insecure = vec![entry("ggml-new", "http://example.com/new.bin", "1 GB")];
+        assert!(config.save_catalog(url, insecure).is_err());
+
+        let builtin_count = config.get_available_models().len();
+        let catalog = vec![
+            entry("ggml-base.bin", "https://example.com/base.bin", "142 MB"),
+            entry("ggml-large-v4.en", "https://example.com/v4.bin", "3.1 GB"),
+        ];
+        config.save_catalog(url, catalog).unwrap();
+        assert!(config.catalog_is_fresh(url));
+        assert!(!config.catalog_is_fresh("https://example.com/other.json"));
+
+        let models = config.get_available_models();
+        assert_eq!(models.len(), builtin_count + 1);
+        let added = models.last().unwrap();
+        assert_eq!(added.name, "ggml-large-v4.en");
+        assert!(!added.multilingual);
+    }
+
+    #[test]
+    fn test_fallback_model_prefers_best_downloaded() {
+        let config = temp_config();
+        let mut models = config.get_available_models();
+        let pick = |models: &[WhisperModel], language: Option<&str>| {
+            fallback_model(models, language).map(|model| model.name.clone())
+        };
+        assert_eq!(pick(&models, None), None);
+
+        for model in &mut models {
+            model.downloaded = ["ggml-base.bin", "ggml-medium.en.bin"].contains(&&*model.name);
+        }
+        // English-only models only stand in for English
+        assert_eq!(pick(&models, None).as_deref(), Some("ggml-base.bin"));
+        assert_eq!(pick(&models, Some("de")).as_deref(), Some("ggml-base.bin"));
+        assert_eq!(
+            pick(&models, Some("en")).as_deref(),
+            Some("ggml-medium.en.bin")
+        );
+
+        for model in &mut models {
+            model.downloaded |= ["ggml-small.bin", "ggml-medium.bin"].contains(&&*model.name);
+        }
+        assert_eq!(pick(&models, None).as_deref(), Some("ggml-medium.bin"));
+
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_larger_model_is_next_downloaded_size() {
+        let config = temp_config();
+        let mut models = config.get_available_models();
+        for model in &mut models {
+            model.downloaded =
+                ["ggml-tiny.bin", "ggml-small.en.bin", "ggml-medium.bin"].contains(&&*model.name);
+        }
+        let pick = |current: &str, language: Option<&str>| {
+            larger_model(&models, current, language).map(|model| model.name.clone())
+        };
+
+        assert_eq!(pick("tiny", None).as_deref(), Some("ggml-medium.bin"));
+        assert_eq!(
+            pick("tiny", Some("en")).as_deref(),
+            Some("ggml-small.en.bin")
+        );
+        assert_eq!(pick("ggml-medium.bin", None), None);
+
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_model_profiles_are_kept_per_model() {
+        let config = temp_config();
+        assert!(config
+            .load_model_profile("ggml-large-v3")
+            .unwrap()
+            .is_none());
+
+        let settings = TranscriptionSettings {
+            audio_ctx: Some(768),
+            ..TranscriptionSettings::default()
+        };
+        config
+            .save_model_profile("ggml-tiny.bin", &settings)
+            .unwrap();
+
+        let profile = config.load_model_profile("tiny").unwrap().unwrap();
+        assert_eq!(profile.model, "tiny");
+        assert_eq!(profile.audio_ctx, Some(768));
+        assert!(config
+            .load_model_profile("ggml-base.bin")
+            .unwrap()
+            .is_none());
+        assert!(config.load_model_profile("../settings").is_err());
+
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_pinned_models_persist() {
+        let config = temp_config();
+        config.set_model_pinned("ggml-base.bin", true).unwrap();
+        config.set_model_pinned("ggml-tiny.bin", true).unwrap();
+        config.set_model_pinned("ggml-tiny.bin", false).unwrap();
+
+        let pinned: Vec<String> = config
+            .get_available_models()
+            .into_iter()
+            .filter(|model| model.pinned)
+            .map(|model| model.name)
+            .collect();
+        assert_eq!(pinned, vec!["ggml-base.bin"]);
+    }
+
+    #[test]
+    fn test_prune_forgets_removed_temp_wavs() {
+        let config = temp_config();
+        let kept = config.config_dir.join("kept.16k.wav");
+        let removed = config.config_dir.join("removed.16k.wav");
+        fs::write(&kept, b"RIFF").unwrap();
+
+        config.track_temp_wav(&kept).unwrap();
+        config.track_temp_wav(&removed).unwrap();
+        config.track_temp_wav(&kept).unwrap();
+        assert_eq!(config.tracked_temp_wavs(), vec![kept.clone(), removed]);
+
+        config.prune_temp_wavs().unwrap();
+        assert_eq!(config.tracked_temp_wavs(), vec![kept]);
+
+        config.untrack_temp_wav(&kept).unwrap();
+        assert!(config.tracked_temp_wavs().is_empty());
+    }
+
+    #[test]
+    fn test_job_segments_round_trip() {
+        let config = temp_config();
+        let segments = vec![Segment {
+            start: 0,
+            end: 150,
+            text: " Hello".to_string(),
+            tokens: Vec::new(),
+            language: None,
+        }];
+
+        config.save_job_segments("job-1", &segments).unwrap();
+        assert_eq!(config.load_job_segments("job-1").unwrap(), Some(segments));
+        config.remove_job_segments("job-1");
+        assert_eq!(config.load_job_segments("job-1").unwrap(), None);
+        assert!(config.save_job_segments("../escape", &[]).is_err());
+    }
+
+    #[test]
+    fn test_recommended_model_size() {
+        const GB: u64 = 1024 * 1024 * 1024;
+        assert_eq!(recommended_model_size(2 * GB, 8), "base");
+        assert_eq!(recommended_model_size(32 * GB, 2), "small");
+        assert_eq!(recommended_model_size(12 * GB, 8), "medium");
+        assert_eq!(recommended_model_size(32 * GB, 16), "large-v3-turbo");
+    }
+
+    #[test]
+    fn test_load_settings_without_file_returns_defaults() {
+        let config = temp_config();
+
+        let settings = config.load_settings().unwrap();
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.transcription_jobs, 1);
+
+        fs::remove_dir_all(config.get_config_dir()).unwrap();
     }
 }
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::io;
+
+/// Raw OS error codes for a full disk.
+#[cfg(unix)]
+const DISK_FULL_CODES: &[i32] = &[28]; // ENOSPC
+#[cfg(windows)]
+const DISK_FULL_CODES: &[i32] = &[39, 112]; // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+#[cfg(not(any(unix, windows)))]
+const DISK_FULL_CODES: &[i32] = &[];
+
+/// The error commands return, serialized as `{"kind": "...", "message": "..."}`
+/// so the frontend can offer a recovery action for the kind of failure.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "message")]
+pub enum AppError {
+    ModelNotDownloaded(String),
+    InsufficientDiskSpace(String),
+    NetworkError(String),
+    ConversionFailed(String),
+    TranscriptionFailed(String),
+    Other(String),
+}
+
+impl AppError {
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::ModelNotDownloaded(message)
+            | AppError::InsufficientDiskSpace(message)
+            | AppError::NetworkError(message)
+            | AppError::ConversionFailed(message)
+            | AppError::TranscriptionFailed(message)
+            | AppError::Other(message) => message,
+        }
+    }
+
+    /// For errors outside anyhow, such as plugin or join errors.
+    pub fn other(error: impl fmt::Display) -> Self {
+        AppError::Other(error.to_string())
+    }
+
+    /// Like `From<anyhow::Error>`, but unrecognised failures are reported as
+    /// a failed conversion. For commands that decode media.
+    pub fn conversion(error: anyhow::Error) -> Self {
+        Self::classify(error, AppError::ConversionFailed)
+    }
+
+    /// Like `From<anyhow::Error>`, but unrecognised failures are reported as
+    /// a failed transcription.
+    pub fn transcription(error: anyhow::Error) -> Self {
+        Self::classify(error, AppError::TranscriptionFailed)
+    }
+
+    /// Finds the kind from the error chain: an `AppError` raised further down,
+    /// a request failure or a full disk. The message is the outermost one.
+    fn classify(error: anyhow::Error, fallback: fn(String) -> AppError) -> Self {
+        let message = error.to_string();
+        for cause in error.chain() {
+            if let Some(app_error) = cause.downcast_ref::<AppError>() {
+                return app_error.clone();
+            }
+            if cause.downcast_ref::<reqwest::Error>().is_some() {
+                return AppError::NetworkError(message);
+            }
+            if cause.downcast_ref::<io::Error>().is_some_and(is_disk_full) {
+                return AppError::InsufficientDiskSpace(message);
+            }
+        }
+        fallback(message)
+    }
+}
+
+fn is_disk_full(error: &io::Error) -> bool {
+    error
+        .raw_os_error()
+        .is_some_and(|code| DISK_FULL_CODES.contains(&code))
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl From<anyhow::Error> for AppError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::classify(error, AppError::Other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_classifies_error_chain() {
+        let missing: anyhow::Error = AppError::ModelNotDownloaded("base".to_string()).into();
+        assert_eq!(
+            AppError::from(missing.context("Failed to start")),
+            AppError::ModelNotDownloaded("base".to_string())
+        );
+
+        let full = Err::<(), _>(io::Error::from_raw_os_error(DISK_FULL_CODES[0]))
+            .context("Failed to write model")
+            .unwrap_err();
+        assert_eq!(
+            AppError::from(full),
+            AppError::InsufficientDiskSpace("Failed to write model".to_string())
+        );
+
+        let other = anyhow::anyhow!("Bad input");
+        assert_eq!(
+            AppError::conversion(other),
+            AppError::ConversionFailed("Bad input".to_string())
+        );
+    }
+
+    #[test]
+    fn test_serializes_as_tagged_object() {
+        assert_eq!(
+            serde_json::to_value(AppError::NetworkError("offline".to_string())).unwrap(),
+            serde_json::json!({ "kind": "NetworkError", "message": "offline" })
+        );
+    }
+}
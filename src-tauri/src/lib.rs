@@ -1,40 +1,91 @@
 mod audio_converter;
+mod completion_hooks;
 mod config;
+mod error;
+mod live_transcribe;
+mod log_buffer;
 mod manager;
 mod model_downloader;
+mod progress_smoother;
+mod progress_throttle;
+mod streaming_output;
+mod subtitles;
 mod transcriber;
 mod types;
 
+use error::AppError;
+use log_buffer::LogBuffer;
 use manager::TranscriptionManager;
 use types::*;
 
 use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_opener::OpenerExt;
 use tokio::sync::{mpsc, Mutex};
 
 type TranscriptionState = Arc<Mutex<TranscriptionManager>>;
 
 #[tauri::command]
-async fn greet(name: String) -> Result<String, String> {
+async fn greet(name: String) -> Result<String, AppError> {
     Ok(name)
 }
 
 #[tauri::command]
 async fn get_available_models(
     state: State<'_, TranscriptionState>,
-) -> Result<Vec<WhisperModel>, String> {
+) -> Result<Vec<WhisperModel>, AppError> {
     let manager = state.lock().await;
     Ok(manager.get_available_models())
 }
 
+/// A model suited to this machine, for new users choosing one.
+#[tauri::command]
+async fn recommend_model(state: State<'_, TranscriptionState>) -> Result<Option<String>, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.recommend_model())
+}
+
+/// Models including those from the configured catalog, fetched when the
+/// cached copy is stale.
+#[tauri::command]
+async fn fetch_model_catalog(
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<WhisperModel>, AppError> {
+    let manager = state.lock().await.clone();
+    manager.fetch_model_catalog().await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn refresh_models(
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<WhisperModel>, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.refresh_models())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn set_model_pinned(
+    model_name: String,
+    pinned: bool,
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<WhisperModel>, AppError> {
+    let manager = state.lock().await;
+    manager
+        .set_model_pinned(&model_name, pinned)
+        .map_err(AppError::from)?;
+    Ok(manager.refresh_models())
+}
+
 #[tauri::command(rename_all = "snake_case")]
 async fn download_model(
     model_name: String,
     state: State<'_, TranscriptionState>,
     app: AppHandle,
-) -> Result<(), String> {
-    let manager = state.lock().await;
+) -> Result<(), AppError> {
+    // Release the state lock for the duration of the download
+    let manager = state.lock().await.clone();
     let model_name_clone = model_name.clone();
     let app_clone = app.clone();
 
@@ -51,138 +102,666 @@ async fn download_model(
         .await
         .map_err(|e| {
             log::error!("Failed to download model: {}", e);
-            e.to_string()
+            AppError::from(e)
         })?;
 
     let _ = app.emit("model-download-complete", &model_name);
     Ok(())
 }
 
+/// Seconds a download of the model would take at the current speed.
+#[tauri::command(rename_all = "snake_case")]
+async fn estimate_download_time(
+    model_name: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<u64, AppError> {
+    // Release the state lock while the speed is measured
+    let manager = state.lock().await.clone();
+    manager
+        .estimate_download_time(&model_name)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn download_missing_models(
+    state: State<'_, TranscriptionState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let manager = state.lock().await.clone();
+    let progress_app = app.clone();
+    let total_app = app.clone();
+
+    let completed = manager
+        .download_missing_models(
+            move |model, progress| {
+                let _ = progress_app.emit(
+                    "model-download-progress",
+                    &serde_json::json!({
+                        "model": model,
+                        "progress": progress
+                    }),
+                );
+            },
+            move |progress| {
+                let _ = total_app.emit(
+                    "model-download-all-progress",
+                    &serde_json::json!({ "progress": progress }),
+                );
+            },
+        )
+        .await;
+
+    match completed {
+        Ok(completed) => {
+            for model_name in completed {
+                let _ = app.emit("model-download-complete", &model_name);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("Failed to download models: {}", e);
+            Err(e.into())
+        }
+    }
+}
+
+#[tauri::command]
+async fn check_model_sources(
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<ModelSourceStatus>, AppError> {
+    let manager = state.lock().await.clone();
+    manager.check_model_sources().await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn get_download_status(
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<DownloadStatus>, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.get_download_status())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn get_supported_languages(
+    model_name: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<Language>, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.get_supported_languages(&model_name))
+}
+
 #[tauri::command]
 async fn load_settings(
     state: State<'_, TranscriptionState>,
-) -> Result<TranscriptionSettings, String> {
+) -> Result<TranscriptionSettings, AppError> {
     let manager = state.lock().await;
-    manager.load_settings().map_err(|e| e.to_string())
+    manager.load_settings().map_err(AppError::from)
 }
 
 #[tauri::command]
 async fn save_settings(
     settings: TranscriptionSettings,
     state: State<'_, TranscriptionState>,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
+    let manager = state.lock().await;
+    manager.save_settings(&settings).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn save_model_profile(
+    model: String,
+    settings: TranscriptionSettings,
+    state: State<'_, TranscriptionState>,
+) -> Result<(), AppError> {
+    let manager = state.lock().await;
+    manager
+        .save_model_profile(&model, &settings)
+        .map_err(AppError::from)
+}
+
+/// The settings saved for `model`; none when no profile was saved, so the
+/// form keeps its edits.
+#[tauri::command]
+async fn load_model_profile(
+    model: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<Option<TranscriptionSettings>, AppError> {
     let manager = state.lock().await;
-    manager.save_settings(&settings).map_err(|e| e.to_string())
+    manager.load_model_profile(&model).map_err(AppError::from)
+}
+
+/// `settings` with the model a batch would actually run, see
+/// `TranscriptionManager::effective_settings`.
+#[tauri::command]
+async fn get_effective_settings(
+    settings: TranscriptionSettings,
+    state: State<'_, TranscriptionState>,
+) -> Result<TranscriptionSettings, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.effective_settings(settings).0)
+}
+
+/// Checks a folder picked in the UI before it is saved as an output folder.
+#[tauri::command]
+async fn check_directory_writable(path: String) -> Result<(), AppError> {
+    config::check_writable(&PathBuf::from(path)).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn export_settings(state: State<'_, TranscriptionState>) -> Result<String, AppError> {
+    let manager = state.lock().await;
+    manager.export_settings().map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn import_settings(
+    json: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<TranscriptionSettings, AppError> {
+    let manager = state.lock().await;
+    manager.import_settings(&json).map_err(AppError::from)
 }
 
 #[tauri::command]
 async fn add_files(
     file_paths: Vec<String>,
     state: State<'_, TranscriptionState>,
-) -> Result<Vec<FileEntry>, String> {
+) -> Result<AddFilesResult, AppError> {
     let paths: Vec<PathBuf> = file_paths.into_iter().map(PathBuf::from).collect();
-    let files = {
+    let result = {
         let manager = state.lock().await;
         manager.add_files(paths).await
     };
-    Ok(files)
+    Ok(result)
+}
+
+/// Downloads a direct media link and adds it to the list. The manager isn't
+/// locked during the download.
+#[tauri::command]
+async fn add_url(url: String, state: State<'_, TranscriptionState>) -> Result<FileEntry, AppError> {
+    let manager = state.lock().await.clone();
+    manager.add_url(&url).await.map_err(AppError::from)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn add_directory(
+    dir_path: String,
+    recursive: bool,
+    state: State<'_, TranscriptionState>,
+    app: AppHandle,
+) -> Result<DirectoryScanResult, AppError> {
+    let manager = state.lock().await;
+    manager
+        .add_directory(&PathBuf::from(dir_path), recursive, |scanned| {
+            let _ = app.emit(
+                "add-directory-progress",
+                &serde_json::json!({ "scanned": scanned }),
+            );
+        })
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn estimate_batch(
+    files: Vec<FileEntry>,
+    settings: TranscriptionSettings,
+    state: State<'_, TranscriptionState>,
+) -> Result<BatchEstimate, AppError> {
+    let manager = state.lock().await.clone();
+
+    // Probing opens every input with ffmpeg, so keep it off the async workers
+    tokio::task::spawn_blocking(move || manager.estimate_batch(&files, &settings))
+        .await
+        .map_err(AppError::other)?
+        .map_err(AppError::from)
 }
 
+/// Returns warnings about a model substituted for one that isn't downloaded
+/// and about what the output format leaves out; they don't stop the batch.
 #[tauri::command]
 async fn start_transcription(
     files: Vec<FileEntry>,
     settings: TranscriptionSettings,
     state: State<'_, TranscriptionState>,
-    app: AppHandle,
-) -> Result<(), String> {
-    // Create a separate instance for this operation
-    let mut transcription_manager = TranscriptionManager::new().map_err(|e| e.to_string())?;
+) -> Result<Vec<String>, AppError> {
+    // Share the job map with the managed instance so the job commands can see
+    // and cancel what is running
+    let transcription_manager = state.lock().await.clone();
 
-    // Set up progress reporting
-    let (tx, mut rx) = mpsc::unbounded_channel();
-    transcription_manager.set_progress_sender(tx);
+    let (settings, substitution) = transcription_manager.effective_settings(settings);
+    let mut warnings: Vec<String> = substitution.into_iter().collect();
+    warnings.extend(TranscriptionManager::output_format_warnings(&settings));
 
-    // Spawn task to listen for progress updates
-    let app_clone = app.clone();
+    // Start transcription in background
     tokio::spawn(async move {
-        while let Some(update) = rx.recv().await {
-            let _ = app_clone.emit("transcription-progress", &update);
+        if let Err(e) = transcription_manager
+            .start_transcription(files, settings)
+            .await
+        {
+            log::error!("Failed to start transcription: {}", e);
         }
     });
 
-    // Start transcription in background
+    Ok(warnings)
+}
+
+/// Transcribes one file with the saved settings, returning its entry so the
+/// caller can follow the job. `output_format` applies to this run only and is
+/// not persisted.
+#[tauri::command(rename_all = "snake_case")]
+async fn transcribe_single(
+    file_path: String,
+    output_format: Option<OutputFormat>,
+    state: State<'_, TranscriptionState>,
+) -> Result<FileEntry, AppError> {
+    let transcription_manager = state.lock().await.clone();
+    let (file, settings) = transcription_manager
+        .single_file_job(PathBuf::from(file_path), output_format)
+        .map_err(AppError::from)?;
+
+    let files = vec![file.clone()];
     tokio::spawn(async move {
-        let _ = transcription_manager
+        if let Err(e) = transcription_manager
             .start_transcription(files, settings)
-            .await;
+            .await
+        {
+            log::error!("Failed to start transcription: {}", e);
+        }
     });
 
-    Ok(())
+    Ok(file)
 }
 
 #[tauri::command]
 async fn get_job_status(
     job_id: String,
     state: State<'_, TranscriptionState>,
-) -> Result<Option<TranscriptionJob>, String> {
+) -> Result<Option<TranscriptionJob>, AppError> {
     let manager = state.lock().await;
     Ok(manager.get_job_status(&job_id).await)
 }
 
+#[tauri::command]
+async fn get_queue_status(state: State<'_, TranscriptionState>) -> Result<QueueStatus, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.queue_status().await)
+}
+
 #[tauri::command]
 async fn get_all_jobs(
     state: State<'_, TranscriptionState>,
-) -> Result<Vec<TranscriptionJob>, String> {
+) -> Result<Vec<TranscriptionJob>, AppError> {
     let manager = state.lock().await;
     Ok(manager.get_all_jobs().await)
 }
 
 #[tauri::command]
-async fn cancel_job(job_id: String, state: State<'_, TranscriptionState>) -> Result<(), String> {
+async fn copy_transcript_to_clipboard(
+    job_id: String,
+    state: State<'_, TranscriptionState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let transcript = {
+        let manager = state.lock().await;
+        manager
+            .read_transcript(&job_id)
+            .await
+            .map_err(AppError::from)?
+    };
+    app.clipboard()
+        .write_text(transcript)
+        .map_err(AppError::other)
+}
+
+#[tauri::command]
+async fn copy_path_to_clipboard(
+    job_id: String,
+    state: State<'_, TranscriptionState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let output_path = {
+        let manager = state.lock().await;
+        manager
+            .completed_output_path(&job_id)
+            .await
+            .map_err(AppError::from)?
+    };
+    app.clipboard()
+        .write_text(output_path.to_string_lossy())
+        .map_err(AppError::other)
+}
+
+/// Opens a completed job's output with the application the system associates
+/// with it. Only job outputs can be opened, never a path from the webview.
+#[tauri::command]
+async fn open_in_default_app(
+    job_id: String,
+    state: State<'_, TranscriptionState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let output_path = {
+        let manager = state.lock().await;
+        manager
+            .completed_output_path(&job_id)
+            .await
+            .map_err(AppError::from)?
+    };
+    app.opener()
+        .open_path(output_path.to_string_lossy(), None::<&str>)
+        .map_err(AppError::other)
+}
+
+/// A completed job's output file, for editing in the UI.
+#[tauri::command]
+async fn get_job_output(
+    job_id: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<String, AppError> {
+    let manager = state.lock().await;
+    manager
+        .read_transcript(&job_id)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn save_edited_transcript(
+    job_id: String,
+    content: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<(), AppError> {
+    let manager = state.lock().await;
+    manager
+        .save_edited_transcript(&job_id, &content)
+        .await
+        .map_err(AppError::from)
+}
+
+/// Cues of a completed SRT or VTT job, for previewing its subtitles.
+#[tauri::command]
+async fn get_job_cues(
+    job_id: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<ParsedSubtitles, AppError> {
     let manager = state.lock().await;
-    manager.cancel_job(&job_id).await.map_err(|e| e.to_string())
+    manager.get_job_cues(&job_id).await.map_err(AppError::from)
 }
 
+#[tauri::command(rename_all = "snake_case")]
+async fn reexport_job(
+    job_id: String,
+    format: OutputFormat,
+    state: State<'_, TranscriptionState>,
+) -> Result<PathBuf, AppError> {
+    let manager = state.lock().await;
+    manager
+        .reexport_job(&job_id, format)
+        .await
+        .map_err(AppError::from)
+}
+
+/// New segments for part of a completed job, transcribed with `model`. The
+/// output isn't changed until they are applied.
+#[tauri::command(rename_all = "snake_case")]
+async fn refine_range(
+    job_id: String,
+    start_secs: f64,
+    end_secs: f64,
+    model: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<Segment>, AppError> {
+    let manager = state.lock().await.clone();
+    manager
+        .refine_range(&job_id, start_secs, end_secs, &model)
+        .await
+        .map_err(AppError::transcription)
+}
+
+/// Transcribes one file with two models, for comparing them side by side.
+#[tauri::command(rename_all = "snake_case")]
+async fn compare_models(
+    file_path: String,
+    models: Vec<String>,
+    settings: TranscriptionSettings,
+    state: State<'_, TranscriptionState>,
+) -> Result<ModelComparison, AppError> {
+    let manager = state.lock().await.clone();
+    manager
+        .compare_models(&PathBuf::from(file_path), &models, &settings)
+        .await
+        .map_err(AppError::transcription)
+}
+
+/// Segments of a completed job, times in the source; empty when no speech
+/// was found.
 #[tauri::command]
-async fn clear_completed_jobs(state: State<'_, TranscriptionState>) -> Result<(), String> {
+async fn get_job_segments(
+    job_id: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<Segment>, AppError> {
+    let manager = state.lock().await;
+    manager
+        .get_job_segments(&job_id)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn apply_refined_range(
+    job_id: String,
+    start_secs: f64,
+    end_secs: f64,
+    segments: Vec<Segment>,
+    state: State<'_, TranscriptionState>,
+) -> Result<TranscriptionJob, AppError> {
+    let manager = state.lock().await;
+    manager
+        .apply_refined_range(&job_id, start_secs, end_secs, segments)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn save_transcript(
+    job_id: String,
+    target_path: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<(), AppError> {
+    let manager = state.lock().await;
+    manager
+        .save_transcript(&job_id, &PathBuf::from(target_path))
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn cancel_job(job_id: String, state: State<'_, TranscriptionState>) -> Result<(), AppError> {
+    let manager = state.lock().await;
+    manager.cancel_job(&job_id).await.map_err(AppError::from)
+}
+
+/// Stops every unfinished job, keeping completed ones in the list.
+#[tauri::command]
+async fn cancel_all_jobs(
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<TranscriptionJob>, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.cancel_all_jobs().await)
+}
+
+/// Queues every failed job again, leaving completed and running ones alone.
+#[tauri::command]
+async fn retry_all_failed(
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<TranscriptionJob>, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.retry_all_failed().await)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn change_job_model(
+    job_id: String,
+    model_name: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<TranscriptionJob, AppError> {
+    let manager = state.lock().await;
+    manager
+        .change_job_model(&job_id, &model_name)
+        .await
+        .map_err(AppError::from)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn move_job(
+    job_id: String,
+    new_index: usize,
+    state: State<'_, TranscriptionState>,
+) -> Result<(), AppError> {
+    let manager = state.lock().await;
+    manager.move_job(&job_id, new_index).map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn clear_completed_jobs(state: State<'_, TranscriptionState>) -> Result<(), AppError> {
     let manager = state.lock().await;
     manager.clear_completed_jobs().await;
     Ok(())
 }
 
 #[tauri::command]
-async fn open_output_folder(path: String) -> Result<(), String> {
+async fn start_live_transcription(
+    state: State<'_, TranscriptionState>,
+    app: AppHandle,
+) -> Result<(), AppError> {
+    let manager = state.lock().await.clone();
+
+    // Loading the model and opening the device block, so keep them off the async workers
+    tokio::task::spawn_blocking(move || {
+        manager.start_live_transcription(move |update| {
+            let _ = app.emit("live-transcript", &update);
+        })
+    })
+    .await
+    .map_err(AppError::other)?
+    .map_err(|e| {
+        log::error!("Failed to start live transcription: {}", e);
+        AppError::transcription(e)
+    })
+}
+
+#[tauri::command]
+async fn get_live_transcription_status(
+    state: State<'_, TranscriptionState>,
+) -> Result<bool, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.is_live_transcription_running())
+}
+
+#[tauri::command]
+async fn stop_live_transcription(state: State<'_, TranscriptionState>) -> Result<(), AppError> {
+    let manager = state.lock().await.clone();
+    tokio::task::spawn_blocking(move || manager.stop_live_transcription())
+        .await
+        .map_err(AppError::other)?
+        .map_err(AppError::from)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn list_audio_streams(
+    file_path: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<AudioStreamInfo>, AppError> {
+    let manager = state.lock().await.clone();
+    tokio::task::spawn_blocking(move || manager.list_audio_streams(&PathBuf::from(file_path)))
+        .await
+        .map_err(AppError::other)?
+        .map_err(AppError::conversion)
+}
+
+/// Container and audio stream details for the file info panel.
+#[tauri::command(rename_all = "snake_case")]
+async fn get_media_info(
+    file_path: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<MediaInfo, AppError> {
+    let manager = state.lock().await.clone();
+    tokio::task::spawn_blocking(move || manager.get_media_info(&PathBuf::from(file_path)))
+        .await
+        .map_err(AppError::other)?
+        .map_err(AppError::conversion)
+}
+
+#[tauri::command]
+async fn get_capabilities(state: State<'_, TranscriptionState>) -> Result<Capabilities, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.get_capabilities())
+}
+
+/// Peak amplitudes of the file's audio in `buckets` slices, for drawing a waveform.
+#[tauri::command(rename_all = "snake_case")]
+async fn get_waveform(
+    file_path: String,
+    buckets: usize,
+    state: State<'_, TranscriptionState>,
+) -> Result<Vec<f32>, AppError> {
+    let manager = state.lock().await.clone();
+    tokio::task::spawn_blocking(move || manager.get_waveform(&PathBuf::from(file_path), buckets))
+        .await
+        .map_err(AppError::other)?
+        .map_err(AppError::conversion)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+async fn get_ffmpeg_command(
+    input_path: String,
+    state: State<'_, TranscriptionState>,
+) -> Result<String, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.equivalent_ffmpeg_command(&PathBuf::from(input_path)))
+}
+
+/// Temp WAVs left behind by killed or failed runs.
+#[tauri::command]
+async fn get_temp_files_info(
+    state: State<'_, TranscriptionState>,
+) -> Result<TempFilesInfo, AppError> {
+    let manager = state.lock().await;
+    Ok(manager.get_temp_files_info().await)
+}
+
+/// Deletes the files `get_temp_files_info` reports, returning what was freed.
+#[tauri::command]
+async fn clear_temp_files(state: State<'_, TranscriptionState>) -> Result<TempFilesInfo, AppError> {
+    let manager = state.lock().await;
+    manager.clear_temp_files().await.map_err(AppError::from)
+}
+
+#[tauri::command]
+async fn get_recent_logs(
+    limit: usize,
+    logs: State<'_, LogBuffer>,
+) -> Result<Vec<LogEntry>, AppError> {
+    Ok(logs.recent(limit))
+}
+
+#[tauri::command]
+async fn open_output_folder(path: String) -> Result<(), AppError> {
     let path_buf = PathBuf::from(path);
     if let Some(parent) = path_buf.parent() {
-        #[cfg(target_os = "macos")]
-        {
-            std::process::Command::new("open")
-                .arg(parent)
-                .spawn()
-                .map_err(|e| e.to_string())?;
-        }
-        #[cfg(target_os = "windows")]
-        {
-            std::process::Command::new("explorer")
-                .arg(parent)
-                .spawn()
-                .map_err(|e| e.to_string())?;
-        }
-        #[cfg(target_os = "linux")]
-        {
-            std::process::Command::new("xdg-open")
-                .arg(parent)
-                .spawn()
-                .map_err(|e| e.to_string())?;
-        }
+        completion_hooks::reveal_in_file_manager(parent).map_err(AppError::other)?;
     }
     Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let log_buffer = LogBuffer::new();
+
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_http::init())
@@ -191,27 +770,112 @@ pub fn run() {
                 .target(tauri_plugin_log::Target::new(
                     tauri_plugin_log::TargetKind::Stdout,
                 ))
+                .target(tauri_plugin_log::Target::new(
+                    tauri_plugin_log::TargetKind::Dispatch(
+                        tauri_plugin_log::fern::Dispatch::new().chain(
+                            tauri_plugin_log::fern::Output::call({
+                                let log_buffer = log_buffer.clone();
+                                move |record| log_buffer.push(record)
+                            }),
+                        ),
+                    ),
+                ))
                 .build(),
         )
+        .manage(log_buffer)
         .setup(|app| {
-            let manager = TranscriptionManager::new()
+            // Surface a broken ffmpeg install at startup; audio commands report
+            // the same error when used
+            if let Err(e) = audio_converter::AudioConverter::init() {
+                log::error!("{}", e);
+            }
+
+            let mut manager = TranscriptionManager::new()
                 .map_err(|e| format!("Failed to initialize transcription manager: {}", e))?;
 
+            // Set up progress reporting
+            let (tx, mut rx) = mpsc::unbounded_channel();
+            manager.set_progress_sender(tx);
+
+            // Spawn task to listen for progress updates. Every job change comes
+            // through here, so the queue summary is refreshed with it
+            let app_handle = app.handle().clone();
+            let status_manager = manager.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut last_status = None;
+                while let Some(update) = rx.recv().await {
+                    let _ = app_handle.emit("transcription-progress", &update);
+
+                    let status = status_manager.queue_status().await;
+                    if last_status.as_ref() != Some(&status) {
+                        let _ = app_handle.emit("queue-status", &status);
+                        last_status = Some(status);
+                    }
+                }
+            });
+
             app.manage(Arc::new(Mutex::new(manager)));
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             greet,
             get_available_models,
+            refresh_models,
+            fetch_model_catalog,
+            recommend_model,
             download_model,
+            set_model_pinned,
+            estimate_download_time,
+            download_missing_models,
+            get_download_status,
+            check_model_sources,
+            get_supported_languages,
             load_settings,
             save_settings,
+            save_model_profile,
+            load_model_profile,
+            get_effective_settings,
+            check_directory_writable,
+            export_settings,
+            import_settings,
             add_files,
+            add_url,
+            add_directory,
+            estimate_batch,
             start_transcription,
+            transcribe_single,
             get_job_status,
             get_all_jobs,
+            get_queue_status,
+            copy_transcript_to_clipboard,
+            copy_path_to_clipboard,
+            open_in_default_app,
+            reexport_job,
+            refine_range,
+            apply_refined_range,
+            compare_models,
+            get_job_cues,
+            get_job_segments,
+            get_job_output,
+            save_edited_transcript,
+            save_transcript,
             cancel_job,
+            cancel_all_jobs,
+            retry_all_failed,
+            change_job_model,
+            move_job,
             clear_completed_jobs,
+            start_live_transcription,
+            get_live_transcription_status,
+            stop_live_transcription,
+            list_audio_streams,
+            get_media_info,
+            get_waveform,
+            get_capabilities,
+            get_ffmpeg_command,
+            get_temp_files_info,
+            clear_temp_files,
+            get_recent_logs,
             open_output_folder
         ])
         .run(tauri::generate_context!())
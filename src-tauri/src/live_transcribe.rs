@@ -0,0 +1,271 @@
+use crate::types::LiveTranscriptUpdate;
+
+use anyhow::{anyhow, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+const WHISPER_SAMPLE_RATE: u32 = 16000;
+/// Audio gathered before each whisper pass over the current window.
+const STEP_SECS: usize = 3;
+/// Length at which the window is finalized and a new one started.
+const WINDOW_SECS: usize = 15;
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Characters of the previous window's text used as the next window's prompt.
+const PROMPT_TAIL_CHARS: usize = 200;
+
+type UpdateCallback = Arc<dyn Fn(LiveTranscriptUpdate) + Send + Sync>;
+
+/// Captures the default input device and transcribes it in rolling windows on a
+/// worker thread. Each pass re-transcribes the growing window and reports its
+/// text as partial; once the window is full its text is reported as final.
+pub struct LiveTranscriber {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LiveTranscriber {
+    pub fn start(
+        model_path: PathBuf,
+        language: Option<String>,
+        carry_context: bool,
+        on_update: impl Fn(LiveTranscriptUpdate) + Send + Sync + 'static,
+    ) -> Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let (ready_tx, ready_rx) = mpsc::channel();
+
+        // cpal streams are not Send, so the stream lives on the worker thread
+        // and reports whether the device could be opened
+        let handle = std::thread::spawn({
+            let stop = stop.clone();
+            let on_update: UpdateCallback = Arc::new(on_update);
+            move || {
+                if let Err(e) = Self::run(
+                    &model_path,
+                    language,
+                    carry_context,
+                    &stop,
+                    on_update,
+                    &ready_tx,
+                ) {
+                    log::error!("Live transcription stopped: {}", e);
+                    let _ = ready_tx.send(Err(e.to_string()));
+                }
+            }
+        });
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self {
+                stop,
+                handle: Some(handle),
+            }),
+            Ok(Err(e)) => {
+                let _ = handle.join();
+                Err(anyhow!(e))
+            }
+            Err(_) => {
+                let _ = handle.join();
+                Err(anyhow!("Live transcription failed to start"))
+            }
+        }
+    }
+
+    /// Stops capturing and waits for the last window to be transcribed.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+
+    fn run(
+        model_path: &Path,
+        language: Option<String>,
+        carry_context: bool,
+        stop: &AtomicBool,
+        on_update: UpdateCallback,
+        ready_tx: &mpsc::Sender<std::result::Result<(), String>>,
+    ) -> Result<()> {
+        let ctx = WhisperContext::new_with_params(
+            &model_path.to_string_lossy(),
+            WhisperContextParameters::default(),
+        )?;
+        let mut state = ctx.create_state()?;
+
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| anyhow!("No microphone or other input device was found"))?;
+        let config = device
+            .default_input_config()
+            .map_err(|e| anyhow!("Input device is unavailable: {}", e))?;
+        let device_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+
+        let captured = Arc::new(Mutex::new(Vec::<f32>::new()));
+        let stream = match config.sample_format() {
+            SampleFormat::F32 => Self::build_stream::<f32>(&device, &config.into(), &captured),
+            SampleFormat::I16 => Self::build_stream::<i16>(&device, &config.into(), &captured),
+            SampleFormat::U16 => Self::build_stream::<u16>(&device, &config.into(), &captured),
+            format => return Err(anyhow!("Unsupported input sample format: {}", format)),
+        }?;
+        stream.play()?;
+        let _ = ready_tx.send(Ok(()));
+        log::info!(
+            "Live transcription started ({} Hz, {} channel(s))",
+            device_rate,
+            channels
+        );
+
+        let mut window: Vec<f32> = Vec::new();
+        let mut prompt = String::new();
+        let step_len = STEP_SECS * device_rate as usize * channels;
+        loop {
+            let stopping = stop.load(Ordering::SeqCst);
+            if !stopping {
+                std::thread::sleep(POLL_INTERVAL);
+            }
+
+            let pending = captured.lock().map(|c| c.len()).unwrap_or(0);
+            if pending < step_len && !stopping {
+                continue;
+            }
+
+            let mono: Vec<f32> = captured
+                .lock()
+                .map(|mut c| c.drain(..).collect())
+                .unwrap_or_default();
+            window.extend(Self::resample(&mono, device_rate, channels));
+
+            let is_final = stopping || window.len() >= WINDOW_SECS * WHISPER_SAMPLE_RATE as usize;
+            if !window.is_empty() {
+                let text =
+                    Self::transcribe_window(&mut state, &window, &language, &prompt, &on_update)?;
+                if is_final && carry_context {
+                    prompt = Self::prompt_tail(&text);
+                }
+                on_update(LiveTranscriptUpdate { text, is_final });
+            }
+            if is_final {
+                window.clear();
+            }
+            if stopping {
+                break;
+            }
+        }
+
+        drop(stream);
+        log::info!("Live transcription stopped");
+        Ok(())
+    }
+
+    /// The end of `text`, starting at a word boundary.
+    fn prompt_tail(text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        if chars.len() <= PROMPT_TAIL_CHARS {
+            return text.to_string();
+        }
+        let tail: String = chars[chars.len() - PROMPT_TAIL_CHARS..].iter().collect();
+        match tail.split_once(' ') {
+            Some((_, rest)) => rest.to_string(),
+            None => tail,
+        }
+    }
+
+    fn build_stream<T>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        captured: &Arc<Mutex<Vec<f32>>>,
+    ) -> Result<cpal::Stream>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
+        let captured = captured.clone();
+        let stream = device.build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                if let Ok(mut captured) = captured.lock() {
+                    captured.extend(data.iter().map(|s| s.to_sample::<f32>()));
+                }
+            },
+            |e| log::error!("Input stream error: {}", e),
+            None,
+        )?;
+        Ok(stream)
+    }
+
+    /// Downmixes interleaved samples and resamples them to 16 kHz with linear
+    /// interpolation, which is enough for speech.
+    fn resample(samples: &[f32], rate: u32, channels: usize) -> Vec<f32> {
+        let mono: Vec<f32> = samples
+            .chunks(channels.max(1))
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect();
+        if rate == WHISPER_SAMPLE_RATE || mono.is_empty() {
+            return mono;
+        }
+
+        let ratio = rate as f64 / WHISPER_SAMPLE_RATE as f64;
+        let out_len = (mono.len() as f64 / ratio) as usize;
+        (0..out_len)
+            .map(|i| {
+                let pos = i as f64 * ratio;
+                let index = pos as usize;
+                let frac = (pos - index as f64) as f32;
+                let current = mono[index];
+                let next = mono.get(index + 1).copied().unwrap_or(current);
+                current + (next - current) * frac
+            })
+            .collect()
+    }
+
+    /// Transcribes the window, reporting each segment as partial text as soon as
+    /// whisper produces it, and returns the window's full text.
+    fn transcribe_window(
+        state: &mut whisper_rs::WhisperState,
+        window: &[f32],
+        language: &Option<String>,
+        prompt: &str,
+        on_update: &UpdateCallback,
+    ) -> Result<String> {
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        if let Some(language) = language {
+            params.set_language(Some(language));
+        }
+        // Each pass re-reads the whole window, so only the previous window's
+        // final text is carried forward, as the prompt
+        params.set_no_context(true);
+        if !prompt.is_empty() {
+            params.set_initial_prompt(prompt);
+        }
+        params.set_print_progress(false);
+
+        let text = Arc::new(Mutex::new(String::new()));
+        params.set_segment_callback_safe({
+            let text = text.clone();
+            let on_update = on_update.clone();
+            move |segment: whisper_rs::SegmentCallbackData| {
+                if let Ok(mut text) = text.lock() {
+                    text.push_str(&segment.text);
+                    on_update(LiveTranscriptUpdate {
+                        text: text.trim().to_string(),
+                        is_final: false,
+                    });
+                }
+            }
+        });
+
+        state.full(params, window)?;
+
+        let text = text
+            .lock()
+            .map(|t| t.trim().to_string())
+            .unwrap_or_default();
+        Ok(text)
+    }
+}
@@ -0,0 +1,73 @@
+use crate::types::LogEntry;
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Lines kept in memory; older lines are dropped first.
+const MAX_LOG_LINES: usize = 1000;
+
+/// Bounded in-memory copy of recent log output, so the Debug view can show
+/// logs without access to stdout.
+#[derive(Clone, Default)]
+pub struct LogBuffer {
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+}
+
+impl LogBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, record: &log::Record) {
+        let entry = LogEntry {
+            level: record.level().to_string(),
+            message: record.args().to_string(),
+        };
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= MAX_LOG_LINES {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+
+    /// The last `limit` lines, oldest first.
+    pub fn recent(&self, limit: usize) -> Vec<LogEntry> {
+        let Ok(entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let skip = entries.len().saturating_sub(limit);
+        entries.iter().skip(skip).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_line(buffer: &LogBuffer, message: &str) {
+        buffer.push(
+            &log::Record::builder()
+                .level(log::Level::Info)
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_buffer_is_bounded_and_keeps_newest_lines() {
+        let buffer = LogBuffer::new();
+        for i in 0..MAX_LOG_LINES + 5 {
+            push_line(&buffer, &format!("line {}", i));
+        }
+
+        let all = buffer.recent(usize::MAX);
+        assert_eq!(all.len(), MAX_LOG_LINES);
+        assert_eq!(all[0].message, "line 5");
+
+        let last = buffer.recent(2);
+        assert_eq!(last.len(), 2);
+        assert_eq!(last[0].message, format!("line {}", MAX_LOG_LINES + 3));
+        assert_eq!(last[1].level, "INFO");
+    }
+}
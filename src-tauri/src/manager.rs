@@ -1,41 +1,184 @@
 use crate::audio_converter::AudioConverter;
-use crate::config::ConfigManager;
+use crate::completion_hooks;
+use crate::config::{
+    builtin_rtf, fallback_model, larger_model, parse_model_size, recommended_model_size,
+    ConfigManager,
+};
+use crate::error::AppError;
+use crate::live_transcribe::LiveTranscriber;
 use crate::model_downloader::ModelDownloader;
-use crate::transcriber::WhisperTranscriber;
+use crate::progress_smoother::{self, ProgressSmoother};
+use crate::progress_throttle::ProgressThrottle;
+use crate::streaming_output::StreamingWriter;
+use crate::subtitles;
+use crate::transcriber::{FormatOptions, Transcription, WhisperTranscriber};
 use crate::types::*;
 
 use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use futures_util::StreamExt;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
 use tokio::sync::mpsc;
-use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::task::AbortHandle;
 use uuid::Uuid;
 
+/// Upper bound on files collected by a single `add_directory` call.
+const MAX_DIRECTORY_FILES: usize = 5000;
+/// Number of directory entries between scan progress reports.
+const DIRECTORY_PROGRESS_INTERVAL: usize = 200;
+/// Downloads run at once by `download_missing_models`.
+const MAX_CONCURRENT_DOWNLOADS: usize = 2;
+/// Shown on completed jobs whose output is empty because whisper found nothing.
+const NO_SPEECH_NOTICE: &str = "No speech detected";
+/// Shown on completed jobs whose raw JSON sidecar has no word timings.
+const NO_WORD_TIMESTAMPS_NOTICE: &str =
+    "Word timestamps unavailable, the raw JSON only has segment times";
+/// How often a finished processing pass checks on batch jobs another pass
+/// is still running.
+const BATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+/// How often a cooldown between jobs checks whether jobs are still queued.
+const COOLDOWN_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+/// How long `check_model_sources` waits for each model URL.
+const SOURCE_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// A running job. The abort handle stops it at its next await; the flag is
+/// checked by the blocking conversion, which has none.
+struct ActiveTask {
+    abort: AbortHandle,
+    cancel: Arc<AtomicBool>,
+}
+
+/// How many jobs may convert and how many may transcribe at once.
+#[derive(Clone, Copy)]
+struct StageLimits {
+    conversion: usize,
+    transcription: usize,
+}
+
+impl StageLimits {
+    fn new(settings: &TranscriptionSettings) -> Self {
+        Self {
+            conversion: settings.conversion_jobs.max(1),
+            transcription: settings.transcription_jobs.max(1),
+        }
+    }
+
+    fn max(self, other: Self) -> Self {
+        Self {
+            conversion: self.conversion.max(other.conversion),
+            transcription: self.transcription.max(other.transcription),
+        }
+    }
+}
+
+/// The slots of one processing pass. A job holds a conversion slot while it
+/// converts, then a transcription slot while it transcribes.
+#[derive(Clone)]
+struct StageSlots {
+    conversion: Arc<Semaphore>,
+    transcription: Arc<Semaphore>,
+}
+
+#[derive(Clone)]
 pub struct TranscriptionManager {
     config: ConfigManager,
-    downloader: ModelDownloader,
     progress_sender: Option<mpsc::UnboundedSender<ProgressUpdate>>,
     jobs: Arc<Mutex<HashMap<String, TranscriptionJob>>>,
-    active_tasks: Arc<Mutex<HashMap<String, JoinHandle<()>>>>,
+    /// Job ids in queue order, which pending jobs are started in.
+    job_order: Arc<StdMutex<Vec<String>>>,
+    active_tasks: Arc<Mutex<HashMap<String, ActiveTask>>>,
+    /// Progress of the downloads in flight, keyed by model name. Uses a std mutex
+    /// because it is updated from the synchronous progress callback.
+    downloads: Arc<StdMutex<HashMap<String, i32>>>,
+    live: Arc<StdMutex<Option<LiveTranscriber>>>,
+    /// Canonical paths of every file added and not yet cleared, so the same
+    /// file isn't queued twice. A file whose jobs were all cancelled or failed
+    /// may be added again.
+    queued_paths: Arc<StdMutex<HashSet<PathBuf>>>,
+    /// Set while a `process_jobs` pass runs, so there is only ever one.
+    processing: Arc<AtomicBool>,
+    /// Files in the app's temp folder from before this are leftovers.
+    started_at: std::time::SystemTime,
 }
 
 impl TranscriptionManager {
     pub fn new() -> Result<Self> {
-        Ok(Self {
-            config: ConfigManager::new()?,
-            downloader: ModelDownloader::new(),
+        Ok(Self::with_config(ConfigManager::new()?))
+    }
+
+    /// A manager over `config`, so tests can keep to a temp folder.
+    pub fn with_config(config: ConfigManager) -> Self {
+        Self {
+            config,
             progress_sender: None,
             jobs: Arc::new(Mutex::new(HashMap::new())),
+            job_order: Arc::new(StdMutex::new(Vec::new())),
             active_tasks: Arc::new(Mutex::new(HashMap::new())),
-        })
+            downloads: Arc::new(StdMutex::new(HashMap::new())),
+            live: Arc::new(StdMutex::new(None)),
+            queued_paths: Arc::new(StdMutex::new(HashSet::new())),
+            processing: Arc::new(AtomicBool::new(false)),
+            started_at: std::time::SystemTime::now(),
+        }
     }
 
     pub fn get_available_models(&self) -> Vec<WhisperModel> {
         self.config.get_available_models()
     }
 
+    /// A model suited to this machine's RAM and CPU count, see
+    /// `recommended_model_size`. A downloaded model of that size is preferred
+    /// over the multilingual default.
+    pub fn recommend_model(&self) -> Option<String> {
+        let mut system = sysinfo::System::new();
+        system.refresh_memory();
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let size = recommended_model_size(system.total_memory(), cpus);
+
+        let suffix = format!("-{}", size);
+        let candidates: Vec<WhisperModel> = self
+            .config
+            .get_available_models()
+            .into_iter()
+            .filter(|model| {
+                let name = model.name.trim_end_matches(".bin");
+                name.ends_with(&suffix) || name.ends_with(&format!("{}.en", suffix))
+            })
+            .collect();
+        candidates
+            .iter()
+            .find(|model| model.downloaded)
+            .or_else(|| candidates.first())
+            .map(|model| model.name.clone())
+    }
+
+    /// `settings` as a batch runs them. When the model isn't downloaded and
+    /// `fallback_to_downloaded_model` is set, the best downloaded model stands
+    /// in; the returned warning says so.
+    pub fn effective_settings(
+        &self,
+        mut settings: TranscriptionSettings,
+    ) -> (TranscriptionSettings, Option<String>) {
+        if !settings.fallback_to_downloaded_model
+            || self.config.get_model_path(&settings.model).exists()
+        {
+            return (settings, None);
+        }
+        let models = self.config.get_available_models();
+        let Some(fallback) = fallback_model(&models, settings.language.as_deref()) else {
+            return (settings, None);
+        };
+        let warning = format!(
+            "{} isn't downloaded, using {} instead",
+            settings.model, fallback.name
+        );
+        settings.model = fallback.name.clone();
+        (settings, Some(warning))
+    }
+
     pub async fn download_model(
         &self,
         model_name: &str,
@@ -48,15 +191,247 @@ impl TranscriptionManager {
             .ok_or_else(|| anyhow!("Model not found: {}", model_name))?;
 
         let output_path = self.config.get_model_path(model_name);
+        let downloader = self.downloader()?;
 
-        self.downloader
-            .download_model(model, &output_path, progress_callback)
-            .await?;
+        {
+            let mut downloads = self
+                .downloads
+                .lock()
+                .map_err(|_| anyhow!("Download state is unavailable"))?;
+            if downloads.contains_key(model_name) {
+                return Err(anyhow!("Model is already downloading: {}", model_name));
+            }
+            downloads.insert(model_name.to_string(), 0);
+        }
 
-        Ok(())
+        let result = downloader
+            .download_model(model, &output_path, {
+                let downloads = self.downloads.clone();
+                let model_name = model_name.to_string();
+                move |progress| {
+                    if let Ok(mut downloads) = downloads.lock() {
+                        downloads.insert(model_name.clone(), progress);
+                    }
+                    progress_callback(progress);
+                }
+            })
+            .await;
+
+        if let Ok(mut downloads) = self.downloads.lock() {
+            downloads.remove(model_name);
+        }
+
+        result
+    }
+
+    /// Seconds downloading `model_name` would take at the speed measured now,
+    /// from the size in its catalog entry.
+    pub async fn estimate_download_time(&self, model_name: &str) -> Result<u64> {
+        let model = self
+            .config
+            .get_available_models()
+            .into_iter()
+            .find(|m| m.name == model_name)
+            .ok_or_else(|| anyhow!("Model not found: {}", model_name))?;
+        let size = parse_model_size(&model.size)
+            .ok_or_else(|| anyhow!("Unknown size for model: {}", model_name))?;
+
+        let bytes_per_sec = self.downloader()?.measure_throughput(&model.url).await?;
+        Ok((size as f64 / bytes_per_sec).ceil() as u64)
+    }
+
+    /// Sends a HEAD request to every model URL at once, so network problems
+    /// show up before a download is attempted.
+    pub async fn check_model_sources(&self) -> Result<Vec<ModelSourceStatus>> {
+        let downloader = &self.downloader()?;
+        let checks = self
+            .config
+            .get_available_models()
+            .into_iter()
+            .map(|model| async move {
+                let check = tokio::time::timeout(
+                    SOURCE_CHECK_TIMEOUT,
+                    downloader.check_model_availability(&model.url),
+                )
+                .await;
+                let (size_bytes, error) = match check {
+                    Ok(Ok(size)) => (Some(size).filter(|size| *size > 0), None),
+                    Ok(Err(e)) => (None, Some(e.to_string())),
+                    Err(_) => (None, Some("Timed out".to_string())),
+                };
+                ModelSourceStatus {
+                    model: model.name,
+                    reachable: error.is_none(),
+                    size_bytes,
+                    error,
+                }
+            });
+        Ok(futures_util::future::join_all(checks).await)
+    }
+
+    /// A downloader using the current proxy and timeout settings.
+    fn downloader(&self) -> Result<ModelDownloader> {
+        ModelDownloader::from_settings(&self.config.load_settings()?)
+    }
+
+    /// Downloads every model that is neither on disk nor already downloading,
+    /// `MAX_CONCURRENT_DOWNLOADS` at a time. `on_total_progress` receives the
+    /// combined progress weighted by model size. Returns the models that finished.
+    pub async fn download_missing_models(
+        &self,
+        on_model_progress: impl Fn(&str, i32) + Send + Sync + 'static,
+        on_total_progress: impl Fn(i32) + Send + Sync + 'static,
+    ) -> Result<Vec<String>> {
+        let missing: Vec<WhisperModel> = self
+            .refresh_models()
+            .into_iter()
+            .filter(|model| !model.downloaded && model.progress.is_none())
+            .collect();
+        let weights: HashMap<String, u64> = missing
+            .iter()
+            .map(|model| {
+                (
+                    model.name.clone(),
+                    parse_model_size(&model.size).unwrap_or(1),
+                )
+            })
+            .collect();
+        let total_weight: u64 = weights.values().sum();
+
+        let progress = Arc::new(StdMutex::new(HashMap::<String, i32>::new()));
+        let on_model_progress = Arc::new(on_model_progress);
+        let on_total_progress = Arc::new(on_total_progress);
+
+        let results: Vec<(String, Result<()>)> = futures_util::stream::iter(missing)
+            .map(|model| {
+                let progress = progress.clone();
+                let weights = weights.clone();
+                let on_model_progress = on_model_progress.clone();
+                let on_total_progress = on_total_progress.clone();
+                async move {
+                    let name = model.name.clone();
+                    let result = self
+                        .download_model(&model.name, move |model_progress| {
+                            on_model_progress(&model.name, model_progress);
+                            if let Ok(mut progress) = progress.lock() {
+                                progress.insert(model.name.clone(), model_progress);
+                                let done: u64 = progress
+                                    .iter()
+                                    .map(|(name, p)| weights[name] * (*p).clamp(0, 100) as u64)
+                                    .sum();
+                                on_total_progress((done / total_weight.max(1)) as i32);
+                            }
+                        })
+                        .await;
+                    (name, result)
+                }
+            })
+            .buffer_unordered(MAX_CONCURRENT_DOWNLOADS)
+            .collect()
+            .await;
+
+        let mut completed = Vec::new();
+        let mut failed = Vec::new();
+        for (name, result) in results {
+            match result {
+                Ok(()) => completed.push(name),
+                Err(e) => {
+                    log::error!("Failed to download model {}: {}", name, e);
+                    failed.push(name);
+                }
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(completed)
+        } else {
+            Err(anyhow!("Failed to download: {}", failed.join(", ")))
+        }
+    }
+
+    /// The models, including those from the catalog at `model_catalog_url`.
+    /// The catalog is fetched again once the cached copy is a day old; when it
+    /// can't be, the cached copy or the built-in list is used.
+    pub async fn fetch_model_catalog(&self) -> Result<Vec<WhisperModel>> {
+        let settings = self.config.load_settings()?;
+        let url = settings
+            .model_catalog_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty());
+        match url {
+            None => self.config.clear_catalog(),
+            Some(url) if !self.config.catalog_is_fresh(url) => {
+                let saved: Result<()> = async {
+                    let models = ModelDownloader::from_settings(&settings)?
+                        .fetch_catalog(url)
+                        .await?;
+                    self.config.save_catalog(url, models)
+                }
+                .await;
+                if let Err(e) = saved {
+                    log::warn!(
+                        "Using the cached model list, fetching {} failed: {}",
+                        url,
+                        e
+                    );
+                }
+            }
+            Some(_) => {}
+        }
+        Ok(self.refresh_models())
+    }
+
+    /// Re-reads the models directory. A model still being downloaded has a
+    /// partial file on disk, so it is reported with its progress instead.
+    pub fn refresh_models(&self) -> Vec<WhisperModel> {
+        let mut models = self.config.get_available_models();
+        if let Ok(downloads) = self.downloads.lock() {
+            for model in &mut models {
+                if let Some(progress) = downloads.get(&model.name) {
+                    model.downloaded = false;
+                    model.file_path = None;
+                    model.progress = Some(*progress as f32);
+                }
+            }
+        }
+        models
+    }
+
+    pub fn set_model_pinned(&self, model_name: &str, pinned: bool) -> Result<()> {
+        if !self
+            .config
+            .get_available_models()
+            .iter()
+            .any(|model| model.name == model_name)
+        {
+            return Err(anyhow!("Model not found: {}", model_name));
+        }
+        self.config.set_model_pinned(model_name, pinned)
+    }
+
+    pub fn get_download_status(&self) -> Vec<DownloadStatus> {
+        self.downloads
+            .lock()
+            .map(|downloads| {
+                downloads
+                    .iter()
+                    .map(|(model, progress)| DownloadStatus {
+                        model: model.clone(),
+                        progress: *progress,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn get_supported_languages(&self, model_name: &str) -> Vec<Language> {
+        WhisperTranscriber::supported_languages(model_name)
     }
 
+    /// Refuses settings whose models or output folder can't be written to.
     pub fn save_settings(&self, settings: &TranscriptionSettings) -> Result<()> {
+        self.config.check_writable_dirs(settings)?;
         self.config.save_settings(settings)
     }
 
@@ -64,133 +439,740 @@ impl TranscriptionManager {
         self.config.load_settings()
     }
 
-    pub async fn add_files(&self, file_paths: Vec<PathBuf>) -> Vec<FileEntry> {
-        let mut files = Vec::new();
+    /// Saves `settings` as the defaults for `model`.
+    pub fn save_model_profile(&self, model: &str, settings: &TranscriptionSettings) -> Result<()> {
+        self.config.check_writable_dirs(settings)?;
+        self.config.save_model_profile(model, settings)
+    }
+
+    /// The settings saved for `model`, if any.
+    pub fn load_model_profile(&self, model: &str) -> Result<Option<TranscriptionSettings>> {
+        self.config.load_model_profile(model)
+    }
+
+    pub fn export_settings(&self) -> Result<String> {
+        self.config.export_settings()
+    }
+
+    pub fn import_settings(&self, json: &str) -> Result<TranscriptionSettings> {
+        self.config.import_settings(json)
+    }
+
+    pub async fn add_files(&self, file_paths: Vec<PathBuf>) -> AddFilesResult {
+        let mut result = AddFilesResult {
+            files: Vec::new(),
+            duplicates: 0,
+        };
 
         for path in file_paths {
-            let converter = AudioConverter::new();
-            if !converter.is_audio_file(&path) {
+            if !AudioConverter::is_audio_file(&path) {
                 continue;
             }
 
-            let id = Uuid::new_v4().to_string();
-            let name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("Unknown")
-                .to_string();
+            if !self.claim_path(&path).await {
+                result.duplicates += 1;
+                continue;
+            }
+            result.files.push(Self::create_file_entry(path));
+        }
 
-            let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        result
+    }
 
-            let file_entry = FileEntry {
-                id,
-                path,
-                name,
-                size,
-                status: FileStatus::Pending,
-                progress: 0.0,
-                error: None,
-                output_path: None,
+    /// Downloads a media URL into the Downloads folder and adds it like a local
+    /// file, listed under the URL.
+    pub async fn add_url(&self, url: &str) -> Result<FileEntry> {
+        let url = url.trim();
+        let parsed =
+            reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid URL '{}': {}", url, e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(anyhow!("Only http and https URLs are supported"));
+        }
+
+        let dir =
+            dirs::download_dir().unwrap_or_else(|| self.config.get_config_dir().join("downloads"));
+        tokio::fs::create_dir_all(&dir).await?;
+        // Query strings aren't part of the name, and ffmpeg probes the format
+        // of files without an extension
+        let file_name = parsed
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .unwrap_or("download");
+        let mut path = dir.join(file_name);
+        if path.exists() {
+            path = dir.join(format!(
+                "{}-{}",
+                &Uuid::new_v4().to_string()[..8],
+                file_name
+            ));
+        }
+
+        log::info!("Downloading {} to {:?}", url, path);
+        self.downloader()?
+            .download_media(url, &path)
+            .await
+            .map_err(|e| anyhow!("Failed to download {}: {:#}", url, e))?;
+
+        self.claim_path(&path).await;
+        let mut entry = Self::create_file_entry(path);
+        entry.name = url.to_string();
+        Ok(entry)
+    }
+
+    /// Records the file as queued. Returns false if it already was, unless
+    /// every job for it was cancelled or failed, so it can be added again.
+    async fn claim_path(&self, path: &Path) -> bool {
+        let canonical = Self::canonical_path(path);
+        let claimed = self
+            .queued_paths
+            .lock()
+            .map(|mut queued| queued.insert(canonical.clone()))
+            .unwrap_or(true);
+        if claimed {
+            return true;
+        }
+
+        let jobs = self.jobs.lock().await;
+        let mut path_jobs = jobs
+            .values()
+            .filter(|job| Self::canonical_path(&job.file_path) == canonical)
+            .peekable();
+        path_jobs.peek().is_some()
+            && path_jobs.all(|job| matches!(job.status, FileStatus::Cancelled | FileStatus::Error))
+    }
+
+    fn canonical_path(path: &Path) -> PathBuf {
+        path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    fn release_path(&self, path: &Path) {
+        let canonical = Self::canonical_path(path);
+        if let Ok(mut queued) = self.queued_paths.lock() {
+            queued.remove(&canonical);
+        }
+    }
+
+    /// Collects the audio files in a directory, descending into subdirectories
+    /// when `recursive` is set. Stops after `MAX_DIRECTORY_FILES` files.
+    pub async fn add_directory(
+        &self,
+        dir_path: &Path,
+        recursive: bool,
+        progress_callback: impl Fn(usize),
+    ) -> Result<DirectoryScanResult> {
+        if !dir_path.is_dir() {
+            return Err(anyhow!("Not a directory: {}", dir_path.display()));
+        }
+
+        let mut result = DirectoryScanResult {
+            files: Vec::new(),
+            skipped: 0,
+            duplicates: 0,
+            truncated: false,
+        };
+        let mut scanned = 0;
+        let mut pending_dirs = vec![dir_path.to_path_buf()];
+
+        while let Some(dir) = pending_dirs.pop() {
+            let entries = match std::fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::warn!("Failed to read directory {:?}: {}", dir, e);
+                    continue;
+                }
             };
 
-            files.push(file_entry);
+            let mut paths: Vec<(PathBuf, bool)> = entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| {
+                    // file_type() does not follow symlinks, so linked directories can't loop
+                    let file_type = entry.file_type().ok()?;
+                    Some((entry.path(), file_type.is_dir()))
+                })
+                .collect();
+            paths.sort();
+
+            for (path, is_dir) in paths {
+                scanned += 1;
+                if scanned.is_multiple_of(DIRECTORY_PROGRESS_INTERVAL) {
+                    progress_callback(scanned);
+                }
+
+                if is_dir {
+                    if recursive {
+                        pending_dirs.push(path);
+                    } else {
+                        result.skipped += 1;
+                    }
+                    continue;
+                }
+
+                if !AudioConverter::is_audio_file(&path) {
+                    result.skipped += 1;
+                    continue;
+                }
+
+                if result.files.len() >= MAX_DIRECTORY_FILES {
+                    result.truncated = true;
+                    break;
+                }
+                if !self.claim_path(&path).await {
+                    result.duplicates += 1;
+                    continue;
+                }
+                result.files.push(Self::create_file_entry(path));
+            }
+
+            if result.truncated {
+                log::warn!(
+                    "Stopped scanning {:?} after {} files",
+                    dir_path,
+                    MAX_DIRECTORY_FILES
+                );
+                break;
+            }
         }
 
-        files
+        progress_callback(scanned);
+        Ok(result)
+    }
+
+    /// Estimates how long a batch will take from the input durations and the
+    /// model's measured (or built-in) real-time factor.
+    pub fn estimate_batch(
+        &self,
+        files: &[FileEntry],
+        settings: &TranscriptionSettings,
+    ) -> Result<BatchEstimate> {
+        let converter = AudioConverter::new()?;
+        let mut total_duration_secs = 0.0;
+        let mut unknown_duration_count = 0;
+
+        for file in files {
+            match converter.probe_duration(&file.path) {
+                Ok(duration) => total_duration_secs += duration,
+                Err(e) => {
+                    log::warn!("Failed to probe duration of {:?}: {}", file.path, e);
+                    unknown_duration_count += 1;
+                }
+            }
+        }
+
+        let history_rtf = self.config.load_rtf_history().get(&settings.model).copied();
+        let rtf = history_rtf.or_else(|| builtin_rtf(&settings.model));
+
+        Ok(BatchEstimate {
+            total_duration_secs,
+            estimated_processing_secs: rtf.map(|rtf| total_duration_secs * rtf),
+            output_count: files.len(),
+            unknown_duration_count,
+            rtf_from_history: history_rtf.is_some(),
+        })
+    }
+
+    fn create_file_entry(path: PathBuf) -> FileEntry {
+        let id = Uuid::new_v4().to_string();
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown")
+            .to_string();
+
+        let size = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+
+        FileEntry {
+            id,
+            path,
+            name,
+            size,
+            status: FileStatus::Pending,
+            progress: 0.0,
+            error: None,
+            output_path: None,
+            audio_stream_index: None,
+        }
+    }
+
+    /// Entry and settings for transcribing one file with the saved settings.
+    /// `output_format` overrides the saved formats for this run only; it is not
+    /// written back to the settings file.
+    pub fn single_file_job(
+        &self,
+        file_path: PathBuf,
+        output_format: Option<OutputFormat>,
+    ) -> Result<(FileEntry, TranscriptionSettings)> {
+        if !AudioConverter::is_audio_file(&file_path) {
+            return Err(anyhow!("Unsupported file type: {:?}", file_path));
+        }
+
+        let mut settings = self.config.load_settings()?;
+        if let Some(output_format) = output_format {
+            settings.output_formats = vec![output_format];
+        }
+        Ok((Self::create_file_entry(file_path), settings))
     }
 
     pub fn set_progress_sender(&mut self, sender: mpsc::UnboundedSender<ProgressUpdate>) {
         self.progress_sender = Some(sender);
     }
 
+    /// What the chosen output formats leave out, or settings they ignore, so
+    /// the user can switch formats before the batch runs. Never blocks a start.
+    pub fn output_format_warnings(settings: &TranscriptionSettings) -> Vec<String> {
+        let formats: Vec<OutputFormat> = std::iter::once(settings.output_format().clone())
+            .chain(settings.extra_output_formats())
+            .collect();
+        let writes = |wanted: &[OutputFormat]| formats.iter().any(|f| wanted.contains(f));
+        let is_subtitles = writes(&[OutputFormat::Srt, OutputFormat::Vtt]);
+        let mut warnings = Vec::new();
+
+        if !writes(&[OutputFormat::Srt, OutputFormat::Vtt, OutputFormat::Json]) {
+            warnings.push("TXT output has no timestamps; choose SRT, VTT or JSON to keep timing");
+        }
+        if writes(&[OutputFormat::Json]) && !settings.emit_raw_json_sidecar {
+            warnings.push(
+                "JSON output only has segment times; enable the raw JSON sidecar for word timings",
+            );
+        }
+        if !is_subtitles && settings.mux_subtitles {
+            warnings.push("Subtitles are only added to videos for SRT and VTT output");
+        }
+        if !is_subtitles && (settings.min_cue_duration_ms > 0 || settings.max_cue_duration_ms > 0) {
+            warnings.push("Cue duration limits only apply to SRT and VTT output");
+        }
+        if !writes(&[OutputFormat::Vtt])
+            && (settings.vtt_align.is_some() || settings.vtt_line.is_some())
+        {
+            warnings.push("VTT cue settings only apply to VTT output");
+        }
+        if settings.include_language_in_output && !writes(&[OutputFormat::Json, OutputFormat::Vtt])
+        {
+            warnings.push("The language is only written into JSON and VTT output");
+        }
+        if settings.include_chapters
+            && !writes(&[OutputFormat::Txt, OutputFormat::Vtt, OutputFormat::Json])
+        {
+            warnings.push("Chapters are only written into TXT, VTT and JSON output");
+        }
+        warnings.into_iter().map(String::from).collect()
+    }
+
     pub async fn start_transcription(
         &self,
         files: Vec<FileEntry>,
         settings: TranscriptionSettings,
     ) -> Result<()> {
+        let (settings, substitution) = self.effective_settings(settings);
+        if let Some(warning) = substitution {
+            log::warn!("{}", warning);
+        }
         let model_path = self.config.get_model_path(&settings.model);
 
         if !model_path.exists() {
-            return Err(anyhow!("Model not downloaded: {}", settings.model));
+            return Err(AppError::ModelNotDownloaded(format!(
+                "Model not downloaded: {}",
+                settings.model
+            ))
+            .into());
         }
 
+        let source_root = Self::common_base(files.iter().map(|file| file.path.as_path()));
+        let batch_ids: Vec<String> = files.iter().map(|file| file.id.clone()).collect();
+
         // Create jobs
         let mut jobs_map = self.jobs.lock().await;
+        if let Ok(mut order) = self.job_order.lock() {
+            for file in &files {
+                if !order.contains(&file.id) {
+                    order.push(file.id.clone());
+                }
+            }
+        }
         for file in files {
             let job = TranscriptionJob {
                 id: file.id.clone(),
                 file_path: file.path.clone(),
                 settings: settings.clone(),
-                status: FileStatus::Pending,
+                status: FileStatus::Queued,
                 progress: 0.0,
                 error: None,
                 output_path: None,
+                temp_wav_path: None,
+                audio_stream_index: file.audio_stream_index,
+                source_root: source_root.clone(),
+                attempt: 0,
+                segments: Vec::new(),
+                timestamp_offset_ms: 0,
+                detected_language: None,
+                notice: None,
             };
+            Self::send_progress(&job, self.progress_sender.as_ref());
             jobs_map.insert(file.id, job);
         }
         drop(jobs_map);
 
         // Start processing jobs
-        self.process_jobs(settings.parallel_jobs).await;
+        self.process_jobs(StageLimits::new(&settings)).await;
+
+        if settings.combine_output || settings.open_folder_on_complete {
+            self.wait_for_jobs(&batch_ids).await;
+        }
+        if settings.combine_output {
+            match self.write_combined_output(&batch_ids, &settings).await {
+                Ok(path) => log::info!("Wrote combined output {:?}", path),
+                Err(e) => log::error!("Failed to write combined output: {}", e),
+            }
+        }
+        if settings.open_folder_on_complete {
+            let jobs = self.jobs.lock().await;
+            let batch: Vec<&TranscriptionJob> =
+                batch_ids.iter().filter_map(|id| jobs.get(id)).collect();
+            completion_hooks::run_batch(&batch, &settings);
+        }
 
         Ok(())
     }
 
-    async fn process_jobs(&self, max_parallel: usize) {
+    /// Waits until none of the jobs is queued or running. A concurrent
+    /// processing pass may still be working on some after this one runs dry.
+    async fn wait_for_jobs(&self, job_ids: &[String]) {
+        loop {
+            let busy = {
+                let jobs = self.jobs.lock().await;
+                job_ids.iter().filter_map(|id| jobs.get(id)).any(|job| {
+                    matches!(
+                        job.status,
+                        FileStatus::Queued | FileStatus::Converting | FileStatus::Transcribing
+                    )
+                })
+            };
+            if !busy {
+                return;
+            }
+            tokio::time::sleep(BATCH_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Writes the batch's transcripts as one file, in queue order. Refused
+    /// unless every file completed, since a gap would shift later timestamps.
+    async fn write_combined_output(
+        &self,
+        job_ids: &[String],
+        settings: &TranscriptionSettings,
+    ) -> Result<PathBuf> {
+        let batch: Vec<TranscriptionJob> = self
+            .ordered_jobs()
+            .await
+            .into_iter()
+            .filter(|job| job_ids.contains(&job.id))
+            .collect();
+        let incomplete = batch
+            .iter()
+            .filter(|job| !matches!(job.status, FileStatus::Completed))
+            .count();
+        if incomplete > 0 {
+            return Err(anyhow!(
+                "{} of {} files did not complete",
+                incomplete,
+                batch.len()
+            ));
+        }
+        let first = batch.first().ok_or_else(|| anyhow!("Batch is empty"))?;
+
+        // Probing opens every input with ffmpeg, so keep it off the async workers
+        let paths: Vec<PathBuf> = batch.iter().map(|job| job.file_path.clone()).collect();
+        let durations = tokio::task::spawn_blocking(move || {
+            let converter = AudioConverter::new()?;
+            paths
+                .iter()
+                .map(|path| {
+                    converter
+                        .probe_duration(path)
+                        .map_err(|e| anyhow!("Unknown duration of {:?}: {}", path, e))
+                })
+                .collect::<Result<Vec<f64>>>()
+        })
+        .await??;
+
+        let clips: Vec<(&TranscriptionJob, f64)> = batch.iter().zip(durations).collect();
+        let segments = Self::combine_segments(&clips);
+        // Chapter times are those of a single file
+        let options = FormatOptions {
+            language: Self::output_language(settings, first.detected_language.as_deref()),
+            chapters: Vec::new(),
+            ..FormatOptions::from_settings(&first.file_path, settings)
+        };
+        let text =
+            WhisperTranscriber::format_segments(&segments, settings.output_format(), &options)?;
+
+        let output_path = match (&settings.combined_output_path, &first.output_path) {
+            (Some(path), _) => path.clone(),
+            (None, Some(first_output)) => {
+                let stem = first_output
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy();
+                first_output.with_file_name(format!(
+                    "{}.combined.{}",
+                    stem,
+                    settings.output_format().extension()
+                ))
+            }
+            (None, None) => return Err(anyhow!("No output location for the combined file")),
+        };
+        tokio::fs::write(&output_path, text).await?;
+
+        if !settings.keep_individual_outputs {
+            let mut jobs = self.jobs.lock().await;
+            for job_id in job_ids {
+                if let Some(job) = jobs.get_mut(job_id) {
+                    if let Some(path) = job.output_path.take() {
+                        if let Err(e) = std::fs::remove_file(&path) {
+                            log::warn!("Failed to remove {:?}: {}", path, e);
+                        }
+                    }
+                    Self::send_progress(job, self.progress_sender.as_ref());
+                }
+            }
+        }
+        Ok(output_path)
+    }
+
+    /// Segments of consecutive clips on one timeline: each clip's times are
+    /// shifted by the total duration of the clips before it.
+    fn combine_segments(clips: &[(&TranscriptionJob, f64)]) -> Vec<Segment> {
+        let mut offset_ms = 0;
+        let mut combined = Vec::new();
+        for (job, duration_secs) in clips {
+            combined.extend(WhisperTranscriber::offset_segments(
+                &job.segments,
+                offset_ms + job.timestamp_offset_ms,
+            ));
+            offset_ms += (duration_secs * 1000.0).round() as i64;
+        }
+        combined
+    }
+
+    /// Runs queued jobs until none are left. Only one pass runs at a time:
+    /// while one is running, further calls return at once and the jobs they
+    /// queued are picked up by the running pass.
+    ///
+    /// Conversion and transcription are separate stages with their own
+    /// limits, so one file can convert while another transcribes.
+    async fn process_jobs(&self, limits: StageLimits) {
+        if self.processing.swap(true, Ordering::SeqCst) {
+            return;
+        }
         let jobs = self.jobs.clone();
         let active_tasks = self.active_tasks.clone();
         let progress_sender = self.progress_sender.clone();
         let config = self.config.clone();
-
-        // Get pending jobs
-        let pending_jobs: Vec<TranscriptionJob> = {
-            let jobs_map = jobs.lock().await;
-            jobs_map
-                .values()
-                .filter(|job| matches!(job.status, FileStatus::Pending))
-                .cloned()
-                .collect()
+        let slots = StageSlots {
+            conversion: Arc::new(Semaphore::new(limits.conversion)),
+            transcription: Arc::new(Semaphore::new(limits.transcription)),
         };
+        // Enough jobs to fill both stages; more would only wait on a slot
+        let pool_size = limits.conversion + limits.transcription;
 
-        // Process jobs in chunks
-        for chunk in pending_jobs.chunks(max_parallel) {
-            let mut handles = Vec::new();
-
-            for job in chunk {
-                let job = job.clone();
-                let jobs_clone = jobs.clone();
-                let progress_sender = progress_sender.clone();
-                let config_clone = config.clone();
+        // Each job is picked when a place in the pool frees up, so that
+        // reordering the queue takes effect
+        let mut running: Vec<(String, tokio::task::JoinHandle<()>)> = Vec::new();
+        let mut ran_job = false;
+        loop {
+            if running.len() >= pool_size {
+                Self::join_next_job(&mut running, &active_tasks).await;
+                continue;
+            }
 
-                let handle = tokio::spawn(async move {
-                    Self::process_single_job(job, jobs_clone, progress_sender, config_clone).await;
-                });
+            let next = self.ordered_jobs().await.into_iter().find(|job| {
+                matches!(job.status, FileStatus::Queued)
+                    && !running.iter().any(|(job_id, _)| *job_id == job.id)
+            });
+            let Some(job) = next else {
+                if !running.is_empty() {
+                    Self::join_next_job(&mut running, &active_tasks).await;
+                    continue;
+                }
+                self.processing.store(false, Ordering::SeqCst);
+                // A job queued after the check above saw the flag still set,
+                // so it's ours to run unless another pass has started since
+                if !self.has_queued_jobs().await || self.processing.swap(true, Ordering::SeqCst) {
+                    break;
+                }
+                continue;
+            };
 
-                handles.push(handle);
+            let cooldown_secs = job.settings.cooldown_secs_between_jobs;
+            if ran_job && cooldown_secs > 0 {
+                // The cooldown is a pause with nothing running
+                while !running.is_empty() {
+                    Self::join_next_job(&mut running, &active_tasks).await;
+                }
+                self.cool_down(std::time::Duration::from_secs(cooldown_secs))
+                    .await;
+                // The queue may have changed while waiting
+                ran_job = false;
+                continue;
             }
+            ran_job = true;
 
-            // Wait for all jobs in this chunk to complete
-            for handle in handles {
-                let _ = handle.await;
-            }
+            let job_id = job.id.clone();
+            let jobs_clone = jobs.clone();
+            let progress_sender = progress_sender.clone();
+            let config_clone = config.clone();
+            let slots = slots.clone();
+            let cancel = Arc::new(AtomicBool::new(false));
+
+            let handle = tokio::spawn({
+                let cancel = cancel.clone();
+                async move {
+                    Self::process_single_job(
+                        job,
+                        jobs_clone,
+                        progress_sender,
+                        config_clone,
+                        slots,
+                        cancel,
+                    )
+                    .await;
+                }
+            });
+
+            active_tasks.lock().await.insert(
+                job_id.clone(),
+                ActiveTask {
+                    abort: handle.abort_handle(),
+                    cancel,
+                },
+            );
+            running.push((job_id, handle));
+        }
+    }
+
+    /// Waits for whichever running job finishes first.
+    async fn join_next_job(
+        running: &mut Vec<(String, tokio::task::JoinHandle<()>)>,
+        active_tasks: &Mutex<HashMap<String, ActiveTask>>,
+    ) {
+        let finished =
+            futures_util::future::select_all(running.iter_mut().map(|(_, handle)| handle))
+                .await
+                .1;
+        let (job_id, _) = running.swap_remove(finished);
+        active_tasks.lock().await.remove(&job_id);
+    }
+
+    /// Waits out the cooldown between jobs, ending early once nothing is
+    /// queued, e.g. when the remaining jobs are cancelled or paused.
+    async fn cool_down(&self, duration: std::time::Duration) {
+        log::info!("Cooling down for {:?} before the next job", duration);
+        let deadline = tokio::time::Instant::now() + duration;
+        while tokio::time::Instant::now() < deadline && self.has_queued_jobs().await {
+            tokio::time::sleep(COOLDOWN_POLL_INTERVAL).await;
         }
     }
 
+    async fn has_queued_jobs(&self) -> bool {
+        self.ordered_jobs()
+            .await
+            .iter()
+            .any(|job| matches!(job.status, FileStatus::Queued))
+    }
+
     async fn process_single_job(
         mut job: TranscriptionJob,
         jobs: Arc<Mutex<HashMap<String, TranscriptionJob>>>,
         progress_sender: Option<mpsc::UnboundedSender<ProgressUpdate>>,
         config: ConfigManager,
+        slots: StageSlots,
+        cancel: Arc<AtomicBool>,
     ) {
-        // Update job status
+        // The job stays queued until a converter is free
+        let Ok(conversion_slot) = slots.conversion.acquire().await else {
+            return;
+        };
+
+        // A WAV already in whisper's format is transcribed as is, unless it
+        // needs gain applied
+        let skip_conversion = job.settings.pre_gain_db == 0.0
+            && job.audio_stream_index.is_none()
+            && WhisperTranscriber::is_whisper_wav(&job.file_path);
+        let wav_path = if skip_conversion {
+            job.file_path.clone()
+        } else {
+            Self::get_temp_wav_path(&job.file_path)
+        };
+
+        // Claim the job so a concurrent processing pass doesn't pick it up too
         job.status = FileStatus::Converting;
-        Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await;
+        job.temp_wav_path = (!skip_conversion).then(|| wav_path.clone());
+        if !Self::claim_job(&jobs, &job, progress_sender.as_ref()).await {
+            return;
+        }
+        if !skip_conversion {
+            // A kept WAV may have been tracked by an earlier run without `keep_wav`
+            let tracked = if job.settings.keep_wav {
+                config.untrack_temp_wav(&wav_path)
+            } else {
+                config.track_temp_wav(&wav_path)
+            };
+            if let Err(e) = tracked {
+                log::warn!("Failed to update tracked temp WAV {:?}: {}", wav_path, e);
+            }
+        }
+
+        let converter = match AudioConverter::new() {
+            Ok(converter) => converter
+                .with_pre_gain_db(job.settings.pre_gain_db)
+                .with_resample_quality(job.settings.resample_quality)
+                .with_downmix(job.settings.downmix)
+                .with_stall_timeout(job.settings.conversion_timeout_secs)
+                .with_audio_stream(job.audio_stream_index),
+            Err(e) => {
+                job.status = FileStatus::Error;
+                job.error = Some(e.to_string());
+                job.temp_wav_path = None;
+                Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await;
+                return;
+            }
+        };
+
+        // Refuse inputs that would not fit in memory once decoded
+        if let Err(e) = Self::check_input_duration(&converter, &job.file_path, &job.settings) {
+            job.status = FileStatus::Error;
+            job.error = Some(e.to_string());
+            job.temp_wav_path = None;
+            Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await;
+            return;
+        }
 
-        // Convert audio to WAV
-        let wav_path = Self::get_temp_wav_path(&job.file_path);
-        let converter = AudioConverter::new();
+        // Convert audio to WAV; conversion is the first 30% of the job
+        let mut throttle = ProgressThrottle::new(job.settings.progress_update_interval());
+        let convert_result = if skip_conversion {
+            log::info!("{:?} is already 16 kHz mono WAV", job.file_path);
+            Ok(())
+        } else {
+            converter.convert_to_wav(&job.file_path, &wav_path, &cancel, |progress| {
+                if throttle.should_send(progress) {
+                    Self::spawn_progress_update(
+                        &jobs,
+                        &job.id,
+                        job.attempt,
+                        progress_sender.as_ref(),
+                        FileStatus::Converting,
+                        progress * 0.3,
+                        "Converting...",
+                    );
+                }
+            })
+        };
 
-        let convert_result = converter.convert_to_wav(&job.file_path, &wav_path);
+        if cancel.load(Ordering::SeqCst) {
+            // Cancelled or requeued mid-conversion; the job was already updated
+            // and the converter removed the partial WAV
+            return;
+        }
 
         if let Err(e) = convert_result {
             job.status = FileStatus::Error;
@@ -199,10 +1181,19 @@ impl TranscriptionManager {
             return;
         }
 
+        // Let the next file convert while this one waits to be transcribed
+        drop(conversion_slot);
+        let Ok(_transcription_slot) = slots.transcription.acquire().await else {
+            return;
+        };
+
         // Load model and transcribe
         job.status = FileStatus::Transcribing;
         job.progress = 30.0;
-        Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await;
+        if !Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await {
+            Self::discard_run(&config, &jobs, &job).await;
+            return;
+        }
 
         let model_path = config.get_model_path(&job.settings.model);
         let mut transcriber = WhisperTranscriber::new();
@@ -214,49 +1205,230 @@ impl TranscriptionManager {
             return;
         }
 
-        let transcription_result = transcriber.transcribe_file(
+        let smoother = job.settings.smooth_progress.then(|| {
+            let rtf = config
+                .load_rtf_history()
+                .get(&job.settings.model)
+                .copied()
+                .or_else(|| builtin_rtf(&job.settings.model));
+            let expected_secs = WhisperTranscriber::wav_duration_secs(&wav_path)
+                .zip(rtf)
+                .map(|(duration, rtf)| duration * rtf);
+            Arc::new(StdMutex::new(ProgressSmoother::new(expected_secs)))
+        });
+        // Transcription blocks this task, so the estimate is reported from
+        // another one until it returns
+        let ticker = smoother.clone().map(|smoother| {
+            let jobs = jobs.clone();
+            let job_id = job.id.clone();
+            let attempt = job.attempt;
+            let progress_sender = progress_sender.clone();
+            let tick = job
+                .settings
+                .progress_update_interval()
+                .max(progress_smoother::MIN_TICK_INTERVAL);
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(tick);
+                let mut last = None;
+                loop {
+                    interval.tick().await;
+                    let Ok(progress) = smoother
+                        .lock()
+                        .map(|mut smoother| smoother.tick(std::time::Instant::now()))
+                    else {
+                        return;
+                    };
+                    if last == Some(progress) {
+                        continue;
+                    }
+                    last = Some(progress);
+                    Self::spawn_progress_update(
+                        &jobs,
+                        &job_id,
+                        attempt,
+                        progress_sender.as_ref(),
+                        FileStatus::Transcribing,
+                        30.0 + (progress * 0.7),
+                        "Transcribing...",
+                    );
+                }
+            })
+        });
+
+        // Text formats are written as whisper goes; a failure to stream falls
+        // back to writing the whole transcript at the end
+        let output_path = Self::get_output_path(
+            &job.file_path,
+            job.source_root.as_deref(),
+            &job.settings,
+            job.settings.output_format(),
+        );
+        let known_language = WhisperTranscriber::resolve_language(
+            &job.settings.model,
+            job.settings.language.as_deref(),
+        )
+        .ok()
+        .flatten();
+        let stream_options = FormatOptions {
+            language: Self::output_language(&job.settings, known_language.as_deref()),
+            ..FormatOptions::from_settings(&job.file_path, &job.settings)
+        };
+        // A language whisper has yet to detect can't go into the VTT header
+        let awaits_detection = job.settings.include_language_in_output
+            && stream_options.language.is_none()
+            && matches!(job.settings.output_format(), OutputFormat::Vtt);
+        let stream = if awaits_detection {
+            None
+        } else {
+            match StreamingWriter::create(
+                &output_path,
+                job.settings.output_format(),
+                stream_options,
+                job.settings.anti_repetition,
+            ) {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("Cannot stream output to {:?}: {}", output_path, e);
+                    None
+                }
+            }
+        };
+        let stream = Arc::new(StdMutex::new(stream));
+
+        let transcription_started = std::time::Instant::now();
+        let transcription_result = transcriber.transcribe_segments(
             &wav_path,
             &job.settings,
             Some(Box::new({
                 let jobs = jobs.clone();
                 let job_id = job.id.clone();
+                let attempt = job.attempt;
                 let progress_sender = progress_sender.clone();
                 move |progress| {
-                    let jobs = jobs.clone();
-                    let job_id = job_id.clone();
-                    let progress_sender = progress_sender.clone();
-                    tokio::spawn(async move {
-                        let mut jobs_map = jobs.lock().await;
-                        if let Some(job) = jobs_map.get_mut(&job_id) {
-                            job.progress = 30.0 + (progress * 0.7); // 70% for transcription
-                            job.status = FileStatus::Transcribing;
-                        }
-                        if let Some(sender) = &progress_sender {
-                            let _ = sender.send(ProgressUpdate {
-                                file_id: job_id.clone(),
-                                status: FileStatus::Transcribing,
-                                progress: 30.0 + (progress * 0.7),
-                                message: Some("Transcribing...".to_string()),
-                            });
-                        }
-                    });
+                    let progress = match &smoother {
+                        Some(smoother) => smoother
+                            .lock()
+                            .map(|mut smoother| smoother.update(progress))
+                            .unwrap_or(progress),
+                        None => progress,
+                    };
+                    Self::spawn_progress_update(
+                        &jobs,
+                        &job_id,
+                        attempt,
+                        progress_sender.as_ref(),
+                        FileStatus::Transcribing,
+                        30.0 + (progress * 0.7), // 70% for transcription
+                        "Transcribing...",
+                    );
+                }
+            })),
+            Some(Box::new({
+                let stream = stream.clone();
+                move |segment| {
+                    let Ok(mut stream) = stream.lock() else {
+                        return;
+                    };
+                    if let Some(Err(e)) = stream.as_mut().map(|writer| writer.push(segment)) {
+                        log::warn!("Stopped streaming output: {}", e);
+                        *stream = None;
+                    }
                 }
             })),
         );
+        let stream = stream.lock().ok().and_then(|mut stream| stream.take());
+        if let Some(ticker) = ticker {
+            ticker.abort();
+        }
 
         log::debug!("Transcribed: result={:?}", transcription_result);
 
+        if transcription_result.is_ok() {
+            if let Some(duration) = WhisperTranscriber::wav_duration_secs(&wav_path) {
+                let rtf = transcription_started.elapsed().as_secs_f64() / duration;
+                if let Err(e) = config.record_rtf(&job.settings.model, rtf) {
+                    log::warn!("Failed to record real-time factor: {}", e);
+                }
+            }
+        }
+
         match transcription_result {
-            Ok(text) => {
-                // Save output
-                let output_path = Self::get_output_path(&job.file_path, &job.settings);
-                if let Err(e) = std::fs::write(&output_path, text) {
+            Ok(transcription) => {
+                let (transcription, stream) = match Self::retry_with_larger_model(
+                    &config,
+                    &jobs,
+                    &job,
+                    &mut transcriber,
+                    &wav_path,
+                    &transcription,
+                    progress_sender.as_ref(),
+                )
+                .await
+                {
+                    Some((model, upgraded)) => {
+                        job.notice = Some(format!(
+                            "Low confidence with {}, used {} instead",
+                            job.settings.model, model
+                        ));
+                        job.settings.model = model;
+                        // The streamed output is from the first model;
+                        // dropping it removes the partial file
+                        (upgraded, None)
+                    }
+                    None => (transcription, stream),
+                };
+
+                // Record the output path first so a cancellation can clean it up
+                job.output_path = Some(output_path.clone());
+                if !Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await {
+                    job.output_path = None;
+                    Self::discard_run(&config, &jobs, &job).await;
+                    return;
+                }
+
+                let saved = match stream {
+                    Some(stream) => stream.finish().map(|_| ()),
+                    None => Self::write_output(
+                        &output_path,
+                        &transcription,
+                        &job,
+                        job.settings.output_format(),
+                    ),
+                }
+                .and_then(|_| Self::write_extra_outputs(&transcription, &job));
+                if let Err(e) = saved {
                     job.status = FileStatus::Error;
                     job.error = Some(format!("Failed to save output: {}", e));
+                    job.output_path = None;
                 } else {
                     job.status = FileStatus::Completed;
                     job.progress = 100.0;
-                    job.output_path = Some(output_path);
+                    if !WhisperTranscriber::has_speech(&transcription.segments) {
+                        log::info!("No speech detected in {:?}", job.file_path);
+                        job.notice = Some(NO_SPEECH_NOTICE.to_string());
+                    }
+                    job.segments = transcription.segments;
+                    job.timestamp_offset_ms = transcription.timestamp_offset_ms;
+                    job.detected_language = transcription.language;
+                    Self::save_job_segments(&config, &job);
+
+                    if job.settings.mux_subtitles {
+                        Self::mux_subtitles(&job);
+                    }
+                    if job.settings.emit_raw_json_sidecar {
+                        if !transcription.word_timestamps && job.notice.is_none() {
+                            job.notice = Some(NO_WORD_TIMESTAMPS_NOTICE.to_string());
+                        }
+                        Self::write_raw_sidecar(
+                            &job.file_path,
+                            &output_path,
+                            &WhisperTranscriber::offset_segments(
+                                &job.segments,
+                                job.timestamp_offset_ms,
+                            ),
+                            transcription.word_timestamps,
+                        );
+                    }
                 }
             }
             Err(e) => {
@@ -265,76 +1437,1955 @@ impl TranscriptionManager {
             }
         }
 
-        // Clean up WAV file if needed
+        // Clean up WAV file if needed; an unconverted input has none
         if !job.settings.keep_wav {
-            let _ = std::fs::remove_file(&wav_path);
+            if let Some(temp_wav_path) = job.temp_wav_path.take() {
+                let _ = std::fs::remove_file(&temp_wav_path);
+                Self::untrack_temp_wav(&config, &temp_wav_path);
+            }
         }
 
-        Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await;
+        if !Self::update_job_progress(&jobs, &job, progress_sender.as_ref()).await {
+            // Cancelled or requeued while the output was being written
+            Self::discard_run(&config, &jobs, &job).await;
+            return;
+        }
+        completion_hooks::run(&job);
     }
 
-    async fn update_job_progress(
+    /// Transcribes again with the next larger downloaded model when
+    /// `auto_upgrade_on_low_confidence` is set and `transcription` scores below
+    /// `confidence_threshold`. Returns that model and its transcription only if
+    /// it scored higher.
+    async fn retry_with_larger_model(
+        config: &ConfigManager,
         jobs: &Arc<Mutex<HashMap<String, TranscriptionJob>>>,
         job: &TranscriptionJob,
+        transcriber: &mut WhisperTranscriber,
+        wav_path: &Path,
+        transcription: &Transcription,
+        progress_sender: Option<&mpsc::UnboundedSender<ProgressUpdate>>,
+    ) -> Option<(String, Transcription)> {
+        let settings = &job.settings;
+        if !settings.auto_upgrade_on_low_confidence {
+            return None;
+        }
+        let confidence = WhisperTranscriber::avg_logprob(&transcription.segments)?;
+        if confidence >= settings.confidence_threshold {
+            return None;
+        }
+        // A job cancelled or requeued during the first pass isn't run again
+        let current = jobs
+            .lock()
+            .await
+            .get(&job.id)
+            .is_some_and(|stored| stored.attempt == job.attempt && !Self::is_cancelled(stored));
+        if !current {
+            return None;
+        }
+        let language =
+            WhisperTranscriber::resolve_language(&settings.model, settings.language.as_deref())
+                .ok()
+                .flatten();
+        let models = config.get_available_models();
+        let model = larger_model(&models, &settings.model, language.as_deref())?
+            .name
+            .clone();
+        log::info!(
+            "Average log probability of {:?} is {:.2} with {}, retrying with {}",
+            job.file_path,
+            confidence,
+            settings.model,
+            model
+        );
+
+        let retry_settings = TranscriptionSettings {
+            model: model.clone(),
+            ..settings.clone()
+        };
+        let message = format!("Low confidence, retrying with {}...", model);
+        // The first model is replaced rather than kept loaded alongside
+        let retried = transcriber
+            .load_model(&config.get_model_path(&model))
+            .and_then(|_| {
+                transcriber.transcribe_segments(
+                    wav_path,
+                    &retry_settings,
+                    Some(Box::new({
+                        let jobs = jobs.clone();
+                        let job_id = job.id.clone();
+                        let attempt = job.attempt;
+                        let progress_sender = progress_sender.cloned();
+                        move |progress| {
+                            Self::spawn_progress_update(
+                                &jobs,
+                                &job_id,
+                                attempt,
+                                progress_sender.as_ref(),
+                                FileStatus::Transcribing,
+                                30.0 + (progress * 0.7),
+                                &message,
+                            );
+                        }
+                    })),
+                    None,
+                )
+            });
+        let retried = match retried {
+            Ok(retried) => retried,
+            Err(e) => {
+                log::warn!(
+                    "Retry with {} failed, keeping the first result: {}",
+                    model,
+                    e
+                );
+                return None;
+            }
+        };
+
+        let retried_confidence = WhisperTranscriber::avg_logprob(&retried.segments)?;
+        if retried_confidence <= confidence {
+            log::info!(
+                "{} scored {:.2}, keeping the first result",
+                model,
+                retried_confidence
+            );
+            return None;
+        }
+        Some((model, retried))
+    }
+
+    /// Records progress reported from a synchronous callback without blocking
+    /// it. Reports from a superseded or cancelled run are dropped.
+    fn spawn_progress_update(
+        jobs: &Arc<Mutex<HashMap<String, TranscriptionJob>>>,
+        job_id: &str,
+        attempt: u32,
         progress_sender: Option<&mpsc::UnboundedSender<ProgressUpdate>>,
+        status: FileStatus,
+        progress: f32,
+        message: &str,
     ) {
+        let jobs = jobs.clone();
+        let job_id = job_id.to_string();
+        let progress_sender = progress_sender.cloned();
+        let message = message.to_string();
+        tokio::spawn(async move {
+            let mut jobs_map = jobs.lock().await;
+            match jobs_map.get_mut(&job_id) {
+                // A late update must not undo a job that has since finished
+                Some(job)
+                    if job.attempt == attempt
+                        && matches!(
+                            job.status,
+                            FileStatus::Converting | FileStatus::Transcribing
+                        ) =>
+                {
+                    job.progress = progress;
+                    job.status = status.clone();
+                }
+                _ => return,
+            }
+            if let Some(sender) = &progress_sender {
+                let _ = sender.send(ProgressUpdate {
+                    file_id: job_id,
+                    status,
+                    progress,
+                    message: Some(message),
+                    output_path: None,
+                    notice: None,
+                });
+            }
+        });
+    }
+
+    /// Transcription loads every sample into memory, so very long inputs are
+    /// rejected up front. Inputs whose duration can't be probed are let through.
+    fn check_input_duration(
+        converter: &AudioConverter,
+        input_path: &Path,
+        settings: &TranscriptionSettings,
+    ) -> Result<()> {
+        if settings.max_input_duration_secs == 0 {
+            return Ok(());
+        }
+
+        let duration = match converter.probe_duration(input_path) {
+            Ok(duration) => duration,
+            Err(e) => {
+                log::warn!("Failed to probe duration of {:?}: {}", input_path, e);
+                return Ok(());
+            }
+        };
+
+        if duration > settings.max_input_duration_secs as f64 {
+            return Err(anyhow!(
+                "Input is {} long, over the {} limit. Split the file into shorter parts or raise the maximum input duration in Settings.",
+                Self::format_duration(duration),
+                Self::format_duration(settings.max_input_duration_secs as f64)
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn format_duration(secs: f64) -> String {
+        let total_minutes = (secs / 60.0).round() as u64;
+        format!("{}h {:02}m", total_minutes / 60, total_minutes % 60)
+    }
+
+    /// Adds the job's first SRT or VTT output to a copy of a video input as
+    /// `<stem>.subtitled.mkv`. A failure here is logged but doesn't fail the
+    /// job, the transcript is already written.
+    fn mux_subtitles(job: &TranscriptionJob) {
+        let input_path = job.file_path.as_path();
+        let Some(format) = job
+            .settings
+            .output_formats
+            .iter()
+            .find(|format| matches!(format, OutputFormat::Srt | OutputFormat::Vtt))
+        else {
+            return;
+        };
+        let subtitle_path = &Self::get_output_path(
+            input_path,
+            job.source_root.as_deref(),
+            &job.settings,
+            format,
+        );
+
+        if !AudioConverter::is_video_file(input_path) {
+            return;
+        }
+        let converter = match AudioConverter::new() {
+            Ok(converter) => converter,
+            Err(e) => {
+                log::error!("Failed to mux subtitles: {}", e);
+                return;
+            }
+        };
+
+        let stem = input_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("output");
+        let muxed_path = subtitle_path.with_file_name(format!("{}.subtitled.mkv", stem));
+
+        if let Err(e) = converter.mux_subtitles(input_path, subtitle_path, &muxed_path) {
+            log::error!("Failed to mux subtitles into {:?}: {}", muxed_path, e);
+            let _ = std::fs::remove_file(&muxed_path);
+        }
+    }
+
+    /// Writes everything whisper produced, including tokens and their
+    /// probabilities, to `<stem>.raw.json` next to the output. Like muxing, a
+    /// failure is only logged. `word_timestamps` is written along, as token
+    /// times are segment times when it's `false`.
+    fn write_raw_sidecar(
+        input_path: &Path,
+        output_path: &Path,
+        segments: &[Segment],
+        word_timestamps: bool,
+    ) {
+        let stem = input_path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("output");
+        let sidecar_path = output_path.with_file_name(format!("{}.raw.json", stem));
+
+        let written = serde_json::to_string_pretty(&serde_json::json!({
+            "word_timestamps": word_timestamps,
+            "segments": segments,
+        }))
+        .map_err(anyhow::Error::from)
+        .and_then(|json| Ok(std::fs::write(&sidecar_path, json)?));
+        if let Err(e) = written {
+            log::error!("Failed to write raw segments to {:?}: {}", sidecar_path, e);
+        }
+    }
+
+    /// Marks a queued job as started. Returns `false` if the job is no longer
+    /// queued or has been requeued since it was picked.
+    async fn claim_job(
+        jobs: &Arc<Mutex<HashMap<String, TranscriptionJob>>>,
+        job: &TranscriptionJob,
+        progress_sender: Option<&mpsc::UnboundedSender<ProgressUpdate>>,
+    ) -> bool {
+        let mut jobs_map = jobs.lock().await;
+        let claimable = jobs_map.get(&job.id).is_some_and(|stored| {
+            matches!(stored.status, FileStatus::Queued) && stored.attempt == job.attempt
+        });
+        if !claimable {
+            return false;
+        }
+        jobs_map.insert(job.id.clone(), job.clone());
+
+        Self::send_progress(job, progress_sender);
+        true
+    }
+
+    /// Stores the job and emits a progress update. Returns `false` without
+    /// touching the stored job if it has been cancelled or requeued in the meantime.
+    async fn update_job_progress(
+        jobs: &Arc<Mutex<HashMap<String, TranscriptionJob>>>,
+        job: &TranscriptionJob,
+        progress_sender: Option<&mpsc::UnboundedSender<ProgressUpdate>>,
+    ) -> bool {
         // Update job in map
         let mut jobs_map = jobs.lock().await;
+        let superseded = jobs_map
+            .get(&job.id)
+            .is_some_and(|stored| stored.attempt != job.attempt || Self::is_cancelled(stored));
+        if superseded {
+            return false;
+        }
         jobs_map.insert(job.id.clone(), job.clone());
 
-        // Send progress update
+        Self::send_progress(job, progress_sender);
+        true
+    }
+
+    fn send_progress(
+        job: &TranscriptionJob,
+        progress_sender: Option<&mpsc::UnboundedSender<ProgressUpdate>>,
+    ) {
         if let Some(sender) = progress_sender {
             let _ = sender.send(ProgressUpdate {
                 file_id: job.id.clone(),
                 status: job.status.clone(),
                 progress: job.progress,
                 message: job.error.clone(),
+                output_path: job.output_path.clone(),
+                notice: job.notice.clone(),
             });
         }
     }
 
+    fn is_cancelled(job: &TranscriptionJob) -> bool {
+        matches!(job.status, FileStatus::Cancelled)
+    }
+
+    /// Cleans up after a run that was superseded. Files are only removed when the
+    /// job was cancelled; a requeued job reuses the same paths for its next attempt.
+    async fn discard_run(
+        config: &ConfigManager,
+        jobs: &Arc<Mutex<HashMap<String, TranscriptionJob>>>,
+        job: &TranscriptionJob,
+    ) {
+        let requeued = jobs
+            .lock()
+            .await
+            .get(&job.id)
+            .is_some_and(|stored| stored.attempt != job.attempt);
+        if !requeued {
+            Self::remove_job_files(config, job);
+        }
+    }
+
+    /// Removes the temp WAV, saved segments and any output written by a job
+    /// that did not complete.
+    fn remove_job_files(config: &ConfigManager, job: &TranscriptionJob) {
+        config.remove_job_segments(&job.id);
+        if let Some(wav_path) = &job.temp_wav_path {
+            if wav_path.exists() {
+                if let Err(e) = std::fs::remove_file(wav_path) {
+                    log::warn!("Failed to remove temp WAV {:?}: {}", wav_path, e);
+                }
+            }
+            Self::untrack_temp_wav(config, wav_path);
+        }
+        if let Some(output_path) = &job.output_path {
+            let extra_paths = job
+                .settings
+                .extra_output_formats()
+                .into_iter()
+                .map(|format| {
+                    Self::get_output_path(
+                        &job.file_path,
+                        job.source_root.as_deref(),
+                        &job.settings,
+                        &format,
+                    )
+                });
+            for output_path in std::iter::once(output_path.clone()).chain(extra_paths) {
+                if output_path.exists() {
+                    if let Err(e) = std::fs::remove_file(&output_path) {
+                        log::warn!("Failed to remove partial output {:?}: {}", output_path, e);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn list_audio_streams(&self, input_path: &Path) -> Result<Vec<AudioStreamInfo>> {
+        AudioConverter::new()?.list_audio_streams(input_path)
+    }
+
+    pub fn get_media_info(&self, input_path: &Path) -> Result<MediaInfo> {
+        AudioConverter::new()?.media_info(input_path)
+    }
+
+    /// GPU backends are read from whisper.cpp's system info, so they match the
+    /// build actually linked in.
+    pub fn get_capabilities(&self) -> Capabilities {
+        let whisper_system_info = WhisperTranscriber::system_info();
+        let cuda = WhisperTranscriber::reports_backend(&whisper_system_info, "CUDA");
+        let metal = WhisperTranscriber::reports_backend(&whisper_system_info, "Metal");
+        let audio_decoders = match AudioConverter::new() {
+            Ok(converter) => converter.audio_decoders(),
+            Err(e) => {
+                log::warn!("Cannot list audio decoders: {}", e);
+                Vec::new()
+            }
+        };
+        Capabilities {
+            gpu_available: cuda || metal,
+            cuda,
+            metal,
+            audio_decoders,
+            whisper_system_info,
+        }
+    }
+
+    pub fn get_waveform(&self, input_path: &Path, buckets: usize) -> Result<Vec<f32>> {
+        AudioConverter::new()?.waveform(input_path, buckets)
+    }
+
+    pub fn equivalent_ffmpeg_command(&self, input_path: &Path) -> String {
+        let settings = self.config.load_settings().unwrap_or_default();
+        AudioConverter::equivalent_ffmpeg_command(
+            input_path,
+            &Self::get_temp_wav_path(input_path),
+            settings.resample_quality,
+            settings.downmix,
+        )
+    }
+
+    /// Folder for temp files that belong to no job, so leftovers can be told
+    /// apart from other programs' files.
+    fn app_temp_dir() -> PathBuf {
+        std::env::temp_dir().join("whisper-tauri")
+    }
+
+    fn untrack_temp_wav(config: &ConfigManager, wav_path: &Path) {
+        if let Err(e) = config.untrack_temp_wav(wav_path) {
+            log::warn!("Failed to untrack temp WAV {:?}: {}", wav_path, e);
+        }
+    }
+
+    /// Temp WAVs nothing is using: files in the app's temp folder from earlier
+    /// sessions, and WAVs written beside inputs whose job isn't running. WAVs
+    /// a job keeps are never listed.
+    async fn stray_temp_files(&self) -> Vec<PathBuf> {
+        let in_use: HashSet<PathBuf> = self
+            .jobs
+            .lock()
+            .await
+            .values()
+            .filter(|job| {
+                job.settings.keep_wav
+                    || matches!(
+                        job.status,
+                        FileStatus::Converting | FileStatus::Transcribing
+                    )
+            })
+            .filter_map(|job| job.temp_wav_path.clone())
+            .collect();
+        let mut files: Vec<PathBuf> = self
+            .config
+            .tracked_temp_wavs()
+            .into_iter()
+            .filter(|path| path.is_file() && !in_use.contains(path))
+            .collect();
+
+        // This session's files may belong to a running refinement or comparison
+        if let Ok(entries) = std::fs::read_dir(Self::app_temp_dir()) {
+            files.extend(
+                entries
+                    .flatten()
+                    .filter(|entry| {
+                        entry
+                            .metadata()
+                            .and_then(|metadata| metadata.modified())
+                            .is_ok_and(|modified| modified < self.started_at)
+                    })
+                    .map(|entry| entry.path()),
+            );
+        }
+        files
+    }
+
+    /// How many stray temp files there are and their total size.
+    pub async fn get_temp_files_info(&self) -> TempFilesInfo {
+        let files = self.stray_temp_files().await;
+        TempFilesInfo {
+            count: files.len(),
+            bytes: files
+                .iter()
+                .filter_map(|path| std::fs::metadata(path).ok())
+                .map(|metadata| metadata.len())
+                .sum(),
+        }
+    }
+
+    /// Deletes the files `get_temp_files_info` counts, returning what was freed.
+    pub async fn clear_temp_files(&self) -> Result<TempFilesInfo> {
+        let mut freed = TempFilesInfo::default();
+        for path in self.stray_temp_files().await {
+            let bytes = std::fs::metadata(&path).map(|metadata| metadata.len());
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    freed.count += 1;
+                    freed.bytes += bytes.unwrap_or(0);
+                }
+                Err(e) => log::warn!("Failed to remove temp file {:?}: {}", path, e),
+            }
+        }
+        self.config.prune_temp_wavs()?;
+        Ok(freed)
+    }
+
+    /// `<stem>.16k.wav` next to the input, so a `.wav` input is never overwritten
+    /// by its converted copy or deleted with it.
     fn get_temp_wav_path(input_path: &Path) -> PathBuf {
-        let mut wav_path = input_path.to_path_buf();
-        wav_path.set_extension("wav");
-        wav_path
+        let stem = input_path.file_stem().unwrap_or_default().to_string_lossy();
+        input_path.with_file_name(format!("{}.16k.wav", stem))
+    }
+
+    /// Formats and writes the whole transcript, creating mirrored subfolders
+    /// as needed.
+    fn write_output(
+        output_path: &Path,
+        transcription: &Transcription,
+        job: &TranscriptionJob,
+        format: &OutputFormat,
+    ) -> Result<()> {
+        let options = FormatOptions {
+            timestamp_offset_ms: transcription.timestamp_offset_ms,
+            language: Self::output_language(&job.settings, transcription.language.as_deref()),
+            ..FormatOptions::from_settings(&job.file_path, &job.settings)
+        };
+        let text = WhisperTranscriber::format_segments(&transcription.segments, format, &options)?;
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(output_path, text)?;
+        Ok(())
+    }
+
+    /// Writes the formats after the first of `output_formats`, each to its
+    /// own output path.
+    fn write_extra_outputs(transcription: &Transcription, job: &TranscriptionJob) -> Result<()> {
+        for format in job.settings.extra_output_formats() {
+            let output_path = Self::get_output_path(
+                &job.file_path,
+                job.source_root.as_deref(),
+                &job.settings,
+                &format,
+            );
+            Self::write_output(&output_path, transcription, job, &format)?;
+        }
+        Ok(())
+    }
+
+    /// The language to write into outputs, if `include_language_in_output` is set.
+    fn output_language(settings: &TranscriptionSettings, code: Option<&str>) -> Option<Language> {
+        code.filter(|_| settings.include_language_in_output)
+            .and_then(WhisperTranscriber::language)
     }
 
-    fn get_output_path(input_path: &Path, settings: &TranscriptionSettings) -> PathBuf {
+    /// Where the `format` output of `input_path` goes. Formats differ in
+    /// extension, so each gets its own file.
+    fn get_output_path(
+        input_path: &Path,
+        source_root: Option<&Path>,
+        settings: &TranscriptionSettings,
+        format: &OutputFormat,
+    ) -> PathBuf {
         let default_dir = input_path.parent().unwrap().to_path_buf();
-        let output_dir = settings.output_dir.as_ref().unwrap_or(&default_dir);
+        let format_dir = settings.format_output_dirs.get(format);
+        let output_dir = if let Some(format_dir) = format_dir {
+            // `join` keeps an absolute folder as it is
+            default_dir.join(format_dir)
+        } else {
+            match &settings.output_mode {
+                OutputMode::SameAsInput => default_dir,
+                OutputMode::FixedDir => settings.output_dir.clone().unwrap_or(default_dir),
+                OutputMode::MirrorTree { root } => {
+                    let relative_dir = source_root
+                        .and_then(|base| default_dir.strip_prefix(base).ok())
+                        .unwrap_or(Path::new(""));
+                    root.join(relative_dir)
+                }
+            }
+        };
 
         let mut output_path = output_dir.join(input_path.file_stem().unwrap_or_default());
-        output_path.set_extension(settings.output_format.extension());
+        output_path.set_extension(format.extension());
         output_path
     }
 
+    /// Deepest folder that contains all of the given files.
+    fn common_base<'a>(mut paths: impl Iterator<Item = &'a Path>) -> Option<PathBuf> {
+        let mut base = paths.next()?.parent()?.to_path_buf();
+        for path in paths {
+            while !path.starts_with(&base) {
+                if !base.pop() {
+                    return None;
+                }
+            }
+        }
+        Some(base)
+    }
+
     pub async fn get_job_status(&self, job_id: &str) -> Option<TranscriptionJob> {
         self.jobs.lock().await.get(job_id).cloned()
     }
 
+    /// Contents of a completed job's output file. Non-text formats such as JSON
+    /// are returned as the serialized file, unchanged.
+    pub async fn read_transcript(&self, job_id: &str) -> Result<String> {
+        let output_path = self.completed_output_path(job_id).await?;
+        Ok(tokio::fs::read_to_string(output_path).await?)
+    }
+
+    /// Output file of a completed job, if it's still on disk.
+    pub async fn completed_output_path(&self, job_id: &str) -> Result<PathBuf> {
+        let job = self
+            .get_job_status(job_id)
+            .await
+            .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+        let output_path = match (job.status, job.output_path) {
+            (FileStatus::Completed, Some(output_path)) => output_path,
+            _ => return Err(anyhow!("Job has not completed: {}", job_id)),
+        };
+        if !output_path.exists() {
+            return Err(anyhow!(
+                "Output file no longer exists: {}",
+                output_path.display()
+            ));
+        }
+        Ok(output_path)
+    }
+
+    /// Segments of a completed job with times in the source, so the UI can show
+    /// cues without parsing the output, whatever its format. Once the job is
+    /// gone, e.g. after a restart, the copy saved on completion is used.
+    pub async fn get_job_segments(&self, job_id: &str) -> Result<Vec<Segment>> {
+        match self.get_job_status(job_id).await {
+            Some(job) if matches!(job.status, FileStatus::Completed) => {
+                Ok(Self::source_segments(&job))
+            }
+            Some(_) => Err(anyhow!("Job has not completed: {}", job_id)),
+            None => self
+                .config
+                .load_job_segments(job_id)?
+                .ok_or_else(|| anyhow!("Job not found: {}", job_id)),
+        }
+    }
+
+    /// The job's segments in source time; none when no speech was found.
+    fn source_segments(job: &TranscriptionJob) -> Vec<Segment> {
+        if !WhisperTranscriber::has_speech(&job.segments) {
+            return Vec::new();
+        }
+        WhisperTranscriber::offset_segments(&job.segments, job.timestamp_offset_ms)
+    }
+
+    /// Saves the job's segments for `get_job_segments`.
+    fn save_job_segments(config: &ConfigManager, job: &TranscriptionJob) {
+        if let Err(e) = config.save_job_segments(&job.id, &Self::source_segments(job)) {
+            log::warn!("Failed to save segments of {:?}: {}", job.file_path, e);
+        }
+    }
+
+    /// Cues of a completed job's SRT or VTT output.
+    pub async fn get_job_cues(&self, job_id: &str) -> Result<ParsedSubtitles> {
+        let job = self
+            .get_job_status(job_id)
+            .await
+            .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+        let transcript = self.read_transcript(job_id).await?;
+        subtitles::parse(&transcript, job.settings.output_format())
+    }
+
+    /// Replaces a completed job's output with edited text. Edited SRT and VTT
+    /// must still parse cleanly. The text is written to a temp file first and
+    /// renamed over the output, so a failed save leaves the original intact.
+    pub async fn save_edited_transcript(&self, job_id: &str, content: &str) -> Result<()> {
+        let job = self
+            .get_job_status(job_id)
+            .await
+            .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+        let output_path = match (&job.status, &job.output_path) {
+            (FileStatus::Completed, Some(output_path)) => output_path,
+            _ => return Err(anyhow!("Job has not completed: {}", job_id)),
+        };
+
+        let format = job.settings.output_format();
+        if matches!(format, OutputFormat::Srt | OutputFormat::Vtt) {
+            let parsed = subtitles::parse(content, format)?;
+            if !parsed.errors.is_empty() {
+                return Err(anyhow!(
+                    "Transcript has malformed cues:\n{}",
+                    parsed.errors.join("\n")
+                ));
+            }
+        }
+
+        let mut temp_path = output_path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        tokio::fs::write(&temp_path, content).await?;
+        if let Err(e) = tokio::fs::rename(&temp_path, output_path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    /// Writes a completed job's transcript to a path of the user's choosing
+    /// instead of the computed output path.
+    pub async fn save_transcript(&self, job_id: &str, target_path: &Path) -> Result<()> {
+        let dir = target_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .ok_or_else(|| anyhow!("Invalid save location: {:?}", target_path))?;
+        let writable = tokio::fs::metadata(dir)
+            .await
+            .is_ok_and(|metadata| metadata.is_dir() && !metadata.permissions().readonly());
+        if !writable {
+            return Err(anyhow!("Folder is not writable: {:?}", dir));
+        }
+
+        let transcript = self.read_transcript(job_id).await?;
+        tokio::fs::write(target_path, transcript).await?;
+        Ok(())
+    }
+
+    /// Writes a completed job's transcript again in another format, next to its
+    /// output, and returns the new file's path.
+    pub async fn reexport_job(&self, job_id: &str, format: OutputFormat) -> Result<PathBuf> {
+        let job = self
+            .get_job_status(job_id)
+            .await
+            .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+        if !matches!(job.status, FileStatus::Completed) {
+            return Err(anyhow!("Job has not completed: {}", job_id));
+        }
+
+        let settings = &job.settings;
+        let options = FormatOptions {
+            timestamp_offset_ms: job.timestamp_offset_ms,
+            language: Self::output_language(settings, job.detected_language.as_deref()),
+            ..FormatOptions::from_settings(&job.file_path, settings)
+        };
+        let text = WhisperTranscriber::format_segments(&job.segments, &format, &options)?;
+        let output_path = Self::get_output_path(
+            &job.file_path,
+            job.source_root.as_deref(),
+            settings,
+            &format,
+        );
+        tokio::fs::write(&output_path, text).await?;
+        Ok(output_path)
+    }
+
+    /// Transcribes `start_secs..end_secs` of a completed job's source again with
+    /// `model_name`, for redoing a poorly transcribed stretch with a bigger
+    /// model. Times in the returned segments refer to the source. The job and
+    /// its output are left as they are until `apply_refined_range`.
+    pub async fn refine_range(
+        &self,
+        job_id: &str,
+        start_secs: f64,
+        end_secs: f64,
+        model_name: &str,
+    ) -> Result<Vec<Segment>> {
+        if !(start_secs >= 0.0 && end_secs > start_secs) {
+            return Err(anyhow!("Invalid range: {}s to {}s", start_secs, end_secs));
+        }
+        if !self.config.get_model_path(model_name).exists() {
+            return Err(AppError::ModelNotDownloaded(format!(
+                "Model not downloaded: {}",
+                model_name
+            ))
+            .into());
+        }
+        let job = self.completed_job(job_id).await?;
+        let mut settings = job.settings.clone();
+        settings.model = model_name.to_string();
+        let range_ms = (start_secs * 1000.0) as u64..(end_secs * 1000.0) as u64;
+
+        // Conversion and whisper both block, so keep them off the async workers
+        tokio::task::spawn_blocking(move || {
+            let wav_path =
+                Self::convert_to_temp_wav(&job.file_path, job.audio_stream_index, &settings)?;
+            let transcription =
+                WhisperTranscriber::new().transcribe_range(&wav_path, &settings, range_ms);
+            let _ = std::fs::remove_file(&wav_path);
+
+            let transcription = transcription?;
+            Ok(WhisperTranscriber::offset_segments(
+                &transcription.segments,
+                transcription.timestamp_offset_ms,
+            ))
+        })
+        .await?
+    }
+
+    /// Transcribes `file_path` with each of two models and counts the words
+    /// that differ, for judging whether a bigger model is worth its time. The
+    /// file is converted once for both.
+    pub async fn compare_models(
+        &self,
+        file_path: &Path,
+        models: &[String],
+        settings: &TranscriptionSettings,
+    ) -> Result<ModelComparison> {
+        if models.len() != 2 {
+            return Err(anyhow!("Pick two models to compare"));
+        }
+        for model in models {
+            if !self.config.get_model_path(model).exists() {
+                return Err(AppError::ModelNotDownloaded(format!(
+                    "Model not downloaded: {}",
+                    model
+                ))
+                .into());
+            }
+        }
+        let file_path = file_path.to_path_buf();
+        let models = models.to_vec();
+        let settings = settings.clone();
+
+        tokio::task::spawn_blocking(move || {
+            let wav_path = Self::convert_to_temp_wav(&file_path, None, &settings)?;
+            let transcripts = models
+                .iter()
+                .map(|model| {
+                    let settings = TranscriptionSettings {
+                        model: model.clone(),
+                        ..settings.clone()
+                    };
+                    let started = std::time::Instant::now();
+                    let transcription = WhisperTranscriber::new()
+                        .transcribe_segments(&wav_path, &settings, None, None)?;
+                    let segments = WhisperTranscriber::offset_segments(
+                        &transcription.segments,
+                        transcription.timestamp_offset_ms,
+                    );
+                    Ok(ModelTranscript {
+                        model: model.clone(),
+                        text: Self::transcript_text(&segments),
+                        segments,
+                        elapsed_secs: started.elapsed().as_secs_f64(),
+                    })
+                })
+                .collect::<Result<Vec<_>>>();
+            let _ = std::fs::remove_file(&wav_path);
+
+            let transcripts = transcripts?;
+            let (insertions, deletions) =
+                Self::word_diff(&transcripts[0].text, &transcripts[1].text);
+            Ok(ModelComparison {
+                transcripts,
+                insertions,
+                deletions,
+            })
+        })
+        .await?
+    }
+
+    /// Converts the file to a temporary WAV the way a job with `settings`
+    /// would. The caller removes it. Blocks.
+    fn convert_to_temp_wav(
+        file_path: &Path,
+        audio_stream_index: Option<usize>,
+        settings: &TranscriptionSettings,
+    ) -> Result<PathBuf> {
+        let temp_dir = Self::app_temp_dir();
+        std::fs::create_dir_all(&temp_dir)?;
+        let wav_path = temp_dir.join(format!("{}.wav", Uuid::new_v4()));
+        let converted = AudioConverter::new().and_then(|converter| {
+            converter
+                .with_pre_gain_db(settings.pre_gain_db)
+                .with_resample_quality(settings.resample_quality)
+                .with_downmix(settings.downmix)
+                .with_stall_timeout(settings.conversion_timeout_secs)
+                .with_audio_stream(audio_stream_index)
+                .convert_to_wav(file_path, &wav_path, &AtomicBool::new(false), |_| {})
+        });
+        match converted {
+            Ok(()) => Ok(wav_path),
+            Err(e) => {
+                let _ = std::fs::remove_file(&wav_path);
+                Err(AppError::ConversionFailed(format!("Conversion failed: {}", e)).into())
+            }
+        }
+    }
+
+    fn transcript_text(segments: &[Segment]) -> String {
+        segments
+            .iter()
+            .map(|segment| segment.text.trim())
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Words inserted and deleted going from `before` to `after`, ignoring
+    /// case and punctuation. Counts what's outside their longest common
+    /// subsequence, kept to one row of the table so long transcripts fit.
+    fn word_diff(before: &str, after: &str) -> (usize, usize) {
+        let before = WhisperTranscriber::repetition_key(before);
+        let after = WhisperTranscriber::repetition_key(after);
+        let before: Vec<&str> = before.split_whitespace().collect();
+        let after: Vec<&str> = after.split_whitespace().collect();
+
+        let mut previous = vec![0usize; after.len() + 1];
+        let mut current = vec![0usize; after.len() + 1];
+        for word in &before {
+            for (j, other) in after.iter().enumerate() {
+                current[j + 1] = if word == other {
+                    previous[j] + 1
+                } else {
+                    previous[j + 1].max(current[j])
+                };
+            }
+            std::mem::swap(&mut previous, &mut current);
+        }
+        let common = previous[after.len()];
+        (after.len() - common, before.len() - common)
+    }
+
+    /// Replaces the job's segments overlapping `start_secs..end_secs` with
+    /// `segments` from `refine_range` and rewrites its outputs.
+    pub async fn apply_refined_range(
+        &self,
+        job_id: &str,
+        start_secs: f64,
+        end_secs: f64,
+        segments: Vec<Segment>,
+    ) -> Result<TranscriptionJob> {
+        let mut job = self.completed_job(job_id).await?;
+        let output_path = job
+            .output_path
+            .clone()
+            .ok_or_else(|| anyhow!("Job has no output file: {}", job_id))?;
+
+        // Stored segments are relative to the audio whisper saw
+        let range = ((start_secs * 1000.0) as i64 - job.timestamp_offset_ms) / 10
+            ..((end_secs * 1000.0) as i64 - job.timestamp_offset_ms) / 10;
+        let replacement = WhisperTranscriber::offset_segments(&segments, -job.timestamp_offset_ms);
+        job.segments = Self::splice_segments(&job.segments, range, replacement);
+
+        let transcription = Transcription {
+            segments: job.segments.clone(),
+            timestamp_offset_ms: job.timestamp_offset_ms,
+            language: job.detected_language.clone(),
+            // Only the raw sidecar reads it, and a refine doesn't rewrite that
+            word_timestamps: true,
+        };
+        Self::write_output(
+            &output_path,
+            &transcription,
+            &job,
+            job.settings.output_format(),
+        )?;
+        Self::write_extra_outputs(&transcription, &job)?;
+
+        Self::save_job_segments(&self.config, &job);
+        let mut jobs = self.jobs.lock().await;
+        if let Some(stored) = jobs.get_mut(job_id) {
+            stored.segments = job.segments.clone();
+        }
+        Ok(job)
+    }
+
+    /// `segments` without those overlapping `range`, with `replacement` in
+    /// their place. Times are in centiseconds.
+    fn splice_segments(
+        segments: &[Segment],
+        range: std::ops::Range<i64>,
+        replacement: Vec<Segment>,
+    ) -> Vec<Segment> {
+        let mut spliced: Vec<Segment> = segments
+            .iter()
+            .filter(|segment| segment.end <= range.start || segment.start >= range.end)
+            .cloned()
+            .chain(replacement)
+            .collect();
+        spliced.sort_by_key(|segment| segment.start);
+        spliced
+    }
+
+    async fn completed_job(&self, job_id: &str) -> Result<TranscriptionJob> {
+        let job = self
+            .get_job_status(job_id)
+            .await
+            .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+        if !matches!(job.status, FileStatus::Completed) {
+            return Err(anyhow!("Job has not completed: {}", job_id));
+        }
+        Ok(job)
+    }
+
+    /// Every job, in queue order.
+    async fn ordered_jobs(&self) -> Vec<TranscriptionJob> {
+        let order = self
+            .job_order
+            .lock()
+            .map(|order| order.clone())
+            .unwrap_or_default();
+        let jobs = self.jobs.lock().await;
+
+        let mut ordered: Vec<TranscriptionJob> = order
+            .iter()
+            .filter_map(|id| jobs.get(id).cloned())
+            .collect();
+        // Jobs missing from the order, if any, go last
+        ordered.extend(
+            jobs.values()
+                .filter(|job| !order.contains(&job.id))
+                .cloned(),
+        );
+        ordered
+    }
+
+    /// Moves a job to `new_index` in the queue, clamped to its end. Queued jobs
+    /// are started in queue order.
+    pub fn move_job(&self, job_id: &str, new_index: usize) -> Result<()> {
+        let mut order = self
+            .job_order
+            .lock()
+            .map_err(|_| anyhow!("Job queue is unavailable"))?;
+        let index = order
+            .iter()
+            .position(|id| id == job_id)
+            .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+        let id = order.remove(index);
+        let new_index = new_index.min(order.len());
+        order.insert(new_index, id);
+        Ok(())
+    }
+
+    /// Every job in the order it was queued, so the list doesn't jump around.
     pub async fn get_all_jobs(&self) -> Vec<TranscriptionJob> {
-        self.jobs.lock().await.values().cloned().collect()
+        self.ordered_jobs().await
+    }
+
+    /// Job counts and overall progress, sent as the `queue-status` event.
+    pub async fn queue_status(&self) -> QueueStatus {
+        let jobs = self.jobs.lock().await;
+        Self::summarize_queue(jobs.values())
+    }
+
+    fn summarize_queue<'a>(jobs: impl Iterator<Item = &'a TranscriptionJob>) -> QueueStatus {
+        let (mut running, mut queued, mut completed, mut failed) = (0, 0, 0, 0);
+        let mut progress_total = 0.0;
+        for job in jobs {
+            match job.status {
+                FileStatus::Converting | FileStatus::Transcribing => running += 1,
+                FileStatus::Pending | FileStatus::Queued | FileStatus::Paused => queued += 1,
+                FileStatus::Completed => completed += 1,
+                FileStatus::Error => failed += 1,
+                FileStatus::Cancelled => continue,
+            }
+            // Failed jobs are finished too, so they count as done
+            progress_total += match job.status {
+                FileStatus::Completed | FileStatus::Error => 100.0,
+                _ => job.progress.clamp(0.0, 100.0),
+            };
+        }
+        let counted = running + queued + completed + failed;
+        let overall_percent = if counted == 0 {
+            0
+        } else {
+            (progress_total / counted as f32).round() as u32
+        };
+
+        let summary = if running + queued > 0 {
+            let mut parts = Vec::new();
+            if running > 0 {
+                parts.push(format!("{} running", running));
+            }
+            if queued > 0 {
+                parts.push(format!("{} queued", queued));
+            }
+            if failed > 0 {
+                parts.push(format!("{} failed", failed));
+            }
+            parts.push(format!("{}%", overall_percent));
+            parts.join(", ")
+        } else if failed > 0 {
+            format!("{} completed, {} failed", completed, failed)
+        } else if completed > 0 {
+            format!("{} completed", completed)
+        } else {
+            "Idle".to_string()
+        };
+
+        QueueStatus {
+            running,
+            queued,
+            completed,
+            failed,
+            overall_percent,
+            summary,
+        }
     }
 
     pub async fn cancel_job(&self, job_id: &str) -> Result<()> {
         // Cancel the task if it's running
-        let mut tasks = self.active_tasks.lock().await;
-        if let Some(handle) = tasks.remove(job_id) {
-            handle.abort();
-        }
+        self.abort_task(job_id).await;
 
         // Update job status
         let mut jobs = self.jobs.lock().await;
         if let Some(job) = jobs.get_mut(job_id) {
-            job.status = FileStatus::Error;
-            job.error = Some("Cancelled by user".to_string());
+            if matches!(job.status, FileStatus::Completed) {
+                return Ok(());
+            }
+            job.status = FileStatus::Cancelled;
+            job.error = None;
+
+            Self::remove_job_files(&self.config, job);
+            job.temp_wav_path = None;
+            job.output_path = None;
+            Self::send_progress(job, self.progress_sender.as_ref());
         }
 
         Ok(())
     }
 
+    /// Stops every unfinished job, keeping completed jobs and their outputs, and
+    /// returns the updated job list.
+    pub async fn cancel_all_jobs(&self) -> Vec<TranscriptionJob> {
+        // Marked first so the queue can't claim a job while tasks are aborted
+        let cancelled: Vec<String> = {
+            let mut jobs = self.jobs.lock().await;
+            jobs.values_mut()
+                .filter(|job| {
+                    matches!(
+                        job.status,
+                        FileStatus::Pending
+                            | FileStatus::Queued
+                            | FileStatus::Paused
+                            | FileStatus::Converting
+                            | FileStatus::Transcribing
+                    )
+                })
+                .map(|job| {
+                    job.status = FileStatus::Cancelled;
+                    job.error = None;
+                    job.id.clone()
+                })
+                .collect()
+        };
+
+        for job_id in &cancelled {
+            self.abort_task(job_id).await;
+        }
+
+        let mut jobs = self.jobs.lock().await;
+        for job_id in &cancelled {
+            if let Some(job) = jobs.get_mut(job_id) {
+                Self::remove_job_files(&self.config, job);
+                job.temp_wav_path = None;
+                job.output_path = None;
+                Self::send_progress(job, self.progress_sender.as_ref());
+            }
+        }
+        drop(jobs);
+
+        self.ordered_jobs().await
+    }
+
+    /// Queues every failed job again and returns the updated job list. Jobs
+    /// that completed or are still running are left alone.
+    pub async fn retry_all_failed(&self) -> Vec<TranscriptionJob> {
+        let retried: Vec<StageLimits> = {
+            let mut jobs = self.jobs.lock().await;
+            jobs.values_mut()
+                .filter(|job| matches!(job.status, FileStatus::Error))
+                .map(|job| {
+                    job.attempt += 1;
+                    job.status = FileStatus::Queued;
+                    job.progress = 0.0;
+                    job.error = None;
+                    job.output_path = None;
+                    job.notice = None;
+                    job.detected_language = None;
+                    Self::send_progress(job, self.progress_sender.as_ref());
+                    StageLimits::new(&job.settings)
+                })
+                .collect()
+        };
+
+        if let Some(limits) = retried.into_iter().reduce(StageLimits::max) {
+            let manager = self.clone();
+            tokio::spawn(async move {
+                manager.process_jobs(limits).await;
+            });
+        }
+
+        self.ordered_jobs().await
+    }
+
+    /// Switches a job to another model. Jobs that aren't running are updated in
+    /// place; a running job is stopped and queued again with the new model.
+    pub async fn change_job_model(
+        &self,
+        job_id: &str,
+        model_name: &str,
+    ) -> Result<TranscriptionJob> {
+        if !self.config.get_model_path(model_name).exists() {
+            return Err(AppError::ModelNotDownloaded(format!(
+                "Model not downloaded: {}",
+                model_name
+            ))
+            .into());
+        }
+
+        let status = self
+            .jobs
+            .lock()
+            .await
+            .get(job_id)
+            .map(|job| job.status.clone())
+            .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+
+        let requeue = match status {
+            FileStatus::Pending
+            | FileStatus::Queued
+            | FileStatus::Paused
+            | FileStatus::Error
+            | FileStatus::Cancelled => false,
+            FileStatus::Converting | FileStatus::Transcribing => true,
+            FileStatus::Completed => {
+                return Err(anyhow!("Job already completed: {}", job_id));
+            }
+        };
+
+        if requeue {
+            self.abort_task(job_id).await;
+        }
+
+        let updated_job = {
+            let mut jobs = self.jobs.lock().await;
+            let job = jobs
+                .get_mut(job_id)
+                .ok_or_else(|| anyhow!("Job not found: {}", job_id))?;
+            job.settings.model = model_name.to_string();
+
+            if requeue {
+                // The previous run notices the new attempt and leaves the job alone
+                job.attempt += 1;
+                job.status = FileStatus::Queued;
+                job.progress = 0.0;
+                job.error = None;
+                job.output_path = None;
+                job.notice = None;
+                job.detected_language = None;
+            }
+            job.clone()
+        };
+
+        if requeue {
+            let manager = self.clone();
+            let limits = StageLimits::new(&updated_job.settings);
+            tokio::spawn(async move {
+                manager.process_jobs(limits).await;
+            });
+        }
+
+        Ok(updated_job)
+    }
+
+    async fn abort_task(&self, job_id: &str) {
+        let task = self.active_tasks.lock().await.remove(job_id);
+        if let Some(task) = task {
+            task.cancel.store(true, Ordering::SeqCst);
+            task.abort.abort();
+        }
+    }
+
+    /// Starts transcribing the default input device with the saved model and
+    /// language. Blocks until the model is loaded and the device is open.
+    pub fn start_live_transcription(
+        &self,
+        on_update: impl Fn(LiveTranscriptUpdate) + Send + Sync + 'static,
+    ) -> Result<()> {
+        let mut live = self
+            .live
+            .lock()
+            .map_err(|_| anyhow!("Live transcription state is unavailable"))?;
+        if live.is_some() {
+            return Err(anyhow!("Live transcription is already running"));
+        }
+
+        let settings = self.config.load_settings()?;
+        let model_path = self.config.get_model_path(&settings.model);
+        if !model_path.exists() {
+            return Err(AppError::ModelNotDownloaded(format!(
+                "Model not downloaded: {}",
+                settings.model
+            ))
+            .into());
+        }
+
+        let language =
+            WhisperTranscriber::resolve_language(&settings.model, settings.language.as_deref())?;
+        *live = Some(LiveTranscriber::start(
+            model_path,
+            language,
+            settings.carry_context,
+            on_update,
+        )?);
+        Ok(())
+    }
+
+    pub fn is_live_transcription_running(&self) -> bool {
+        self.live.lock().map(|live| live.is_some()).unwrap_or(false)
+    }
+
+    /// Stops live transcription, blocking until the last window is reported.
+    pub fn stop_live_transcription(&self) -> Result<()> {
+        let live = self
+            .live
+            .lock()
+            .map_err(|_| anyhow!("Live transcription state is unavailable"))?
+            .take();
+        match live {
+            Some(live) => {
+                live.stop();
+                Ok(())
+            }
+            None => Err(anyhow!("Live transcription is not running")),
+        }
+    }
+
     pub async fn clear_completed_jobs(&self) {
         let mut jobs = self.jobs.lock().await;
-        jobs.retain(|_, job| !matches!(job.status, FileStatus::Completed));
+        jobs.retain(|_, job| {
+            let completed = matches!(job.status, FileStatus::Completed);
+            if completed {
+                self.release_path(&job.file_path);
+                self.config.remove_job_segments(&job.id);
+            }
+            !completed
+        });
+        if let Ok(mut order) = self.job_order.lock() {
+            order.retain(|id| jobs.contains_key(id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A manager over a fresh temp config folder, leaving the user's alone.
+    fn temp_manager() -> TranscriptionManager {
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        TranscriptionManager::with_config(ConfigManager::with_config_dir(dir).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_cancel_job_removes_temp_wav() {
+        let manager = temp_manager();
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let input = dir.join("recording.mp3");
+        let temp_wav = TranscriptionManager::get_temp_wav_path(&input);
+        fs::write(&temp_wav, b"RIFF").unwrap();
+
+        let job = TranscriptionJob {
+            id: "cancel-test".to_string(),
+            file_path: input,
+            settings: TranscriptionSettings::default(),
+            status: FileStatus::Transcribing,
+            progress: 30.0,
+            error: None,
+            output_path: None,
+            temp_wav_path: Some(temp_wav.clone()),
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: Vec::new(),
+            timestamp_offset_ms: 0,
+            detected_language: None,
+            notice: None,
+        };
+        manager.jobs.lock().await.insert(job.id.clone(), job);
+        manager
+            .config
+            .save_job_segments("cancel-test", &[])
+            .unwrap();
+
+        manager.cancel_job("cancel-test").await.unwrap();
+
+        assert!(!temp_wav.exists(), "Temp WAV was left on disk");
+        assert!(manager
+            .config
+            .load_job_segments("cancel-test")
+            .unwrap()
+            .is_none());
+        let job = manager.get_job_status("cancel-test").await.unwrap();
+        assert!(TranscriptionManager::is_cancelled(&job));
+        assert!(job.temp_wav_path.is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_temp_wav_never_replaces_wav_input() {
+        let manager = temp_manager();
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let input = dir.join("recording.wav");
+        let original = b"RIFF original recording".to_vec();
+        fs::write(&input, &original).unwrap();
+        let temp_wav = TranscriptionManager::get_temp_wav_path(&input);
+        assert_eq!(temp_wav, dir.join("recording.16k.wav"));
+        fs::write(&temp_wav, b"RIFF converted").unwrap();
+
+        let job = TranscriptionJob {
+            id: "wav-input-test".to_string(),
+            file_path: input.clone(),
+            settings: TranscriptionSettings::default(),
+            status: FileStatus::Transcribing,
+            progress: 30.0,
+            error: None,
+            output_path: None,
+            temp_wav_path: Some(temp_wav.clone()),
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: Vec::new(),
+            timestamp_offset_ms: 0,
+            detected_language: None,
+            notice: None,
+        };
+        manager.jobs.lock().await.insert(job.id.clone(), job);
+
+        // Cancelling deletes the converted copy, which must not be the input
+        manager.cancel_job("wav-input-test").await.unwrap();
+
+        assert!(!temp_wav.exists());
+        assert_eq!(fs::read(&input).unwrap(), original);
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_whisper_wav_input_is_not_converted() {
+        let manager = temp_manager();
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let input = dir.join("recording.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&input, spec).unwrap();
+        for _ in 0..32000 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let job = TranscriptionJob {
+            id: "whisper-wav-test".to_string(),
+            file_path: input.clone(),
+            settings: TranscriptionSettings {
+                keep_wav: true,
+                ..TranscriptionSettings::default()
+            },
+            status: FileStatus::Queued,
+            progress: 0.0,
+            error: None,
+            output_path: None,
+            temp_wav_path: None,
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: Vec::new(),
+            timestamp_offset_ms: 0,
+            detected_language: None,
+            notice: None,
+        };
+        manager.jobs.lock().await.insert(job.id.clone(), job);
+        manager
+            .job_order
+            .lock()
+            .unwrap()
+            .push("whisper-wav-test".to_string());
+
+        manager
+            .process_jobs(StageLimits::new(&TranscriptionSettings::default()))
+            .await;
+
+        let job = manager.get_job_status("whisper-wav-test").await.unwrap();
+        assert!(
+            matches!(job.status, FileStatus::Completed),
+            "{:?}",
+            job.error
+        );
+        assert!(job.temp_wav_path.is_none());
+        assert!(!TranscriptionManager::get_temp_wav_path(&input).exists());
+        assert!(input.exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    async fn queue_job(manager: &TranscriptionManager, id: &str) {
+        let job = TranscriptionJob {
+            id: id.to_string(),
+            file_path: PathBuf::from(format!("{}.mp3", id)),
+            settings: TranscriptionSettings::default(),
+            status: FileStatus::Queued,
+            progress: 0.0,
+            error: None,
+            output_path: None,
+            temp_wav_path: None,
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: Vec::new(),
+            timestamp_offset_ms: 0,
+            detected_language: None,
+            notice: None,
+        };
+        manager.jobs.lock().await.insert(job.id.clone(), job);
+        manager.job_order.lock().unwrap().push(id.to_string());
+    }
+
+    #[tokio::test]
+    async fn test_get_all_jobs_keeps_queue_order() {
+        let manager = temp_manager();
+        let ids: Vec<String> = (0..20).map(|i| format!("job-{}", i)).collect();
+        for id in &ids {
+            queue_job(&manager, id).await;
+        }
+
+        let listed: Vec<String> = manager
+            .get_all_jobs()
+            .await
+            .into_iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(listed, ids);
+
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cancel_all_jobs_keeps_completed() {
+        let manager = temp_manager();
+        for id in ["done", "next", "last"] {
+            queue_job(&manager, id).await;
+        }
+        manager.jobs.lock().await.get_mut("done").unwrap().status = FileStatus::Completed;
+
+        let statuses: Vec<(String, FileStatus)> = manager
+            .cancel_all_jobs()
+            .await
+            .into_iter()
+            .map(|job| (job.id, job.status))
+            .collect();
+        assert!(matches!(statuses[0], (ref id, FileStatus::Completed) if id == "done"));
+        assert!(statuses[1..]
+            .iter()
+            .all(|(_, status)| matches!(status, FileStatus::Cancelled)));
+
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_queue_status_summary() {
+        let manager = temp_manager();
+        assert_eq!(manager.queue_status().await.summary, "Idle");
+
+        for id in ["done", "running", "next", "later", "dropped"] {
+            queue_job(&manager, id).await;
+        }
+        {
+            let mut jobs = manager.jobs.lock().await;
+            jobs.get_mut("done").unwrap().status = FileStatus::Completed;
+            let running = jobs.get_mut("running").unwrap();
+            running.status = FileStatus::Transcribing;
+            running.progress = 60.0;
+            jobs.get_mut("dropped").unwrap().status = FileStatus::Cancelled;
+        }
+
+        let status = manager.queue_status().await;
+        assert_eq!((status.running, status.queued, status.completed), (1, 2, 1));
+        assert_eq!(status.overall_percent, 40);
+        assert_eq!(status.summary, "1 running, 2 queued, 40%");
+
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_passes_process_each_job_once() {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let mut manager = temp_manager();
+        manager.set_progress_sender(sender);
+        let config_dir = manager.config.get_config_dir().clone();
+        for id in ["a", "b", "c"] {
+            queue_job(&manager, id).await;
+        }
+
+        // As if Start were clicked twice in quick succession
+        let limits = StageLimits {
+            conversion: 2,
+            transcription: 2,
+        };
+        tokio::join!(manager.process_jobs(limits), manager.process_jobs(limits));
+        drop(manager);
+
+        let mut claims: HashMap<String, usize> = HashMap::new();
+        while let Some(update) = receiver.recv().await {
+            if matches!(update.status, FileStatus::Converting) && update.progress == 0.0 {
+                *claims.entry(update.file_id).or_default() += 1;
+            }
+        }
+        assert_eq!(claims.len(), 3);
+        assert!(claims.values().all(|count| *count == 1));
+
+        fs::remove_dir_all(&config_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_retry_all_failed_requeues_only_failed_jobs() {
+        let manager = temp_manager();
+        for id in ["done", "failed", "running"] {
+            queue_job(&manager, id).await;
+        }
+        {
+            let mut jobs = manager.jobs.lock().await;
+            jobs.get_mut("done").unwrap().status = FileStatus::Completed;
+            let failed = jobs.get_mut("failed").unwrap();
+            failed.status = FileStatus::Error;
+            failed.error = Some("Disk full".to_string());
+            jobs.get_mut("running").unwrap().status = FileStatus::Transcribing;
+        }
+
+        let jobs = manager.retry_all_failed().await;
+        assert!(matches!(jobs[0].status, FileStatus::Completed));
+        assert!(matches!(jobs[1].status, FileStatus::Queued));
+        assert_eq!(jobs[1].error, None);
+        assert_eq!(jobs[1].attempt, 1);
+        assert!(matches!(jobs[2].status, FileStatus::Transcribing));
+        assert_eq!(jobs[2].attempt, 0);
+
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_combine_segments_offsets_by_previous_durations() {
+        let clip = |id: &str, start: i64, trimmed_ms: i64| TranscriptionJob {
+            id: id.to_string(),
+            file_path: PathBuf::from(format!("{}.mp3", id)),
+            settings: TranscriptionSettings::default(),
+            status: FileStatus::Completed,
+            progress: 100.0,
+            error: None,
+            output_path: None,
+            temp_wav_path: None,
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: vec![Segment {
+                start,
+                end: start + 100,
+                text: id.to_string(),
+                tokens: Vec::new(),
+                language: None,
+            }],
+            timestamp_offset_ms: trimmed_ms,
+            detected_language: None,
+            notice: None,
+        };
+        let first = clip("first", 50, 0);
+        let second = clip("second", 0, 2000);
+
+        let combined = TranscriptionManager::combine_segments(&[(&first, 12.5), (&second, 30.0)]);
+        let times: Vec<(i64, i64)> = combined.iter().map(|s| (s.start, s.end)).collect();
+        // Centiseconds: the second clip starts 12.5s in, plus its trimmed 2s
+        assert_eq!(times, vec![(50, 150), (1450, 1550)]);
+        assert_eq!(combined[1].text, "second");
+    }
+
+    #[test]
+    fn test_splice_segments_replaces_overlapping_range() {
+        let segment = |start: i64, end: i64, text: &str| Segment {
+            start,
+            end,
+            text: text.to_string(),
+            tokens: Vec::new(),
+            language: None,
+        };
+        let segments = vec![
+            segment(0, 200, "before"),
+            segment(200, 450, "garbled"),
+            segment(450, 600, "also garbled"),
+            segment(600, 800, "after"),
+        ];
+
+        let spliced = TranscriptionManager::splice_segments(
+            &segments,
+            300..500,
+            vec![segment(200, 400, "fixed"), segment(400, 600, "words")],
+        );
+        let texts: Vec<&str> = spliced.iter().map(|s| s.text.as_str()).collect();
+        assert_eq!(texts, vec!["before", "fixed", "words", "after"]);
+    }
+
+    #[test]
+    fn test_word_diff_ignores_case_and_punctuation() {
+        assert_eq!(
+            TranscriptionManager::word_diff(
+                "Hello, world. This is a test.",
+                "hello world this was a test"
+            ),
+            (1, 1)
+        );
+        assert_eq!(TranscriptionManager::word_diff("", "two words"), (2, 0));
+        assert_eq!(TranscriptionManager::word_diff("same", "Same!"), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_move_job_reorders_queue() {
+        let manager = temp_manager();
+        for id in ["a", "b", "c"] {
+            queue_job(&manager, id).await;
+        }
+
+        manager.move_job("c", 0).unwrap();
+        manager.move_job("a", 10).unwrap();
+        assert!(manager.move_job("missing", 0).is_err());
+
+        let order: Vec<String> = manager
+            .ordered_jobs()
+            .await
+            .into_iter()
+            .map(|job| job.id)
+            .collect();
+        assert_eq!(order, vec!["c", "b", "a"]);
+
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_save_edited_transcript_rejects_malformed_srt() {
+        let manager = temp_manager();
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("talk.srt");
+        let original = "1\n00:00:00,000 --> 00:00:01,000\nHelo\n";
+        fs::write(&output, original).unwrap();
+
+        let job = TranscriptionJob {
+            id: "edit-test".to_string(),
+            file_path: dir.join("talk.mp3"),
+            settings: TranscriptionSettings::default(),
+            status: FileStatus::Completed,
+            progress: 100.0,
+            error: None,
+            output_path: Some(output.clone()),
+            temp_wav_path: None,
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: Vec::new(),
+            timestamp_offset_ms: 0,
+            detected_language: None,
+            notice: None,
+        };
+        manager.jobs.lock().await.insert(job.id.clone(), job);
+
+        let broken = "1\n00:00:00,000 -> 00:00:01,000\nHello\n";
+        assert!(manager
+            .save_edited_transcript("edit-test", broken)
+            .await
+            .is_err());
+        assert_eq!(fs::read_to_string(&output).unwrap(), original);
+
+        let fixed = "1\n00:00:00,000 --> 00:00:01,000\nHello\n";
+        manager
+            .save_edited_transcript("edit-test", fixed)
+            .await
+            .unwrap();
+        assert_eq!(manager.read_transcript("edit-test").await.unwrap(), fixed);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_add_files_skips_queued_paths() {
+        let manager = temp_manager();
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let input = dir.join("meeting.mp3");
+        fs::write(&input, b"ID3").unwrap();
+
+        let first = manager.add_files(vec![input.clone()]).await;
+        assert_eq!(first.files.len(), 1);
+        assert_eq!(first.duplicates, 0);
+
+        // The same file through a different spelling of its path
+        let second = manager
+            .add_files(vec![dir.join(".").join("meeting.mp3")])
+            .await;
+        assert!(second.files.is_empty());
+        assert_eq!(second.duplicates, 1);
+
+        // Once its job is cancelled the file can be queued again
+        let job = TranscriptionJob {
+            id: first.files[0].id.clone(),
+            file_path: input.clone(),
+            settings: TranscriptionSettings::default(),
+            status: FileStatus::Cancelled,
+            progress: 0.0,
+            error: None,
+            output_path: None,
+            temp_wav_path: None,
+            audio_stream_index: None,
+            source_root: None,
+            attempt: 0,
+            segments: Vec::new(),
+            timestamp_offset_ms: 0,
+            detected_language: None,
+            notice: None,
+        };
+        manager.jobs.lock().await.insert(job.id.clone(), job);
+        let third = manager.add_files(vec![input]).await;
+        assert_eq!(third.files.len(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(manager.config.get_config_dir()).unwrap();
+    }
+
+    #[test]
+    fn test_mirror_tree_output_path() {
+        let inputs = [
+            PathBuf::from("/archive/2023/january/interview.mp3"),
+            PathBuf::from("/archive/2023/march/talks/keynote.mp4"),
+            PathBuf::from("/archive/2023/notes.wav"),
+        ];
+        let source_root = TranscriptionManager::common_base(inputs.iter().map(|p| p.as_path()));
+        assert_eq!(source_root, Some(PathBuf::from("/archive/2023")));
+
+        let settings = TranscriptionSettings {
+            output_mode: OutputMode::MirrorTree {
+                root: PathBuf::from("/transcripts"),
+            },
+            ..TranscriptionSettings::default()
+        };
+        let outputs: Vec<PathBuf> = inputs
+            .iter()
+            .map(|input| {
+                TranscriptionManager::get_output_path(
+                    input,
+                    source_root.as_deref(),
+                    &settings,
+                    settings.output_format(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                PathBuf::from("/transcripts/january/interview.srt"),
+                PathBuf::from("/transcripts/march/talks/keynote.srt"),
+                PathBuf::from("/transcripts/notes.srt"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_format_warnings() {
+        let mut settings = TranscriptionSettings::default();
+        assert!(TranscriptionManager::output_format_warnings(&settings).is_empty());
+
+        settings.output_formats = vec![OutputFormat::Txt];
+        settings.mux_subtitles = true;
+        settings.include_language_in_output = true;
+        assert_eq!(
+            TranscriptionManager::output_format_warnings(&settings).len(),
+            3
+        );
+
+        settings.output_formats = vec![OutputFormat::Json];
+        settings.emit_raw_json_sidecar = true;
+        assert_eq!(
+            TranscriptionManager::output_format_warnings(&settings),
+            vec!["Subtitles are only added to videos for SRT and VTT output"]
+        );
+
+        // Another format can make up for what one leaves out
+        settings.output_formats = vec![OutputFormat::Txt, OutputFormat::Srt];
+        assert!(TranscriptionManager::output_format_warnings(&settings).is_empty());
+    }
+
+    #[test]
+    fn test_extra_output_formats_skip_repeats() {
+        let settings = TranscriptionSettings {
+            output_formats: vec![
+                OutputFormat::Vtt,
+                OutputFormat::Txt,
+                OutputFormat::Vtt,
+                OutputFormat::Txt,
+            ],
+            ..TranscriptionSettings::default()
+        };
+        assert_eq!(settings.output_format(), &OutputFormat::Vtt);
+        assert_eq!(settings.extra_output_formats(), vec![OutputFormat::Txt]);
+
+        let input = Path::new("/videos/talk.mp4");
+        let output_path =
+            |format| TranscriptionManager::get_output_path(input, None, &settings, format);
+        assert_ne!(
+            output_path(&OutputFormat::Vtt),
+            output_path(&OutputFormat::Txt)
+        );
+    }
+
+    #[test]
+    fn test_format_output_dirs_take_precedence() {
+        let input = Path::new("/videos/talk.mp4");
+        let mut settings = TranscriptionSettings {
+            output_mode: OutputMode::FixedDir,
+            output_dir: Some(PathBuf::from("/transcripts")),
+            format_output_dirs: HashMap::from([
+                (OutputFormat::Srt, PathBuf::from(".")),
+                (OutputFormat::Json, PathBuf::from("/debug")),
+            ]),
+            ..TranscriptionSettings::default()
+        };
+        let output_path = |settings: &TranscriptionSettings| {
+            TranscriptionManager::get_output_path(input, None, settings, settings.output_format())
+        };
+
+        assert_eq!(output_path(&settings), Path::new("/videos/talk.srt"));
+        settings.output_formats = vec![OutputFormat::Json];
+        assert_eq!(output_path(&settings), Path::new("/debug/talk.json"));
+        // Formats without an override use the global folder, then the input's
+        settings.output_formats = vec![OutputFormat::Txt];
+        assert_eq!(output_path(&settings), Path::new("/transcripts/talk.txt"));
+        settings.output_dir = None;
+        assert_eq!(output_path(&settings), Path::new("/videos/talk.txt"));
     }
 }
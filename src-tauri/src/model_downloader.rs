@@ -1,20 +1,50 @@
+use crate::error::AppError;
+use crate::progress_throttle::ProgressThrottle;
 use crate::types::*;
 use anyhow::{anyhow, Result};
 use futures_util::StreamExt;
 use reqwest;
 use std::path::Path;
+use std::time::{Duration, Instant};
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+/// Attempts per download; only timeouts and connection failures are retried.
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
+/// First bytes of a ggml model: the magic 0x67676d6c, little endian in files
+/// written by whisper.cpp and big endian in some older conversions.
+const GGML_MAGICS: [&[u8; 4]; 2] = [b"lmgg", b"ggml"];
+/// How long `measure_throughput` reads for.
+const PROBE_DURATION: Duration = Duration::from_secs(1);
+/// Bytes `measure_throughput` asks for; it stops early once it has them.
+const PROBE_BYTES: u64 = 16 * 1024 * 1024;
+
+#[derive(Clone)]
 pub struct ModelDownloader {
     client: reqwest::Client,
+    stall_timeout: Duration,
+    progress_interval: Duration,
 }
 
 impl ModelDownloader {
-    pub fn new() -> Self {
-        Self {
-            client: reqwest::Client::new(),
+    /// A client using the proxy, stall timeout and progress interval from
+    /// `settings`. Without a configured proxy the environment's is used.
+    pub fn from_settings(settings: &TranscriptionSettings) -> Result<Self> {
+        let stall_timeout = Duration::from_secs(settings.download_timeout_secs.max(1));
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(CONNECT_TIMEOUT)
+            .read_timeout(stall_timeout);
+        if let Some(proxy) = settings.download_proxy.as_deref() {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|e| anyhow!("Invalid proxy URL '{}': {}", proxy, e))?;
+            builder = builder.proxy(proxy);
         }
+        Ok(Self {
+            client: builder.build()?,
+            stall_timeout,
+            progress_interval: settings.progress_update_interval(),
+        })
     }
 
     pub async fn download_model<F>(
@@ -23,17 +53,72 @@ impl ModelDownloader {
         output_path: &Path,
         progress_callback: F,
     ) -> Result<()>
+    where
+        F: Fn(i32) + Send + Sync,
+    {
+        let mut attempt = 1;
+        loop {
+            match self
+                .download_once(model, output_path, &progress_callback)
+                .await
+            {
+                Err(e) if attempt < MAX_DOWNLOAD_ATTEMPTS && Self::is_transient(&e) => {
+                    log::warn!(
+                        "Download of {} failed (attempt {}/{}), retrying: {}",
+                        model.name,
+                        attempt,
+                        MAX_DOWNLOAD_ATTEMPTS,
+                        e
+                    );
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+                Ok(()) => return Self::verify_model_file(output_path).await,
+            }
+        }
+    }
+
+    /// Checks the file starts with the ggml magic, which catches an HTML error
+    /// page saved in place of the model. The file is deleted if it doesn't.
+    async fn verify_model_file(path: &Path) -> Result<()> {
+        let mut magic = [0u8; 4];
+        let is_model = match File::open(path).await {
+            Ok(mut file) => {
+                file.read_exact(&mut magic).await.is_ok() && GGML_MAGICS.contains(&&magic)
+            }
+            Err(_) => false,
+        };
+
+        if !is_model {
+            let _ = tokio::fs::remove_file(path).await;
+            return Err(anyhow!("downloaded file is not a valid model"));
+        }
+        Ok(())
+    }
+
+    async fn download_once<F>(
+        &self,
+        model: &WhisperModel,
+        output_path: &Path,
+        progress_callback: &F,
+    ) -> Result<()>
     where
         F: Fn(i32) + Send + Sync,
     {
         log::debug!("Downloading Model: {}", model.name);
-        let response = self.client.get(&model.url).send().await?;
+        let response = self
+            .client
+            .get(&model.url)
+            .send()
+            .await
+            .map_err(|e| self.describe_error(e))?;
 
         if !response.status().is_success() {
-            return Err(anyhow!(
+            return Err(AppError::NetworkError(format!(
                 "Failed to download model: HTTP {}",
                 response.status()
-            ));
+            ))
+            .into());
         }
 
         let total_size = response.content_length().unwrap_or(0);
@@ -41,16 +126,15 @@ impl ModelDownloader {
         let mut stream = response.bytes_stream();
         let mut file = File::create(output_path).await?;
 
-        let mut last_progress: i32 = 0;
+        let mut throttle = ProgressThrottle::new(self.progress_interval);
         while let Some(chunk) = stream.next().await {
-            let chunk = chunk?;
+            let chunk = chunk.map_err(|e| self.describe_error(e))?;
             file.write_all(&chunk).await?;
             downloaded += chunk.len() as u64;
 
             if total_size > 0  {
                 let progress: i32 = ((downloaded as f32 / total_size as f32) * 100.0) as i32;
-                if progress > last_progress {
-                    last_progress = progress;
+                if throttle.should_send(progress as f32) {
                     progress_callback(progress);
                 }
             }
@@ -61,8 +145,64 @@ impl ModelDownloader {
         Ok(())
     }
 
+    /// Fetches a media file to transcribe. A partial file is removed on failure.
+    pub async fn download_media(&self, url: &str, output_path: &Path) -> Result<()> {
+        let result = async {
+            let response = self
+                .client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| self.describe_error(e))?;
+            if !response.status().is_success() {
+                return Err(AppError::NetworkError(format!(
+                    "Server returned HTTP {}",
+                    response.status()
+                ))
+                .into());
+            }
+
+            let mut stream = response.bytes_stream();
+            let mut file = File::create(output_path).await?;
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.map_err(|e| self.describe_error(e))?;
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            Ok(())
+        }
+        .await;
+
+        if result.is_err() {
+            let _ = tokio::fs::remove_file(output_path).await;
+        }
+        result
+    }
+
+    /// The model catalog at `url`, parsed but not yet validated.
+    pub async fn fetch_catalog(&self, url: &str) -> Result<Vec<CatalogModel>> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| self.describe_error(e))?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Catalog not available: HTTP {}", response.status()));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Catalog is not a list of models: {}", e))
+    }
+
     pub async fn check_model_availability(&self, url: &str) -> Result<u64> {
-        let response = self.client.head(url).send().await?;
+        let response = self
+            .client
+            .head(url)
+            .send()
+            .await
+            .map_err(|e| self.describe_error(e))?;
 
         if !response.status().is_success() {
             return Err(anyhow!("Model not available: HTTP {}", response.status()));
@@ -70,4 +210,93 @@ impl ModelDownloader {
 
         Ok(response.content_length().unwrap_or(0))
     }
+
+    /// Download speed from `url` in bytes per second, from reading for about a
+    /// second. A byte range is requested; servers that ignore it start sending
+    /// the whole file, which is dropped once the probe is over.
+    pub async fn measure_throughput(&self, url: &str) -> Result<f64> {
+        let response = self
+            .client
+            .get(url)
+            .header(
+                reqwest::header::RANGE,
+                format!("bytes=0-{}", PROBE_BYTES - 1),
+            )
+            .send()
+            .await
+            .map_err(|e| self.describe_error(e))?;
+        if !response.status().is_success() {
+            return Err(AppError::NetworkError(format!(
+                "Failed to measure download speed: HTTP {}",
+                response.status()
+            ))
+            .into());
+        }
+
+        // Timed from the first response, so connection setup doesn't count
+        let started = Instant::now();
+        let deadline = tokio::time::Instant::from_std(started + PROBE_DURATION);
+        let mut received = 0u64;
+        let mut stream = response.bytes_stream();
+        while let Ok(Some(chunk)) = tokio::time::timeout_at(deadline, stream.next()).await {
+            received += chunk.map_err(|e| self.describe_error(e))?.len() as u64;
+            if received >= PROBE_BYTES {
+                break;
+            }
+        }
+
+        let secs = started.elapsed().as_secs_f64();
+        if received == 0 || secs <= 0.0 {
+            return Err(AppError::NetworkError(
+                "No data received while measuring download speed".to_string(),
+            )
+            .into());
+        }
+        Ok(received as f64 / secs)
+    }
+
+    /// Keeps the reqwest error as the source but leads with what went wrong.
+    fn describe_error(&self, error: reqwest::Error) -> anyhow::Error {
+        if error.is_timeout() {
+            anyhow::Error::new(error).context(format!(
+                "Download timed out: no data received for {}s",
+                self.stall_timeout.as_secs()
+            ))
+        } else if error.is_connect() {
+            anyhow::Error::new(error).context("Could not connect to the download server")
+        } else {
+            error.into()
+        }
+    }
+
+    fn is_transient(error: &anyhow::Error) -> bool {
+        error
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_timeout() || e.is_connect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verify_model_file_rejects_html() {
+        let dir = std::env::temp_dir();
+        let page = dir.join(format!("model-check-{}-page.bin", std::process::id()));
+        let model = dir.join(format!("model-check-{}-model.bin", std::process::id()));
+        tokio::fs::write(&page, "<!DOCTYPE html><title>Not Found</title>")
+            .await
+            .unwrap();
+        tokio::fs::write(&model, b"lmgg\x00\x00\x00\x00")
+            .await
+            .unwrap();
+
+        let error = ModelDownloader::verify_model_file(&page).await.unwrap_err();
+        assert_eq!(error.to_string(), "downloaded file is not a valid model");
+        assert!(!page.exists());
+
+        ModelDownloader::verify_model_file(&model).await.unwrap();
+        tokio::fs::remove_file(&model).await.unwrap();
+    }
 }
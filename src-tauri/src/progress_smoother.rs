@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+/// The interpolated progress is reported at the progress update interval,
+/// but never more often than this.
+pub const MIN_TICK_INTERVAL: Duration = Duration::from_millis(50);
+/// Interpolation stops here; only a real update reaches 100%.
+const MAX_INTERPOLATED: f32 = 99.0;
+
+/// Advances progress between whisper's coarse callbacks at the rate the
+/// expected transcription time implies. The reported value never goes
+/// backwards: a real update behind the estimate is held until it catches up.
+pub struct ProgressSmoother {
+    real: f32,
+    real_at: Instant,
+    shown: f32,
+    /// Percent per second, from the expected transcription time.
+    rate: Option<f64>,
+}
+
+impl ProgressSmoother {
+    /// Without an expected duration, only real updates move the progress.
+    pub fn new(expected_secs: Option<f64>) -> Self {
+        Self {
+            real: 0.0,
+            real_at: Instant::now(),
+            shown: 0.0,
+            rate: expected_secs
+                .filter(|secs| *secs > 0.0)
+                .map(|secs| 100.0 / secs),
+        }
+    }
+
+    /// Records a real progress value and returns the value to show.
+    pub fn update(&mut self, real: f32) -> f32 {
+        self.real = real;
+        self.real_at = Instant::now();
+        self.show(real)
+    }
+
+    /// The interpolated value to show at `now`.
+    pub fn tick(&mut self, now: Instant) -> f32 {
+        let Some(rate) = self.rate else {
+            return self.shown;
+        };
+        let elapsed = now.saturating_duration_since(self.real_at).as_secs_f64();
+        let estimate = (self.real + (elapsed * rate) as f32).min(MAX_INTERPOLATED);
+        self.show(estimate)
+    }
+
+    fn show(&mut self, value: f32) -> f32 {
+        if value >= 100.0 {
+            self.shown = 100.0;
+        } else {
+            self.shown = self.shown.max(value);
+        }
+        self.shown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolates_between_real_updates() {
+        let mut smoother = ProgressSmoother::new(Some(10.0));
+        let start = smoother.real_at;
+
+        assert_eq!(smoother.tick(start + Duration::from_secs(2)), 20.0);
+        // A real update behind the estimate doesn't move the bar back
+        smoother.real_at = start;
+        assert_eq!(smoother.update(10.0), 20.0);
+        assert_eq!(smoother.update(50.0), 50.0);
+
+        let later = smoother.real_at + Duration::from_secs(60);
+        assert_eq!(smoother.tick(later), MAX_INTERPOLATED);
+        assert_eq!(smoother.update(100.0), 100.0);
+    }
+
+    #[test]
+    fn test_without_estimate_only_real_updates_count() {
+        let mut smoother = ProgressSmoother::new(None);
+        assert_eq!(smoother.tick(Instant::now() + Duration::from_secs(5)), 0.0);
+        assert_eq!(smoother.update(40.0), 40.0);
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::{Duration, Instant};
+
+/// Limits how often progress callbacks reach the event bus to once per
+/// `interval`. The first update and 100% are always delivered.
+pub struct ProgressThrottle {
+    interval: Duration,
+    last_value: Option<f32>,
+    last_sent: Instant,
+}
+
+impl ProgressThrottle {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_value: None,
+            last_sent: Instant::now(),
+        }
+    }
+
+    pub fn should_send(&mut self, value: f32) -> bool {
+        let send = match self.last_value {
+            None => true,
+            Some(last) if value == last => false,
+            Some(_) if value >= 100.0 => true,
+            Some(_) => self.last_sent.elapsed() >= self.interval,
+        };
+
+        if send {
+            self.last_value = Some(value);
+            self.last_sent = Instant::now();
+        }
+        send
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updates_within_interval_are_dropped_but_completion_is_sent() {
+        let mut throttle = ProgressThrottle::new(Duration::from_secs(60));
+
+        assert!(throttle.should_send(0.0));
+        assert!(!throttle.should_send(0.3));
+        assert!(!throttle.should_send(50.0));
+        // Within the interval, but the final update must not be lost
+        assert!(throttle.should_send(100.0));
+        assert!(!throttle.should_send(100.0));
+
+        let mut throttle = ProgressThrottle::new(Duration::ZERO);
+        assert!(throttle.should_send(0.0));
+        assert!(throttle.should_send(0.3));
+        assert!(!throttle.should_send(0.3));
+    }
+}
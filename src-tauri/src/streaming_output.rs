@@ -0,0 +1,276 @@
+use crate::transcriber::{FormatOptions, WhisperTranscriber};
+use crate::types::{OutputFormat, Segment};
+
+use anyhow::Result;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Writes TXT, SRT and VTT output cue by cue while whisper runs, so long files
+/// aren't held as one string and the output can be watched as it grows. Cues
+/// go to `<output>.partial`, which `finish` renames over the output; dropping
+/// an unfinished writer removes it. The result is the same as formatting the
+/// whole transcript at the end.
+pub struct StreamingWriter {
+    format: OutputFormat,
+    options: FormatOptions,
+    anti_repetition: bool,
+    file: File,
+    temp_path: PathBuf,
+    output_path: PathBuf,
+    /// Segments sharing the same text, which may still collapse into one.
+    run: Vec<Segment>,
+    run_key: String,
+    /// A cue shorter than `min_cue_duration_ms`, waiting for the next segment.
+    pending: Option<Segment>,
+    cues_written: usize,
+    /// Start of the last cue written, for placing chapter headings.
+    last_cue_start: Option<i64>,
+    finished: bool,
+}
+
+impl StreamingWriter {
+    /// `None` for JSON, which is only written once the transcript is complete.
+    /// `options.timestamp_offset_ms` is ignored: segments are expected to be
+    /// offset already, as `transcribe_segments` passes them on.
+    pub fn create(
+        output_path: &Path,
+        format: &OutputFormat,
+        options: FormatOptions,
+        anti_repetition: bool,
+    ) -> Result<Option<Self>> {
+        if matches!(format, OutputFormat::Json) {
+            return Ok(None);
+        }
+        if let Some(parent) = output_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut temp_name = output_path.as_os_str().to_owned();
+        temp_name.push(".partial");
+        let temp_path = PathBuf::from(temp_name);
+
+        let mut file = File::create(&temp_path)?;
+        file.write_all(WhisperTranscriber::format_header(format, &options).as_bytes())?;
+        Ok(Some(Self {
+            format: format.clone(),
+            options,
+            anti_repetition,
+            file,
+            temp_path,
+            output_path: output_path.to_path_buf(),
+            run: Vec::new(),
+            run_key: String::new(),
+            pending: None,
+            cues_written: 0,
+            last_cue_start: None,
+            finished: false,
+        }))
+    }
+
+    pub fn push(&mut self, segment: Segment) -> Result<()> {
+        if !self.anti_repetition {
+            return self.merge(segment);
+        }
+        let key = WhisperTranscriber::repetition_key(&segment.text);
+        if self.run.is_empty() || key == self.run_key {
+            self.run.push(segment);
+            self.run_key = key;
+            return Ok(());
+        }
+        self.flush_run()?;
+        self.run.push(segment);
+        self.run_key = key;
+        Ok(())
+    }
+
+    /// Writes the remaining cues and moves the file into place.
+    pub fn finish(mut self) -> Result<PathBuf> {
+        self.flush_run()?;
+        if let Some(cue) = self.pending.take() {
+            self.write_cue(cue)?;
+        }
+        self.file.sync_all()?;
+        std::fs::rename(&self.temp_path, &self.output_path)?;
+        self.finished = true;
+        Ok(self.output_path.clone())
+    }
+
+    fn flush_run(&mut self) -> Result<()> {
+        let run = std::mem::take(&mut self.run);
+        for segment in WhisperTranscriber::collapse_run(run, &self.run_key) {
+            self.merge(segment)?;
+        }
+        Ok(())
+    }
+
+    fn merge(&mut self, segment: Segment) -> Result<()> {
+        let cue = match self.format {
+            OutputFormat::Txt => Some(segment),
+            _ => WhisperTranscriber::merge_short_segment(
+                &mut self.pending,
+                segment,
+                self.options.min_cue_duration_ms,
+            ),
+        };
+        match cue {
+            Some(cue) => self.write_cue(cue),
+            None => Ok(()),
+        }
+    }
+
+    fn write_cue(&mut self, cue: Segment) -> Result<()> {
+        let cues = match self.format {
+            OutputFormat::Txt => vec![cue],
+            _ => WhisperTranscriber::split_long_cue(cue, self.options.max_cue_duration_ms),
+        };
+        for cue in cues {
+            let chapters = WhisperTranscriber::format_chapters(
+                &self.format,
+                self.last_cue_start,
+                &cue,
+                &self.options,
+            );
+            self.file.write_all(chapters.as_bytes())?;
+            let text = WhisperTranscriber::format_cue(
+                &self.format,
+                self.cues_written,
+                &cue,
+                &self.options,
+            );
+            self.file.write_all(text.as_bytes())?;
+            self.cues_written += 1;
+            self.last_cue_start = Some(cue.start);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StreamingWriter {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = std::fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Chapter;
+    use uuid::Uuid;
+
+    fn segment(start: i64, end: i64, text: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            text: text.to_string(),
+            tokens: Vec::new(),
+            language: None,
+        }
+    }
+
+    fn options() -> FormatOptions {
+        FormatOptions {
+            metadata: Some(vec!["Model: ggml-base.bin".to_string()]),
+            max_line_chars: 42,
+            min_cue_duration_ms: 500,
+            max_cue_duration_ms: 2000,
+            vtt_cue_settings: String::new(),
+            timestamp_offset_ms: 0,
+            language: None,
+            chapters: vec![Chapter {
+                title: "Outro".to_string(),
+                start_ms: 4000,
+                end_ms: 9000,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_streamed_output_matches_batch_output() {
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        let segments = vec![
+            segment(0, 20, " Hi"),
+            segment(20, 150, " there."),
+            segment(150, 300, " Thank you."),
+            segment(300, 450, " thank you"),
+            segment(450, 600, " Thank you!"),
+            segment(600, 900, " Bye."),
+        ];
+
+        for format in [OutputFormat::Txt, OutputFormat::Srt, OutputFormat::Vtt] {
+            let output = dir.join(format!("talk.{}", format.extension()));
+            let mut writer = StreamingWriter::create(&output, &format, options(), true)
+                .unwrap()
+                .unwrap();
+            for segment in segments.clone() {
+                writer.push(segment).unwrap();
+            }
+            assert_eq!(writer.finish().unwrap(), output);
+
+            let collapsed = WhisperTranscriber::collapse_repetitions(segments.clone());
+            let expected =
+                WhisperTranscriber::format_segments(&collapsed, &format, &options()).unwrap();
+            assert_eq!(std::fs::read_to_string(&output).unwrap(), expected);
+        }
+
+        assert!(StreamingWriter::create(
+            &dir.join("talk.json"),
+            &OutputFormat::Json,
+            options(),
+            true
+        )
+        .unwrap()
+        .is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_long_transcript_is_not_buffered() {
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        let output = dir.join("long.srt");
+        let mut writer = StreamingWriter::create(&output, &OutputFormat::Srt, options(), true)
+            .unwrap()
+            .unwrap();
+
+        // About 55 hours of 2 second segments
+        const SEGMENTS: i64 = 100_000;
+        for i in 0..SEGMENTS {
+            writer
+                .push(segment(
+                    i * 200,
+                    (i + 1) * 200,
+                    &format!(" Sentence {}.", i),
+                ))
+                .unwrap();
+            assert!(writer.run.len() + usize::from(writer.pending.is_some()) <= 2);
+        }
+        // Everything but the last segment is already on disk, at over 40 bytes a cue
+        let written = std::fs::metadata(&writer.temp_path).unwrap().len();
+        assert!(written > (SEGMENTS as u64 - 1) * 40);
+        assert!(!output.exists());
+
+        writer.finish().unwrap();
+        let content = std::fs::read_to_string(&output).unwrap();
+        assert!(content.contains(&format!("{}\n", SEGMENTS + 1)));
+        assert!(content.ends_with(&format!("\nSentence {}.\n\n", SEGMENTS - 1)));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_unfinished_writer_removes_partial_file() {
+        let dir = std::env::temp_dir().join(format!("whisper-tauri-test-{}", Uuid::new_v4()));
+        let output = dir.join("cancelled.vtt");
+        let mut writer = StreamingWriter::create(&output, &OutputFormat::Vtt, options(), false)
+            .unwrap()
+            .unwrap();
+        writer.push(segment(0, 100, " Hello.")).unwrap();
+        let temp_path = writer.temp_path.clone();
+        assert!(temp_path.exists());
+
+        drop(writer);
+        assert!(!temp_path.exists());
+        assert!(!output.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+use crate::types::{OutputFormat, ParsedSubtitles, SubtitleCue};
+
+use anyhow::{anyhow, Result};
+
+/// Reads the cues of an SRT or VTT file. Blocks that aren't valid cues are
+/// skipped and described in `errors`, so a single bad cue doesn't hide the rest.
+pub fn parse(content: &str, format: &OutputFormat) -> Result<ParsedSubtitles> {
+    let content = content.replace("\r\n", "\n");
+    let mut blocks = content
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|block| !block.is_empty());
+
+    match format {
+        OutputFormat::Srt => {}
+        OutputFormat::Vtt => match blocks.next() {
+            Some(header) if header.starts_with("WEBVTT") => {}
+            _ => return Err(anyhow!("Missing WEBVTT header")),
+        },
+        _ => return Err(anyhow!("Only SRT and VTT files have cues")),
+    }
+
+    let mut parsed = ParsedSubtitles::default();
+    for (index, block) in blocks.enumerate() {
+        let is_vtt_metadata = ["NOTE", "STYLE", "REGION"]
+            .iter()
+            .any(|keyword| block.starts_with(keyword));
+        if matches!(format, OutputFormat::Vtt) && is_vtt_metadata {
+            continue;
+        }
+        match parse_cue(block) {
+            Ok(cue) => parsed.cues.push(cue),
+            Err(e) => parsed.errors.push(format!("Cue {}: {}", index + 1, e)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// One cue: an optional identifier line, the timing line, then the text.
+fn parse_cue(block: &str) -> Result<SubtitleCue> {
+    let mut lines = block.lines();
+    let first = lines.next().unwrap_or_default();
+    let timing = if first.contains("-->") {
+        first
+    } else {
+        lines
+            .next()
+            .filter(|line| line.contains("-->"))
+            .ok_or_else(|| anyhow!("missing timing line"))?
+    };
+
+    let (start, rest) = timing
+        .split_once("-->")
+        .ok_or_else(|| anyhow!("missing timing line"))?;
+    // VTT cue settings may follow the end time
+    let end = rest
+        .split_whitespace()
+        .next()
+        .ok_or_else(|| anyhow!("missing end time"))?;
+    let start_ms = parse_timestamp(start.trim())?;
+    let end_ms = parse_timestamp(end)?;
+    if end_ms < start_ms {
+        return Err(anyhow!("ends before it starts"));
+    }
+
+    Ok(SubtitleCue {
+        start_ms,
+        end_ms,
+        text: lines.collect::<Vec<_>>().join("\n"),
+    })
+}
+
+/// Milliseconds in `HH:MM:SS,mmm`, `HH:MM:SS.mmm` or `MM:SS.mmm`.
+fn parse_timestamp(text: &str) -> Result<u64> {
+    let invalid = || anyhow!("invalid timestamp '{}'", text);
+    let (clock, millis) = text.split_once([',', '.']).ok_or_else(invalid)?;
+    let parts = clock
+        .split(':')
+        .map(|part| part.parse::<u64>())
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        [minutes, seconds] => (0, minutes, seconds),
+        _ => return Err(invalid()),
+    };
+    if minutes >= 60 || seconds >= 60 || millis.len() != 3 {
+        return Err(invalid());
+    }
+    let millis: u64 = millis.parse().map_err(|_| invalid())?;
+    Ok(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_srt_skips_malformed_cues() {
+        let srt = "1\r\n00:00:00,000 --> 00:00:01,500\r\n Hello there.\r\n\r\n\
+                   2\r\n00:00:02,000 -> 00:00:03,000\r\nBroken arrow\r\n\r\n\
+                   3\r\n01:02:03,250 --> 01:02:05,000\r\nTwo\r\nlines\r\n";
+
+        let parsed = parse(srt, &OutputFormat::Srt).unwrap();
+        assert_eq!(
+            parsed.cues,
+            vec![
+                SubtitleCue {
+                    start_ms: 0,
+                    end_ms: 1500,
+                    text: " Hello there.".to_string(),
+                },
+                SubtitleCue {
+                    start_ms: 3_723_250,
+                    end_ms: 3_725_000,
+                    text: "Two\nlines".to_string(),
+                },
+            ]
+        );
+        assert_eq!(parsed.errors, vec!["Cue 2: missing timing line"]);
+    }
+
+    #[test]
+    fn test_parse_vtt() {
+        let vtt = "WEBVTT\n\nNOTE\nModel: ggml-base.bin\n\n\
+                   00:01.000 --> 00:02.000 align:center line:90%\nShort form\n\n\
+                   00:00:05.000 --> 00:00:04.000\nBackwards\n";
+
+        let parsed = parse(vtt, &OutputFormat::Vtt).unwrap();
+        assert_eq!(parsed.cues.len(), 1);
+        assert_eq!(parsed.cues[0].start_ms, 1000);
+        assert_eq!(parsed.cues[0].text, "Short form");
+        assert_eq!(parsed.errors, vec!["Cue 3: ends before it starts"]);
+
+        assert!(parse("00:01.000 --> 00:02.000\nNo header", &OutputFormat::Vtt).is_err());
+    }
+}
@@ -1,10 +1,133 @@
+use crate::audio_converter::AudioConverter;
 use crate::config::ConfigManager;
+use crate::progress_throttle::ProgressThrottle;
 use crate::types::*;
 
 use anyhow::{anyhow, Result};
 use hound::{SampleFormat, WavReader};
+use std::borrow::Cow;
+use std::ops::Range;
 use std::path::Path;
-use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+use std::sync::{Arc, Mutex};
+use whisper_rs::{
+    FullParams, SamplingStrategy, SegmentCallbackData, WhisperContext, WhisperContextParameters,
+    WhisperState,
+};
+
+/// Consecutive near-identical segments from this many on are treated as a
+/// repetition loop.
+const MIN_REPETITION_RUN: usize = 3;
+
+const WHISPER_SAMPLE_RATE: usize = 16000;
+/// Samples quieter than this (about -50 dBFS) count as silence when trimming.
+const SILENCE_THRESHOLD: f32 = 0.003;
+/// Audio kept before the first and after the last sound when trimming, so
+/// quiet onsets and trailing words aren't clipped.
+const SILENCE_PADDING_SAMPLES: usize = WHISPER_SAMPLE_RATE / 2;
+/// Length of the chunks whose language is detected separately in
+/// multi-language mode; whisper detects language from the first 30 s it sees.
+const MULTILANG_CHUNK_SAMPLES: usize = 30 * WHISPER_SAMPLE_RATE;
+/// A final chunk shorter than this joins the one before, as language detection
+/// needs some speech to go on.
+const MIN_MULTILANG_CHUNK_SAMPLES: usize = 10 * WHISPER_SAMPLE_RATE;
+/// whisper.cpp refuses input shorter than a second, so shorter audio is
+/// padded with silence up to this.
+const MIN_CLIP_SAMPLES: usize = WHISPER_SAMPLE_RATE;
+/// Nice value for background mode transcription threads on Linux.
+#[cfg(target_os = "linux")]
+const BACKGROUND_NICE: i32 = 10;
+
+/// Whisper parameters that can be set through `advanced_params`, named after
+/// the `whisper_full_params` fields they set.
+pub const ADVANCED_PARAMS: &[&str] = &[
+    "n_threads",
+    "n_max_text_ctx",
+    "offset_ms",
+    "duration_ms",
+    "max_len",
+    "max_tokens",
+    "audio_ctx",
+    "thold_pt",
+    "thold_ptsum",
+    "temperature",
+    "temperature_inc",
+    "max_initial_ts",
+    "length_penalty",
+    "entropy_thold",
+    "logprob_thold",
+    "no_speech_thold",
+];
+
+/// Values of the WebVTT `align:` cue setting.
+const VTT_ALIGNMENTS: &[&str] = &["start", "center", "end", "left", "right"];
+
+/// Formatting choices shared by every output format.
+pub struct FormatOptions {
+    /// Header lines, written where the format allows comments.
+    pub metadata: Option<Vec<String>>,
+    pub max_line_chars: usize,
+    pub min_cue_duration_ms: u64,
+    /// SRT/VTT cues longer than this are split, 0 for no limit.
+    pub max_cue_duration_ms: u64,
+    /// Appended to each VTT timing line, e.g. ` align:center line:90%`.
+    pub vtt_cue_settings: String,
+    /// Added to every segment and token time before formatting, so output
+    /// times refer to the source even when whisper saw trimmed audio.
+    pub timestamp_offset_ms: i64,
+    /// Written at the top of JSON output and as a VTT `NOTE`.
+    pub language: Option<Language>,
+    /// Chapter markers of the source, written as headings before the first
+    /// cue of each chapter and as a list in JSON.
+    pub chapters: Vec<Chapter>,
+}
+
+impl FormatOptions {
+    pub fn from_settings(source_path: &Path, settings: &TranscriptionSettings) -> Self {
+        Self {
+            metadata: settings
+                .include_metadata_header
+                .then(|| WhisperTranscriber::metadata_lines(source_path, settings)),
+            max_line_chars: settings.max_line_chars,
+            min_cue_duration_ms: settings.min_cue_duration_ms,
+            max_cue_duration_ms: settings.max_cue_duration_ms,
+            vtt_cue_settings: WhisperTranscriber::vtt_cue_settings(
+                settings.vtt_align.as_deref(),
+                settings.vtt_line.as_deref(),
+            ),
+            timestamp_offset_ms: 0,
+            language: None,
+            chapters: if settings.include_chapters {
+                Self::read_chapters(source_path)
+            } else {
+                Vec::new()
+            },
+        }
+    }
+
+    /// A file whose chapters can't be read is written without them.
+    fn read_chapters(source_path: &Path) -> Vec<Chapter> {
+        match AudioConverter::new().and_then(|converter| converter.chapters(source_path)) {
+            Ok(chapters) => chapters,
+            Err(e) => {
+                log::warn!("Failed to read chapters of {:?}: {}", source_path, e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Whisper's output for one file.
+pub struct Transcription {
+    /// Times are relative to the audio whisper saw.
+    pub segments: Vec<Segment>,
+    /// Where that audio starts in the source; pass it on in `FormatOptions`.
+    pub timestamp_offset_ms: i64,
+    /// Code of the language whisper transcribed, detected when none was set.
+    pub language: Option<String>,
+    /// Whether token times are word timings. When whisper's were missing or
+    /// inconsistent, tokens carry their segment's times instead.
+    pub word_timestamps: bool,
+}
 
 pub struct WhisperTranscriber {
     model_path: Option<String>,
@@ -46,12 +169,182 @@ impl WhisperTranscriber {
             .collect::<Vec<_>>()
     }
 
+    /// Whether a file is already a WAV in the format `parse_wav_file` expects,
+    /// judged from the header alone.
+    pub fn is_whisper_wav(path: &Path) -> bool {
+        let is_wav = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+        if !is_wav {
+            return false;
+        }
+        WavReader::open(path).is_ok_and(|reader| {
+            let spec = reader.spec();
+            spec.channels == 1
+                && spec.sample_rate == WHISPER_SAMPLE_RATE as u32
+                && spec.bits_per_sample == 16
+                && spec.sample_format == SampleFormat::Int
+        })
+    }
+
+    /// Duration of a WAV file in seconds, or `None` if it can't be read or is empty.
+    pub fn wav_duration_secs(path: &Path) -> Option<f64> {
+        let reader = WavReader::open(path).ok()?;
+        let duration = reader.duration() as f64 / reader.spec().sample_rate as f64;
+        (duration > 0.0).then_some(duration)
+    }
+
+    /// whisper.cpp's description of its CPU features and backends.
+    pub fn system_info() -> String {
+        whisper_rs::print_system_info().trim().to_string()
+    }
+
+    /// Whether `system_info` lists `backend` (e.g. "CUDA"), either as a ggml
+    /// backend section ("CUDA : ARCHS = 860 |") or an older "CUDA = 1" flag.
+    pub fn reports_backend(system_info: &str, backend: &str) -> bool {
+        let backend = backend.to_uppercase();
+        system_info.split('|').any(|part| {
+            let part = part.trim().to_uppercase();
+            part.starts_with(&format!("{} :", backend)) || part == format!("{} = 1", backend)
+        })
+    }
+
+    /// English-only models are published with a `.en` suffix, e.g. `ggml-base.en.bin`.
+    pub fn is_english_only(model_name: &str) -> bool {
+        model_name.trim_end_matches(".bin").ends_with(".en")
+    }
+
+    /// Language to pass to whisper. English-only models always get English, and a
+    /// different configured language is an error rather than garbage output.
+    pub fn resolve_language(model_name: &str, language: Option<&str>) -> Result<Option<String>> {
+        if !Self::is_english_only(model_name) {
+            return Ok(language.map(str::to_string));
+        }
+        match language {
+            None | Some("auto") | Some("en") => Ok(Some("en".to_string())),
+            Some(language) => Err(anyhow!(
+                "{} only supports English, but the language is set to '{}'. Choose English or a multilingual model.",
+                model_name,
+                language
+            )),
+        }
+    }
+
+    /// Languages whisper can transcribe with the given model.
+    pub fn supported_languages(model_name: &str) -> Vec<Language> {
+        (0..=whisper_rs::get_lang_max_id())
+            .filter_map(|id| {
+                let code = whisper_rs::get_lang_str(id)?;
+                let name = whisper_rs::get_lang_str_full(id)?;
+                Some(Language {
+                    code: code.to_string(),
+                    name: Self::capitalize_words(name),
+                })
+            })
+            .filter(|language| !Self::is_english_only(model_name) || language.code == "en")
+            .collect()
+    }
+
+    /// Code and name of the language with whisper code `code`.
+    pub fn language(code: &str) -> Option<Language> {
+        let id = whisper_rs::get_lang_id(code)?;
+        Some(Language {
+            code: whisper_rs::get_lang_str(id)?.to_string(),
+            name: Self::capitalize_words(whisper_rs::get_lang_str_full(id)?),
+        })
+    }
+
+    fn capitalize_words(name: &str) -> String {
+        name.split(' ')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Transcribes the WAV and formats the result in the first of `settings.output_formats`.
+    #[allow(dead_code)] // The manager keeps the segments, so it uses the two steps
     pub fn transcribe_file(
         &mut self,
         audio_path: &Path,
+        source_path: &Path,
         settings: &TranscriptionSettings,
         progress_callback: Option<Box<dyn Fn(f32) + Send>>,
     ) -> Result<String> {
+        let transcription =
+            self.transcribe_segments(audio_path, settings, progress_callback, None)?;
+        let options = FormatOptions {
+            timestamp_offset_ms: transcription.timestamp_offset_ms,
+            ..FormatOptions::from_settings(source_path, settings)
+        };
+        Self::format_segments(&transcription.segments, settings.output_format(), &options)
+    }
+
+    /// Runs whisper over the WAV and returns its segments, with repetition loops
+    /// collapsed when `anti_repetition` is set. `segment_callback` gets each
+    /// segment as whisper finishes it, before collapsing, with times already
+    /// offset to refer to the source and without tokens.
+    ///
+    /// In `background_mode` whisper runs on its own thread, so the lowered
+    /// priority ends with the transcription.
+    pub fn transcribe_segments(
+        &mut self,
+        audio_path: &Path,
+        settings: &TranscriptionSettings,
+        progress_callback: Option<Box<dyn Fn(f32) + Send>>,
+        segment_callback: Option<Box<dyn FnMut(Segment) + Send>>,
+    ) -> Result<Transcription> {
+        if !settings.background_mode {
+            return self.run_whisper(
+                audio_path,
+                settings,
+                None,
+                progress_callback,
+                segment_callback,
+            );
+        }
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    Self::lower_thread_priority();
+                    self.run_whisper(
+                        audio_path,
+                        settings,
+                        None,
+                        progress_callback,
+                        segment_callback,
+                    )
+                })
+                .join()
+                .unwrap_or_else(|_| Err(anyhow!("Transcription thread panicked")))
+        })
+    }
+
+    /// Runs whisper over `range_ms` of the WAV only, for transcribing part of a
+    /// file again. Silence isn't trimmed; the returned offset is where the
+    /// range starts.
+    pub fn transcribe_range(
+        &mut self,
+        audio_path: &Path,
+        settings: &TranscriptionSettings,
+        range_ms: Range<u64>,
+    ) -> Result<Transcription> {
+        self.run_whisper(audio_path, settings, Some(range_ms), None, None)
+    }
+
+    fn run_whisper(
+        &mut self,
+        audio_path: &Path,
+        settings: &TranscriptionSettings,
+        range_ms: Option<Range<u64>>,
+        progress_callback: Option<Box<dyn Fn(f32) + Send>>,
+        segment_callback: Option<Box<dyn FnMut(Segment) + Send>>,
+    ) -> Result<Transcription> {
         log::debug!("audio_path: {:?}", audio_path.to_str());
         log::debug!("setting: {:?}", settings);
 
@@ -64,143 +357,1426 @@ impl WhisperTranscriber {
         whisper_rs::convert_integer_to_float_audio(&original_samples, &mut samples)
             .expect("failed to convert samples");
 
+        let bounds = match &range_ms {
+            Some(range_ms) => Self::range_bounds(samples.len(), range_ms)?,
+            None if settings.trim_silence => Self::speech_bounds(&samples),
+            None => 0..samples.len(),
+        };
+        let timestamp_offset_ms = (bounds.start * 1000 / WHISPER_SAMPLE_RATE) as i64;
+
+        // Whisper tends to invent text for pure silence, so don't run it at all
+        if !samples[bounds.clone()]
+            .iter()
+            .any(|sample| sample.abs() > SILENCE_THRESHOLD)
+        {
+            log::info!("Audio is silent, skipping transcription");
+            return Ok(Transcription {
+                segments: Vec::new(),
+                timestamp_offset_ms,
+                language: None,
+                word_timestamps: true,
+            });
+        }
+
+        if settings.trim_silence && range_ms.is_none() {
+            log::info!(
+                "Trimmed {:.1}s of leading and {:.1}s of trailing silence",
+                bounds.start as f64 / WHISPER_SAMPLE_RATE as f64,
+                (samples.len() - bounds.end) as f64 / WHISPER_SAMPLE_RATE as f64
+            );
+        }
+
         let ctx = WhisperContext::new_with_params(
             &model_path.to_string_lossy(),
             WhisperContextParameters::default(),
         )?;
 
         let mut state = ctx.create_state().expect("failed to create key");
-        let mut params = FullParams::new(SamplingStrategy::default());
 
-        // Set language from settings
-        if let Some(language) = &settings.language {
-            params.set_language(Some(language));
+        // Set language from settings, detecting it when unset
+        let language = Self::resolve_language(&settings.model, settings.language.as_deref())?;
+        // In multi-language mode each chunk is detected and transcribed on its own
+        let multilang = settings.multilang && language.is_none() && range_ms.is_none();
+        let chunks = if multilang {
+            Self::multilang_chunks(bounds.clone())
+        } else {
+            vec![bounds.clone()]
+        };
+
+        // Callbacks are shared by the chunks' runs
+        let progress_callback = progress_callback.map(|callback| Arc::new(Mutex::new(callback)));
+        let segment_callback = segment_callback.map(|callback| Arc::new(Mutex::new(callback)));
+        let total_samples = bounds.len().max(1) as f32;
+
+        let mut segments = Vec::new();
+        let mut detected_languages = Vec::new();
+        let mut missing_tokens = false;
+        let st = std::time::Instant::now();
+        for chunk in chunks {
+            // whisper-rs has no sampling seed parameter; whisper.cpp seeds its
+            // sampler with a fixed value, so output is already reproducible for
+            // the same model, thread count and backend
+            let mut params = FullParams::new(SamplingStrategy::default());
+            params.set_language(Some(language.as_deref().unwrap_or("auto")));
+            params.set_no_context(!settings.carry_context);
+            params.set_token_timestamps(true);
+            if let Some(audio_ctx) = Self::audio_ctx(settings) {
+                params.set_audio_ctx(audio_ctx);
+            }
+            // An explicit `n_threads` advanced parameter still takes precedence
+            if settings.background_mode {
+                params.set_n_threads(Self::background_threads());
+            }
+            for (key, value) in &settings.advanced_params {
+                if !Self::apply_advanced_param(&mut params, key, *value) {
+                    log::warn!("Ignoring unknown whisper parameter '{}'", key);
+                }
+            }
+
+            // Where the chunk starts, in centiseconds after `bounds.start`
+            let chunk_offset = ((chunk.start - bounds.start) * 100 / WHISPER_SAMPLE_RATE) as i64;
+            // Where its audio ends; anything after comes from padding
+            let chunk_end = (chunk.len() * 100 / WHISPER_SAMPLE_RATE) as i64;
+            if let Some(callback) = &progress_callback {
+                let callback = callback.clone();
+                let done = (chunk.start - bounds.start) as f32 / total_samples;
+                let share = chunk.len() as f32 / total_samples;
+                let mut throttle = ProgressThrottle::new(settings.progress_update_interval());
+                params.set_progress_callback_safe(move |progress| {
+                    let progress = (done + share * progress as f32 / 100.0) * 100.0;
+                    if throttle.should_send(progress) {
+                        if let Ok(callback) = callback.lock() {
+                            callback(progress);
+                        }
+                    }
+                });
+            }
+            if let Some(callback) = &segment_callback {
+                let callback = callback.clone();
+                let offset = timestamp_offset_ms / 10 + chunk_offset;
+                // Lossy like the collected segments, so streamed output has them all
+                params.set_segment_callback_safe_lossy(move |data: SegmentCallbackData| {
+                    if data.start_timestamp >= chunk_end {
+                        return;
+                    }
+                    if let Ok(mut callback) = callback.lock() {
+                        callback(Segment {
+                            start: data.start_timestamp + offset,
+                            end: data.end_timestamp.min(chunk_end) + offset,
+                            text: data.text,
+                            tokens: Vec::new(),
+                            language: None,
+                        });
+                    }
+                });
+            }
+
+            state.full(params, &Self::pad_short_clip(&samples[chunk]))?;
+
+            let chunk_language = language.clone().or_else(|| {
+                let id = state.full_lang_id_from_state().ok()?;
+                whisper_rs::get_lang_str(id).map(str::to_string)
+            });
+            if multilang {
+                log::info!(
+                    "Chunk at {}s is in {:?}",
+                    chunk_offset / 100,
+                    chunk_language
+                );
+            }
+
+            let num_segments = state
+                .full_n_segments()
+                .expect("failed to get number of segments");
+
+            // Collect segments instead of printing them
+            let mut chunk_segments = Vec::new();
+            for i in 0..num_segments {
+                // A multibyte character split across segments isn't valid
+                // UTF-8 on its own, so it's replaced rather than failing
+                let text = state
+                    .full_get_segment_text_lossy(i)
+                    .expect("failed to get segment");
+                let start = state
+                    .full_get_segment_t0(i)
+                    .expect("failed to get start timestamp");
+                let end = state
+                    .full_get_segment_t1(i)
+                    .expect("failed to get end timestamp");
+
+                let tokens = match Self::segment_tokens(&state, i) {
+                    Ok(tokens) => tokens,
+                    Err(e) => {
+                        log::warn!("Failed to read the tokens of segment {}: {}", i, e);
+                        missing_tokens = true;
+                        Vec::new()
+                    }
+                };
+
+                chunk_segments.push(Segment {
+                    start,
+                    end,
+                    text,
+                    tokens,
+                    language: chunk_language.clone().filter(|_| multilang),
+                });
+            }
+            let chunk_segments = Self::trim_padding(chunk_segments, chunk_end);
+            segments.extend(Self::offset_segments(&chunk_segments, chunk_offset * 10));
+            detected_languages.extend(chunk_language);
         }
+        let et = std::time::Instant::now();
+        let language = Self::main_language(&detected_languages);
+
+        println!("took {}ms", (et - st).as_millis());
 
-        // Set progress callback if provided
-        if let Some(callback) = progress_callback {
-            params.set_progress_callback_safe(move |progress| {
-                callback(progress as f32);
+        if settings.anti_repetition {
+            segments = Self::collapse_repetitions(segments);
+        }
+
+        let (segments, valid_times) = Self::with_usable_token_times(segments);
+        let word_timestamps = valid_times && !missing_tokens;
+        if !word_timestamps {
+            log::warn!(
+                "{} gave no usable token timestamps, only segment times are available",
+                settings.model
+            );
+        }
+
+        Ok(Transcription {
+            segments,
+            timestamp_offset_ms,
+            language,
+            word_timestamps,
+        })
+    }
+
+    fn segment_tokens(state: &WhisperState, segment: i32) -> Result<Vec<Token>> {
+        let mut tokens = Vec::new();
+        for j in 0..state.full_n_tokens(segment)? {
+            let data = state.full_get_token_data(segment, j)?;
+            tokens.push(Token {
+                id: data.id,
+                text: state.full_get_token_text_lossy(segment, j)?,
+                start: data.t0,
+                end: data.t1,
+                probability: data.p,
             });
         }
+        Ok(tokens)
+    }
 
-        let st = std::time::Instant::now();
-        state
-            .full(params, &samples)
-            .expect("failed to convert samples");
-        let et = std::time::Instant::now();
+    /// Checks whisper's token times: each text token must lie within its
+    /// segment and start no earlier than the one before, and some must have a
+    /// duration. Otherwise every token gets its segment's times, and `false`
+    /// is returned so outputs don't pass them off as word timings.
+    fn with_usable_token_times(mut segments: Vec<Segment>) -> (Vec<Segment>, bool) {
+        let mut has_duration = false;
+        let valid = segments.iter().all(|segment| {
+            let mut previous_start = segment.start;
+            segment
+                .tokens
+                .iter()
+                .filter(|token| !token.text.starts_with("[_"))
+                .all(|token| {
+                    has_duration |= token.end > token.start;
+                    let valid = token.start >= previous_start
+                        && token.start <= token.end
+                        && token.end <= segment.end;
+                    previous_start = token.start;
+                    valid
+                })
+        });
+        let has_text_tokens = segments
+            .iter()
+            .flat_map(|segment| &segment.tokens)
+            .any(|token| !token.text.starts_with("[_"));
+        if valid && (has_duration || !has_text_tokens) {
+            return (segments, true);
+        }
+        for segment in &mut segments {
+            for token in &mut segment.tokens {
+                token.start = segment.start;
+                token.end = segment.end;
+            }
+        }
+        (segments, false)
+    }
 
-        let num_segments = state
-            .full_n_segments()
-            .expect("failed to get number of segments");
+    /// `samples`, padded with silence to `MIN_CLIP_SAMPLES` if shorter.
+    fn pad_short_clip(samples: &[f32]) -> Cow<'_, [f32]> {
+        if samples.len() >= MIN_CLIP_SAMPLES {
+            return Cow::Borrowed(samples);
+        }
+        log::info!(
+            "Padding {}ms of audio to {}ms",
+            samples.len() * 1000 / WHISPER_SAMPLE_RATE,
+            MIN_CLIP_SAMPLES * 1000 / WHISPER_SAMPLE_RATE
+        );
+        let mut padded = samples.to_vec();
+        padded.resize(MIN_CLIP_SAMPLES, 0.0);
+        Cow::Owned(padded)
+    }
 
-        // Collect segments instead of printing them
-        let mut segments = Vec::new();
-        for i in 0..num_segments {
-            let segment = state
-                .full_get_segment_text(i)
-                .expect("failed to get segment");
-            let start_timestamp = state
-                .full_get_segment_t0(i)
-                .expect("failed to get start timestamp");
-            let end_timestamp = state
-                .full_get_segment_t1(i)
-                .expect("failed to get end timestamp");
-
-            segments.push((start_timestamp, end_timestamp, segment));
+    /// Drops what whisper made of the padding after `clip_end` (in
+    /// centiseconds): segments starting in it, times reaching into it and
+    /// empty segments left at the end.
+    fn trim_padding(segments: Vec<Segment>, clip_end: i64) -> Vec<Segment> {
+        let mut segments: Vec<Segment> = segments
+            .into_iter()
+            .filter(|segment| segment.start < clip_end)
+            .map(|mut segment| {
+                segment.end = segment.end.min(clip_end);
+                for token in &mut segment.tokens {
+                    token.start = token.start.min(clip_end);
+                    token.end = token.end.min(clip_end);
+                }
+                segment
+            })
+            .collect();
+        while segments
+            .last()
+            .is_some_and(|segment| Self::clean_text(&segment.text).is_empty())
+        {
+            segments.pop();
         }
-        println!("took {}ms", (et - st).as_millis());
+        segments
+    }
 
-        let result = match settings.output_format {
-            OutputFormat::Txt => segments
-                .into_iter()
-                .map(|(_, _, text)| text)
-                .collect::<Vec<_>>()
-                .join("\n"),
-            OutputFormat::Srt => {
-                let mut srt_content = String::new();
-                for (index, (start, end, text)) in segments.iter().enumerate() {
-                    let start_ms = start * 10;
-                    let end_ms = end * 10;
-
-                    let start_hours = start_ms / 3600000;
-                    let start_minutes = (start_ms % 3600000) / 60000;
-                    let start_seconds = (start_ms % 60000) / 1000;
-                    let start_millis = start_ms % 1000;
-
-                    let end_hours = end_ms / 3600000;
-                    let end_minutes = (end_ms % 3600000) / 60000;
-                    let end_seconds = (end_ms % 60000) / 1000;
-                    let end_millis = end_ms % 1000;
-
-                    srt_content.push_str(&format!(
-                        "{}\n{:02}:{:02}:{:02},{:03} --> {:02}:{:02}:{:02},{:03}\n{}\n\n",
-                        index + 1,
-                        start_hours,
-                        start_minutes,
-                        start_seconds,
-                        start_millis,
-                        end_hours,
-                        end_minutes,
-                        end_seconds,
-                        end_millis,
-                        text
-                    ));
+    /// `bounds` in chunks of `MULTILANG_CHUNK_SAMPLES`, the last one absorbing
+    /// a remainder too short to detect a language from.
+    fn multilang_chunks(bounds: Range<usize>) -> Vec<Range<usize>> {
+        let mut chunks: Vec<Range<usize>> = bounds
+            .clone()
+            .step_by(MULTILANG_CHUNK_SAMPLES)
+            .map(|start| start..(start + MULTILANG_CHUNK_SAMPLES).min(bounds.end))
+            .collect();
+        if chunks.len() > 1 && chunks[chunks.len() - 1].len() < MIN_MULTILANG_CHUNK_SAMPLES {
+            let last = chunks.pop().unwrap_or_default();
+            if let Some(previous) = chunks.last_mut() {
+                previous.end = last.end;
+            }
+        }
+        chunks
+    }
+
+    /// The language detected most often, the earliest one on a tie.
+    fn main_language(languages: &[String]) -> Option<String> {
+        let mut counts: Vec<(&String, usize)> = Vec::new();
+        for language in languages {
+            match counts.iter_mut().find(|(seen, _)| *seen == language) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((language, 1)),
+            }
+        }
+        counts
+            .iter()
+            .rev()
+            .max_by_key(|(_, count)| *count)
+            .map(|(language, _)| (*language).clone())
+    }
+
+    /// A quarter of the logical CPUs, leaving the rest for other work.
+    fn background_threads() -> i32 {
+        let cpus = std::thread::available_parallelism().map_or(1, |n| n.get());
+        (cpus / 4).max(1) as i32
+    }
+
+    /// Lowers the calling thread's priority. On Linux the nice value is per
+    /// thread and inherited by the threads whisper starts. macOS and Windows
+    /// have no equivalent here, so background mode only reduces the thread
+    /// count there.
+    fn lower_thread_priority() {
+        #[cfg(target_os = "linux")]
+        {
+            // `who` 0 is the calling thread under Linux's per-thread nice values
+            let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, BACKGROUND_NICE) };
+            if result != 0 {
+                log::warn!(
+                    "Failed to lower transcription priority: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+        }
+    }
+
+    /// The `audio_ctx` to pass to whisper, if any; 0 means the full context.
+    fn audio_ctx(settings: &TranscriptionSettings) -> Option<i32> {
+        settings.audio_ctx.filter(|frames| *frames > 0)
+    }
+
+    /// Sets one of `ADVANCED_PARAMS`. Returns `false` for an unknown key.
+    fn apply_advanced_param(params: &mut FullParams, key: &str, value: f32) -> bool {
+        let int = value.round() as i32;
+        match key {
+            "n_threads" => params.set_n_threads(int),
+            "n_max_text_ctx" => params.set_n_max_text_ctx(int),
+            "offset_ms" => params.set_offset_ms(int),
+            "duration_ms" => params.set_duration_ms(int),
+            "max_len" => params.set_max_len(int),
+            "max_tokens" => params.set_max_tokens(int),
+            "audio_ctx" => params.set_audio_ctx(int),
+            "thold_pt" => params.set_thold_pt(value),
+            "thold_ptsum" => params.set_thold_ptsum(value),
+            "temperature" => params.set_temperature(value),
+            "temperature_inc" => params.set_temperature_inc(value),
+            "max_initial_ts" => params.set_max_initial_ts(value),
+            "length_penalty" => params.set_length_penalty(value),
+            "entropy_thold" => params.set_entropy_thold(value),
+            "logprob_thold" => params.set_logprob_thold(value),
+            "no_speech_thold" => params.set_no_speech_thold(value),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Renders segments in the given format, shifted by the options' offset.
+    pub fn format_segments(
+        segments: &[Segment],
+        format: &OutputFormat,
+        options: &FormatOptions,
+    ) -> Result<String> {
+        let segments = &Self::offset_segments(segments, options.timestamp_offset_ms);
+        match format {
+            OutputFormat::Json => Self::format_json(segments, options),
+            OutputFormat::Txt => Ok(Self::format_cues(segments, format, options)),
+            OutputFormat::Srt | OutputFormat::Vtt => {
+                let cues: Vec<Segment> =
+                    Self::merge_short_segments(segments, options.min_cue_duration_ms)
+                        .into_iter()
+                        .flat_map(|cue| Self::split_long_cue(cue, options.max_cue_duration_ms))
+                        .collect();
+                Ok(Self::format_cues(&cues, format, options))
+            }
+        }
+    }
+
+    fn format_cues(cues: &[Segment], format: &OutputFormat, options: &FormatOptions) -> String {
+        let mut content = Self::format_header(format, options);
+        let mut previous_start = None;
+        for (index, cue) in cues.iter().enumerate() {
+            content.push_str(&Self::format_chapters(format, previous_start, cue, options));
+            content.push_str(&Self::format_cue(format, index, cue, options));
+            previous_start = Some(cue.start);
+        }
+        content
+    }
+
+    /// Whether any segment has text. Every format still writes a valid, empty
+    /// file when there is none.
+    pub fn has_speech(segments: &[Segment]) -> bool {
+        segments
+            .iter()
+            .any(|segment| !segment.text.trim().is_empty())
+    }
+
+    /// Mean log probability of the text tokens, whisper's `avg_logprob`.
+    /// Special tokens such as timestamps are left out. `None` without tokens.
+    pub fn avg_logprob(segments: &[Segment]) -> Option<f32> {
+        let logprobs: Vec<f32> = segments
+            .iter()
+            .flat_map(|segment| &segment.tokens)
+            .filter(|token| !token.text.starts_with("[_"))
+            .map(|token| token.probability.max(f32::MIN_POSITIVE).ln())
+            .collect();
+        if logprobs.is_empty() {
+            return None;
+        }
+        Some(logprobs.iter().sum::<f32>() / logprobs.len() as f32)
+    }
+
+    /// Segments with `offset_ms` added to every segment and token time.
+    pub fn offset_segments(segments: &[Segment], offset_ms: i64) -> Vec<Segment> {
+        let offset = offset_ms / 10;
+        segments
+            .iter()
+            .map(|segment| Segment {
+                start: segment.start + offset,
+                end: segment.end + offset,
+                text: segment.text.clone(),
+                language: segment.language.clone(),
+                tokens: segment
+                    .tokens
+                    .iter()
+                    .map(|token| Token {
+                        start: token.start + offset,
+                        end: token.end + offset,
+                        ..token.clone()
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Everything before the first cue of a TXT, SRT or VTT file.
+    pub(crate) fn format_header(format: &OutputFormat, options: &FormatOptions) -> String {
+        let lines = options.metadata.as_deref();
+        match (format, lines) {
+            (OutputFormat::Txt, Some(lines)) => {
+                let mut header: String = lines.iter().map(|line| format!("# {}\n", line)).collect();
+                header.push('\n');
+                header
+            }
+            // SRT has no comment syntax, so the header goes into a zero-length
+            // first cue that players never display
+            (OutputFormat::Srt, Some(lines)) => {
+                format!("1\n00:00:00,000 --> 00:00:00,000\n{}\n\n", lines.join("\n"))
+            }
+            (OutputFormat::Vtt, lines) => {
+                let mut header = String::from("WEBVTT\n\n");
+                if let Some(lines) = lines {
+                    header.push_str(&format!("NOTE\n{}\n\n", lines.join("\n")));
                 }
-                srt_content
-            }
-            OutputFormat::Vtt => {
-                let mut vtt_content = String::from("WEBVTT\n\n");
-                for (start, end, text) in segments.iter() {
-                    let start_ms = start * 10;
-                    let end_ms = end * 10;
-
-                    let start_hours = start_ms / 3600000;
-                    let start_minutes = (start_ms % 3600000) / 60000;
-                    let start_seconds = (start_ms % 60000) / 1000;
-                    let start_millis = start_ms % 1000;
-
-                    let end_hours = end_ms / 3600000;
-                    let end_minutes = (end_ms % 3600000) / 60000;
-                    let end_seconds = (end_ms % 60000) / 1000;
-                    let end_millis = end_ms % 1000;
-
-                    vtt_content.push_str(&format!(
-                        "{:02}:{:02}:{:02}.{:03} --> {:02}:{:02}:{:02}.{:03}\n{}\n\n",
-                        start_hours,
-                        start_minutes,
-                        start_seconds,
-                        start_millis,
-                        end_hours,
-                        end_minutes,
-                        end_seconds,
-                        end_millis,
-                        text
+                if let Some(language) = &options.language {
+                    header.push_str(&format!(
+                        "NOTE\nLanguage: {} ({})\n\n",
+                        language.name, language.code
                     ));
                 }
-                vtt_content
-            }
-            OutputFormat::Json => {
-                let json_segments: Vec<serde_json::Value> = segments
-                    .into_iter()
-                    .enumerate()
-                    .map(|(index, (start, end, text))| {
-                        serde_json::json!({
-                            "id": index,
-                            "start": start as f64 / 100.0, // Convert centiseconds to seconds
-                            "end": end as f64 / 100.0,
-                            "text": text
-                        })
-                    })
-                    .collect();
+                header
+            }
+            _ => String::new(),
+        }
+    }
+
+    /// The cue at `index` of a TXT, SRT or VTT file, counting from 0. SRT and
+    /// VTT cues are expected to have been through `merge_short_segments`.
+    pub(crate) fn format_cue(
+        format: &OutputFormat,
+        index: usize,
+        cue: &Segment,
+        options: &FormatOptions,
+    ) -> String {
+        let text = Self::clean_text(&cue.text);
+        match format {
+            OutputFormat::Srt => {
+                let number = index + if options.metadata.is_some() { 2 } else { 1 };
+                format!(
+                    "{}\n{} --> {}\n{}\n\n",
+                    number,
+                    Self::format_timestamp(cue.start, ','),
+                    Self::format_timestamp(cue.end, ','),
+                    Self::wrap_subtitle_text(&text, options.max_line_chars)
+                )
+            }
+            OutputFormat::Vtt => format!(
+                "{} --> {}{}\n{}\n\n",
+                Self::format_timestamp(cue.start, '.'),
+                Self::format_timestamp(cue.end, '.'),
+                options.vtt_cue_settings,
+                Self::wrap_subtitle_text(&text, options.max_line_chars)
+            ),
+            _ if index == 0 => text,
+            _ => format!("\n{}", text),
+        }
+    }
+
+    /// Headings for the chapters that start after the previous cue did and by
+    /// the time `cue` does, to go before it. SRT has nowhere to put them.
+    pub(crate) fn format_chapters(
+        format: &OutputFormat,
+        previous_start: Option<i64>,
+        cue: &Segment,
+        options: &FormatOptions,
+    ) -> String {
+        options
+            .chapters
+            .iter()
+            .filter(|chapter| {
+                previous_start.map_or(true, |start| chapter.start_ms > start * 10)
+                    && chapter.start_ms <= cue.start * 10
+            })
+            .map(|chapter| {
+                let title = Self::clean_text(&chapter.title);
+                match (format, previous_start) {
+                    // A NOTE can't contain the cue timing arrow
+                    (OutputFormat::Vtt, _) => {
+                        format!("NOTE\nChapter: {}\n\n", title.replace("-->", "->"))
+                    }
+                    (OutputFormat::Txt, None) => format!("## {}\n", title),
+                    (OutputFormat::Txt, Some(_)) => format!("\n\n## {}", title),
+                    _ => String::new(),
+                }
+            })
+            .collect()
+    }
 
-                let json_data = serde_json::json!({
-                    "segments": json_segments
+    /// Segment text as it is written out: without the space whisper puts in
+    /// front, control characters, which could end a cue early, or the
+    /// replacement characters left by split multibyte sequences.
+    pub(crate) fn clean_text(text: &str) -> String {
+        let cleaned: String = text
+            .chars()
+            .filter(|c| *c != char::REPLACEMENT_CHARACTER)
+            .map(|c| if c.is_whitespace() { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .collect();
+        cleaned.trim().to_string()
+    }
+
+    /// VTT cue settings for the given `align:` and `line:` values. Values the
+    /// WebVTT spec doesn't allow are logged and left out.
+    fn vtt_cue_settings(align: Option<&str>, line: Option<&str>) -> String {
+        let mut cue_settings = String::new();
+        if let Some(align) = align {
+            if VTT_ALIGNMENTS.contains(&align) {
+                cue_settings.push_str(&format!(" align:{}", align));
+            } else {
+                log::warn!("Ignoring invalid VTT alignment '{}'", align);
+            }
+        }
+        if let Some(line) = line {
+            if Self::is_valid_vtt_line(line) {
+                cue_settings.push_str(&format!(" line:{}", line));
+            } else {
+                log::warn!("Ignoring invalid VTT line '{}'", line);
+            }
+        }
+        cue_settings
+    }
+
+    /// A line number such as `-1` or a percentage such as `90%`, optionally
+    /// followed by `,start`, `,center` or `,end`.
+    fn is_valid_vtt_line(line: &str) -> bool {
+        let (position, line_align) = match line.split_once(',') {
+            Some((position, line_align)) => (position, Some(line_align)),
+            None => (line, None),
+        };
+        if line_align.is_some_and(|a| !["start", "center", "end"].contains(&a)) {
+            return false;
+        }
+        match position.strip_suffix('%') {
+            Some(percent) => {
+                percent.chars().all(|c| c.is_ascii_digit() || c == '.')
+                    && percent.parse::<f64>().is_ok_and(|p| p <= 100.0)
+            }
+            None => {
+                let digits = position.strip_prefix('-').unwrap_or(position);
+                !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit())
+            }
+        }
+    }
+
+    fn format_json(segments: &[Segment], options: &FormatOptions) -> Result<String> {
+        let json_segments: Vec<serde_json::Value> = segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                let mut json_segment = serde_json::json!({
+                    "id": index,
+                    "start": segment.start as f64 / 100.0, // Convert centiseconds to seconds
+                    "end": segment.end as f64 / 100.0,
+                    "text": Self::clean_text(&segment.text)
                 });
-                serde_json::to_string_pretty(&json_data)?
+                if let Some(language) = &segment.language {
+                    json_segment["language"] = language.clone().into();
+                }
+                json_segment
+            })
+            .collect();
+
+        let mut json_data = serde_json::json!({
+            "segments": json_segments
+        });
+        if let Some(language) = &options.language {
+            json_data["language"] = language.code.clone().into();
+            json_data["language_name"] = language.name.clone().into();
+        }
+        if !options.chapters.is_empty() {
+            let chapters: Vec<serde_json::Value> = options
+                .chapters
+                .iter()
+                .map(|chapter| {
+                    serde_json::json!({
+                        "title": chapter.title,
+                        "start": chapter.start_ms as f64 / 1000.0,
+                        "end": chapter.end_ms as f64 / 1000.0
+                    })
+                })
+                .collect();
+            json_data["chapters"] = chapters.into();
+        }
+        Ok(serde_json::to_string_pretty(&json_data)?)
+    }
+
+    /// `HH:MM:SS<separator>mmm` from centiseconds; SRT separates milliseconds
+    /// with a comma and VTT with a dot.
+    fn format_timestamp(centiseconds: i64, separator: char) -> String {
+        let ms = centiseconds * 10;
+        format!(
+            "{:02}:{:02}:{:02}{}{:03}",
+            ms / 3600000,
+            (ms % 3600000) / 60000,
+            (ms % 60000) / 1000,
+            separator,
+            ms % 1000
+        )
+    }
+
+    /// Collapses runs of `MIN_REPETITION_RUN` or more consecutive segments whose
+    /// text only differs in case and punctuation into the first of them,
+    /// stretched over the whole run.
+    pub(crate) fn collapse_repetitions(segments: Vec<Segment>) -> Vec<Segment> {
+        let mut collapsed: Vec<Segment> = Vec::with_capacity(segments.len());
+        let mut segments = segments.into_iter().peekable();
+        while let Some(segment) = segments.next() {
+            let key = Self::repetition_key(&segment.text);
+            let mut run = vec![segment];
+            while let Some(next) = segments.next_if(|next| Self::repetition_key(&next.text) == key)
+            {
+                run.push(next);
+            }
+            collapsed.extend(Self::collapse_run(run, &key));
+        }
+        collapsed
+    }
+
+    /// Segment text compared by `collapse_repetitions`.
+    pub(crate) fn repetition_key(text: &str) -> String {
+        text.chars()
+            .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+            .flat_map(char::to_lowercase)
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// One run of segments sharing `key`, collapsed if it's long enough.
+    pub(crate) fn collapse_run(run: Vec<Segment>, key: &str) -> Vec<Segment> {
+        if run.len() < MIN_REPETITION_RUN || key.is_empty() {
+            return run;
+        }
+        let mut segment = run[0].clone();
+        log::warn!(
+            "Collapsed {} repeated segments at {:.2}s: {}",
+            run.len(),
+            segment.start as f64 / 100.0,
+            segment.text.trim()
+        );
+        segment.end = run[run.len() - 1].end;
+        vec![segment]
+    }
+
+    /// Samples covering `range_ms`, clipped to the audio.
+    fn range_bounds(len: usize, range_ms: &Range<u64>) -> Result<Range<usize>> {
+        let to_sample = |ms: u64| {
+            (ms as usize)
+                .saturating_mul(WHISPER_SAMPLE_RATE / 1000)
+                .min(len)
+        };
+        let bounds = to_sample(range_ms.start)..to_sample(range_ms.end);
+        if bounds.is_empty() {
+            return Err(anyhow!(
+                "{:.1}s to {:.1}s is outside the audio",
+                range_ms.start as f64 / 1000.0,
+                range_ms.end as f64 / 1000.0
+            ));
+        }
+        Ok(bounds)
+    }
+
+    /// Samples between the leading and trailing silence, padded on both sides.
+    /// The whole range if nothing rises above the silence threshold.
+    fn speech_bounds(samples: &[f32]) -> Range<usize> {
+        let is_sound = |sample: &f32| sample.abs() > SILENCE_THRESHOLD;
+        match (
+            samples.iter().position(is_sound),
+            samples.iter().rposition(is_sound),
+        ) {
+            (Some(first), Some(last)) => {
+                first.saturating_sub(SILENCE_PADDING_SAMPLES)
+                    ..(last + 1 + SILENCE_PADDING_SAMPLES).min(samples.len())
+            }
+            _ => 0..samples.len(),
+        }
+    }
+
+    /// Merges each segment shorter than `min_duration_ms` into the one after it,
+    /// so rapid one-word segments don't flicker as subtitles. Timestamps are in
+    /// centiseconds, as whisper reports them.
+    fn merge_short_segments(segments: &[Segment], min_duration_ms: u64) -> Vec<Segment> {
+        let mut cues: Vec<Segment> = Vec::with_capacity(segments.len());
+        let mut pending: Option<Segment> = None;
+        for segment in segments {
+            cues.extend(Self::merge_short_segment(
+                &mut pending,
+                segment.clone(),
+                min_duration_ms,
+            ));
+        }
+
+        // A short final segment has nothing to merge into
+        cues.extend(pending);
+        cues
+    }
+
+    /// One step of `merge_short_segments`: adds `segment` to the short cue in
+    /// `pending`, if any, and returns the cue once it's long enough.
+    pub(crate) fn merge_short_segment(
+        pending: &mut Option<Segment>,
+        segment: Segment,
+        min_duration_ms: u64,
+    ) -> Option<Segment> {
+        let cue = match pending.take() {
+            Some(pending) => Segment {
+                start: pending.start,
+                end: segment.end,
+                text: format!("{} {}", pending.text.trim_end(), segment.text.trim_start()),
+                tokens: [pending.tokens, segment.tokens].concat(),
+                language: pending.language,
+            },
+            None => segment,
+        };
+
+        if (((cue.end - cue.start) * 10) as u64) < min_duration_ms {
+            *pending = Some(cue);
+            None
+        } else {
+            Some(cue)
+        }
+    }
+
+    /// Splits a cue longer than `max_duration_ms` into evenly timed, contiguous
+    /// cues, sharing out its words in proportion. A cue with fewer words than
+    /// pieces repeats its text in each. Timestamps are in centiseconds.
+    pub(crate) fn split_long_cue(cue: Segment, max_duration_ms: u64) -> Vec<Segment> {
+        let duration = cue.end - cue.start;
+        let max_duration = (max_duration_ms / 10) as i64;
+        if max_duration == 0 || duration <= max_duration {
+            return vec![cue];
+        }
+
+        let pieces = (duration + max_duration - 1) / max_duration;
+        let words: Vec<&str> = cue.text.split_whitespace().collect();
+        let words_per_piece = words.len() as f64 / pieces as f64;
+        (0..pieces)
+            .map(|piece| {
+                let start = cue.start + duration * piece / pieces;
+                let end = cue.start + duration * (piece + 1) / pieces;
+                let text = if (words.len() as i64) < pieces {
+                    cue.text.trim().to_string()
+                } else {
+                    let first = (piece as f64 * words_per_piece).round() as usize;
+                    let last = ((piece + 1) as f64 * words_per_piece).round() as usize;
+                    words[first..last].join(" ")
+                };
+                Segment {
+                    start,
+                    end,
+                    text,
+                    tokens: cue
+                        .tokens
+                        .iter()
+                        .filter(|token| token.start >= start && token.start < end)
+                        .cloned()
+                        .collect(),
+                    language: cue.language.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Breaks subtitle text into at most two lines on word boundaries, splitting
+    /// where the longer line is shortest. Text that can't fit in two lines of
+    /// `max_chars` still gets two lines rather than losing words.
+    fn wrap_subtitle_text(text: &str, max_chars: usize) -> String {
+        let trimmed = text.trim();
+        if max_chars == 0 || trimmed.chars().count() <= max_chars {
+            return text.to_string();
+        }
+
+        let words: Vec<&str> = trimmed.split_whitespace().collect();
+        (1..words.len())
+            .map(|split| (words[..split].join(" "), words[split..].join(" ")))
+            .min_by_key(|(first, second)| first.chars().count().max(second.chars().count()))
+            .map(|(first, second)| format!("{}\n{}", first, second))
+            .unwrap_or_else(|| text.to_string())
+    }
+
+    fn metadata_lines(source_path: &Path, settings: &TranscriptionSettings) -> Vec<String> {
+        let source_name = source_path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("Unknown");
+
+        vec![
+            format!("Source: {}", source_name),
+            format!("Model: {}", settings.model),
+            format!(
+                "Language: {}",
+                settings.language.as_deref().unwrap_or("auto")
+            ),
+            format!("Date: {}", chrono::Local::now().format("%Y-%m-%d %H:%M:%S")),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_subtitle_text_splits_long_segment() {
+        let text = " The quick brown fox jumps over the lazy dog while the band plays on.";
+        let wrapped = WhisperTranscriber::wrap_subtitle_text(text, 42);
+
+        let lines: Vec<&str> = wrapped.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines.iter().all(|line| line.chars().count() <= 42));
+        assert_eq!(lines.join(" "), text.trim());
+    }
+
+    #[test]
+    fn test_supported_languages_for_english_only_model() {
+        let languages = WhisperTranscriber::supported_languages("ggml-base.en.bin");
+        assert_eq!(languages.len(), 1);
+        assert_eq!(languages[0].code, "en");
+        assert_eq!(languages[0].name, "English");
+
+        let languages = WhisperTranscriber::supported_languages("ggml-base.bin");
+        assert!(languages.iter().any(|l| l.code == "ja"));
+    }
+
+    #[test]
+    fn test_resolve_language_for_english_only_model() {
+        assert_eq!(
+            WhisperTranscriber::resolve_language("ggml-small.en.bin", None).unwrap(),
+            Some("en".to_string())
+        );
+        assert!(WhisperTranscriber::resolve_language("ggml-small.en.bin", Some("de")).is_err());
+        assert_eq!(
+            WhisperTranscriber::resolve_language("ggml-small.bin", Some("de")).unwrap(),
+            Some("de".to_string())
+        );
+    }
+
+    fn segment(start: i64, end: i64, text: &str) -> Segment {
+        Segment {
+            start,
+            end,
+            text: text.to_string(),
+            tokens: Vec::new(),
+            language: None,
+        }
+    }
+
+    fn sample_segments() -> Vec<Segment> {
+        vec![
+            segment(0, 150, " Hello there."),
+            segment(150, 372525, " General Kenobi."),
+        ]
+    }
+
+    fn plain_options() -> FormatOptions {
+        FormatOptions {
+            metadata: None,
+            max_line_chars: 0,
+            min_cue_duration_ms: 0,
+            max_cue_duration_ms: 0,
+            vtt_cue_settings: String::new(),
+            timestamp_offset_ms: 0,
+            language: None,
+            chapters: Vec::new(),
+        }
+    }
+
+    /// Every format of `segments` shifted by `offset_ms`, for checking that
+    /// output times refer to the source rather than the trimmed audio.
+    fn format_all_with_offset(segments: &[Segment], offset_ms: i64) -> Vec<(OutputFormat, String)> {
+        let options = FormatOptions {
+            timestamp_offset_ms: offset_ms,
+            ..plain_options()
+        };
+        [
+            OutputFormat::Txt,
+            OutputFormat::Srt,
+            OutputFormat::Vtt,
+            OutputFormat::Json,
+        ]
+        .into_iter()
+        .map(|format| {
+            let text = WhisperTranscriber::format_segments(segments, &format, &options).unwrap();
+            (format, text)
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_audio_ctx_zero_means_full_context() {
+        let mut settings = TranscriptionSettings::default();
+        assert_eq!(WhisperTranscriber::audio_ctx(&settings), None);
+        settings.audio_ctx = Some(0);
+        assert_eq!(WhisperTranscriber::audio_ctx(&settings), None);
+        settings.audio_ctx = Some(768);
+        assert_eq!(WhisperTranscriber::audio_ctx(&settings), Some(768));
+    }
+
+    #[test]
+    fn test_reports_backend() {
+        let cpu_only = "WHISPER : COREML = 0 | OPENVINO = 0 | CPU : SSE3 = 1 | AVX = 1 |";
+        assert!(!WhisperTranscriber::reports_backend(cpu_only, "CUDA"));
+        assert!(!WhisperTranscriber::reports_backend(cpu_only, "Metal"));
+
+        let cuda = "WHISPER : COREML = 0 | CUDA : ARCHS = 860 | USE_GRAPHS = 1 | CPU : AVX = 1 |";
+        assert!(WhisperTranscriber::reports_backend(cuda, "CUDA"));
+
+        let older = "AVX = 1 | METAL = 1 | CUDA = 0 |";
+        assert!(WhisperTranscriber::reports_backend(older, "Metal"));
+        assert!(!WhisperTranscriber::reports_backend(older, "CUDA"));
+    }
+
+    #[test]
+    fn test_apply_advanced_param() {
+        let mut params = FullParams::new(SamplingStrategy::default());
+        for key in ADVANCED_PARAMS {
+            assert!(
+                WhisperTranscriber::apply_advanced_param(&mut params, key, 1.0),
+                "{} is listed but not applied",
+                key
+            );
+        }
+        assert!(!WhisperTranscriber::apply_advanced_param(
+            &mut params,
+            "beam_size",
+            5.0
+        ));
+    }
+
+    #[test]
+    fn test_format_txt() {
+        let text = WhisperTranscriber::format_segments(
+            &sample_segments(),
+            &OutputFormat::Txt,
+            &FormatOptions {
+                metadata: Some(vec!["Model: ggml-base.bin".to_string()]),
+                ..plain_options()
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            "# Model: ggml-base.bin\n\nHello there.\nGeneral Kenobi."
+        );
+    }
+
+    #[test]
+    fn test_format_srt() {
+        let text = WhisperTranscriber::format_segments(
+            &sample_segments(),
+            &OutputFormat::Srt,
+            &plain_options(),
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n\
+             2\n00:00:01,500 --> 01:02:05,250\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_long_cue_is_split_into_contiguous_cues() {
+        // 20 seconds, with a 7 second cap
+        let cue = segment(100, 2100, " one two three four five six");
+        let cues = WhisperTranscriber::split_long_cue(cue, 7000);
+
+        assert_eq!(cues.len(), 3);
+        assert_eq!(cues[0].start, 100);
+        assert_eq!(cues[2].end, 2100);
+        for pair in cues.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert!(cues.iter().all(|cue| cue.end - cue.start <= 700));
+        let texts: Vec<&str> = cues.iter().map(|cue| cue.text.as_str()).collect();
+        assert_eq!(texts, vec!["one two", "three four", "five six"]);
+
+        // Too few words to share out, so the text repeats
+        let cues = WhisperTranscriber::split_long_cue(segment(0, 1500, " Music"), 7000);
+        assert_eq!(cues.len(), 3);
+        assert!(cues.iter().all(|cue| cue.text == "Music"));
+
+        let short = segment(0, 500, " Short");
+        assert_eq!(
+            WhisperTranscriber::split_long_cue(short.clone(), 7000),
+            vec![short]
+        );
+    }
+
+    #[test]
+    fn test_format_vtt() {
+        let text = WhisperTranscriber::format_segments(
+            &sample_segments(),
+            &OutputFormat::Vtt,
+            &plain_options(),
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.500\nHello there.\n\n\
+             00:00:01.500 --> 01:02:05.250\nGeneral Kenobi.\n\n"
+        );
+    }
+
+    #[test]
+    fn test_timestamp_offset_applies_to_every_format() {
+        for (format, text) in format_all_with_offset(&sample_segments(), 10_000) {
+            match format {
+                OutputFormat::Txt => assert_eq!(text, "Hello there.\nGeneral Kenobi."),
+                OutputFormat::Srt => {
+                    assert!(text.contains("00:00:10,000 --> 00:00:11,500\n"));
+                    assert!(text.contains("00:00:11,500 --> 01:02:15,250\n"));
+                }
+                OutputFormat::Vtt => {
+                    assert!(text.contains("00:00:10.000 --> 00:00:11.500\n"));
+                    assert!(text.contains("00:00:11.500 --> 01:02:15.250\n"));
+                }
+                OutputFormat::Json => {
+                    let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+                    assert_eq!(json["segments"][0]["start"], 10.0);
+                    assert_eq!(json["segments"][1]["end"], 3735.25);
+                }
             }
+        }
+
+        // Without an offset, times are left as whisper reported them
+        let (_, srt) = &format_all_with_offset(&sample_segments(), 0)[1];
+        assert!(srt.contains("00:00:00,000 --> 00:00:01,500\n"));
+    }
+
+    #[test]
+    fn test_vtt_cue_settings() {
+        let options = FormatOptions {
+            vtt_cue_settings: WhisperTranscriber::vtt_cue_settings(Some("center"), Some("90%")),
+            ..plain_options()
+        };
+        let text =
+            WhisperTranscriber::format_segments(&sample_segments(), &OutputFormat::Vtt, &options)
+                .unwrap();
+        assert!(text.contains("00:00:00.000 --> 00:00:01.500 align:center line:90%\n"));
+
+        assert_eq!(
+            WhisperTranscriber::vtt_cue_settings(None, Some("-2,end")),
+            " line:-2,end"
+        );
+        // Invalid values are dropped rather than written into the file
+        assert_eq!(
+            WhisperTranscriber::vtt_cue_settings(Some("middle"), Some("120%")),
+            ""
+        );
+        assert_eq!(
+            WhisperTranscriber::vtt_cue_settings(None, Some("5,top")),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_multilang_chunks_absorb_short_remainder() {
+        let chunks = WhisperTranscriber::multilang_chunks(0..MULTILANG_CHUNK_SAMPLES * 2 + 1000);
+        assert_eq!(
+            chunks,
+            vec![
+                0..MULTILANG_CHUNK_SAMPLES,
+                MULTILANG_CHUNK_SAMPLES..MULTILANG_CHUNK_SAMPLES * 2 + 1000,
+            ]
+        );
+        assert_eq!(WhisperTranscriber::multilang_chunks(5..10), vec![5..10]);
+
+        let languages = ["en", "zh", "zh", "en"].map(String::from);
+        assert_eq!(
+            WhisperTranscriber::main_language(&languages),
+            Some("en".to_string())
+        );
+    }
+
+    #[test]
+    fn test_segment_text_is_cleaned_in_every_format() {
+        let segments = vec![
+            segment(0, 100, "  Hello\u{0}\u{7} there.\u{FFFD} "),
+            segment(100, 200, " Two\nlines\r\n"),
+        ];
+        let options = plain_options();
+
+        let srt =
+            WhisperTranscriber::format_segments(&segments, &OutputFormat::Srt, &options).unwrap();
+        assert_eq!(
+            srt,
+            "1\n00:00:00,000 --> 00:00:01,000\nHello there.\n\n\
+             2\n00:00:01,000 --> 00:00:02,000\nTwo lines\n\n"
+        );
+        let txt =
+            WhisperTranscriber::format_segments(&segments, &OutputFormat::Txt, &options).unwrap();
+        assert_eq!(txt, "Hello there.\nTwo lines");
+        let json =
+            WhisperTranscriber::format_segments(&segments, &OutputFormat::Json, &options).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["segments"][0]["text"], "Hello there.");
+    }
+
+    #[test]
+    fn test_chapters_head_their_first_cue() {
+        let segments = vec![
+            segment(0, 100, " Welcome."),
+            segment(100, 200, " Still intro."),
+            segment(200, 300, " Main part."),
+        ];
+        let chapter = |title: &str, start_ms: i64, end_ms: i64| Chapter {
+            title: title.to_string(),
+            start_ms,
+            end_ms,
         };
+        let options = FormatOptions {
+            chapters: vec![chapter("Intro", 0, 1500), chapter("Main", 1500, 3000)],
+            ..plain_options()
+        };
+
+        let txt =
+            WhisperTranscriber::format_segments(&segments, &OutputFormat::Txt, &options).unwrap();
+        assert_eq!(
+            txt,
+            "## Intro\nWelcome.\nStill intro.\n\n## Main\nMain part."
+        );
+        let vtt =
+            WhisperTranscriber::format_segments(&segments, &OutputFormat::Vtt, &options).unwrap();
+        assert!(vtt.starts_with("WEBVTT\n\nNOTE\nChapter: Intro\n\n00:00:00.000 -->"));
+        assert!(vtt.contains("Still intro.\n\nNOTE\nChapter: Main\n\n00:00:02.000 -->"));
+        let json =
+            WhisperTranscriber::format_segments(&segments, &OutputFormat::Json, &options).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["chapters"][1]["title"], "Main");
+        assert_eq!(json["chapters"][1]["start"], 1.5);
+
+        // Without chapters the output is unchanged
+        let json =
+            WhisperTranscriber::format_segments(&segments, &OutputFormat::Json, &plain_options())
+                .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(json.get("chapters").is_none());
+    }
+
+    #[test]
+    fn test_format_json() {
+        let text = WhisperTranscriber::format_segments(
+            &sample_segments(),
+            &OutputFormat::Json,
+            &plain_options(),
+        )
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(json["segments"][1]["id"], 1);
+        assert_eq!(json["segments"][1]["start"], 1.5);
+        assert_eq!(json["segments"][1]["end"], 3725.25);
+        assert_eq!(json["segments"][1]["text"], "General Kenobi.");
+        assert!(json.get("language").is_none());
+    }
+
+    #[test]
+    fn test_detected_language_in_output() {
+        let options = FormatOptions {
+            language: WhisperTranscriber::language("de"),
+            ..plain_options()
+        };
+        let json =
+            WhisperTranscriber::format_segments(&sample_segments(), &OutputFormat::Json, &options)
+                .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["language"], "de");
+        assert_eq!(json["language_name"], "German");
+
+        let vtt =
+            WhisperTranscriber::format_segments(&sample_segments(), &OutputFormat::Vtt, &options)
+                .unwrap();
+        assert!(vtt.starts_with("WEBVTT\n\nNOTE\nLanguage: German (de)\n\n"));
+        assert!(WhisperTranscriber::language("xx").is_none());
+    }
+
+    #[test]
+    fn test_collapse_repetitions() {
+        let mut segments = vec![segment(0, 150, " Welcome back.")];
+        for i in 0..6 {
+            let start = 150 + i * 100;
+            segments.push(segment(start, start + 100, " Thank you for watching!"));
+        }
+        segments.push(segment(750, 850, " thank you for watching"));
+        segments.push(segment(850, 950, " Bye."));
+        segments.push(segment(950, 1000, " Bye."));
+
+        let collapsed = WhisperTranscriber::collapse_repetitions(segments);
+        assert_eq!(
+            collapsed,
+            vec![
+                segment(0, 150, " Welcome back."),
+                segment(150, 850, " Thank you for watching!"),
+                // Runs shorter than the threshold are left alone
+                segment(850, 950, " Bye."),
+                segment(950, 1000, " Bye."),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_avg_logprob_skips_special_tokens() {
+        let token = |text: &str, probability: f32| Token {
+            id: 0,
+            text: text.to_string(),
+            start: 0,
+            end: 0,
+            probability,
+        };
+        let mut spoken = segment(0, 100, " Hi there");
+        spoken.tokens = vec![
+            token("[_BEG_]", 0.01),
+            token(" Hi", 1.0),
+            token(" there", (-2.0f32).exp()),
+        ];
+
+        let avg = WhisperTranscriber::avg_logprob(&[spoken]).unwrap();
+        assert!((avg + 1.0).abs() < 1e-6);
+        assert_eq!(
+            WhisperTranscriber::avg_logprob(&[segment(0, 100, "")]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_unusable_token_times_fall_back_to_segment_times() {
+        let token = |text: &str, start: i64, end: i64| Token {
+            id: 0,
+            text: text.to_string(),
+            start,
+            end,
+            probability: 0.9,
+        };
+        let mut timed = segment(100, 300, " Hello there");
+        timed.tokens = vec![
+            token("[_BEG_]", 0, 0),
+            token(" Hello", 100, 180),
+            token(" there", 180, 300),
+        ];
+        let (segments, word_timestamps) =
+            WhisperTranscriber::with_usable_token_times(vec![timed.clone()]);
+        assert!(word_timestamps);
+        assert_eq!(segments, vec![timed.clone()]);
+
+        // A build without token timestamps reports -1 for every token
+        for token in &mut timed.tokens {
+            token.start = -1;
+            token.end = -1;
+        }
+        let (segments, word_timestamps) = WhisperTranscriber::with_usable_token_times(vec![timed]);
+        assert!(!word_timestamps);
+        assert!(segments[0]
+            .tokens
+            .iter()
+            .all(|token| token.start == 100 && token.end == 300));
+    }
+
+    #[test]
+    fn test_silent_audio_is_no_speech_not_an_error() {
+        let path = std::env::temp_dir().join(format!("silent-{}.wav", std::process::id()));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: WHISPER_SAMPLE_RATE as u32,
+            bits_per_sample: 16,
+            sample_format: SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..WHISPER_SAMPLE_RATE * 2 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let transcription = WhisperTranscriber::new()
+            .transcribe_segments(&path, &TranscriptionSettings::default(), None, None)
+            .expect("Silence should not be an error");
+        std::fs::remove_file(&path).unwrap();
+        assert!(!WhisperTranscriber::has_speech(&transcription.segments));
+
+        let options = FormatOptions::from_settings(&path, &TranscriptionSettings::default());
+        let vtt = WhisperTranscriber::format_segments(
+            &transcription.segments,
+            &OutputFormat::Vtt,
+            &options,
+        )
+        .unwrap();
+        assert_eq!(vtt, "WEBVTT\n\n");
+    }
+
+    #[test]
+    fn test_short_clip_is_padded_and_trimmed() {
+        // 0.2 s of sound
+        let clip = vec![0.5f32; WHISPER_SAMPLE_RATE / 5];
+        let padded = WhisperTranscriber::pad_short_clip(&clip);
+        assert_eq!(padded.len(), MIN_CLIP_SAMPLES);
+        assert_eq!(&padded[..clip.len()], &clip[..]);
+        assert!(padded[clip.len()..].iter().all(|sample| *sample == 0.0));
+
+        let long = vec![0.5f32; MIN_CLIP_SAMPLES];
+        assert!(matches!(
+            WhisperTranscriber::pad_short_clip(&long),
+            Cow::Borrowed(_)
+        ));
+
+        let segments = vec![
+            segment(0, 60, " Hi."),
+            segment(15, 60, " "),
+            segment(20, 100, " [BLANK_AUDIO]"),
+        ];
+        assert_eq!(
+            WhisperTranscriber::trim_padding(segments, 20),
+            vec![segment(0, 20, " Hi.")]
+        );
+        assert!(WhisperTranscriber::trim_padding(vec![segment(0, 100, "")], 20).is_empty());
+    }
+
+    #[test]
+    fn test_speech_bounds_keeps_padding() {
+        let mut samples = vec![0.0f32; WHISPER_SAMPLE_RATE * 4];
+        samples[WHISPER_SAMPLE_RATE] = 0.5;
+        samples[WHISPER_SAMPLE_RATE * 2] = -0.5;
+
+        let bounds = WhisperTranscriber::speech_bounds(&samples);
+        assert_eq!(bounds.start, WHISPER_SAMPLE_RATE - SILENCE_PADDING_SAMPLES);
+        assert_eq!(
+            bounds.end,
+            WHISPER_SAMPLE_RATE * 2 + 1 + SILENCE_PADDING_SAMPLES
+        );
+
+        // Nothing is trimmed from all-silent audio
+        let silent = vec![0.001f32; 100];
+        assert_eq!(WhisperTranscriber::speech_bounds(&silent), 0..100);
+    }
+
+    #[test]
+    fn test_merge_short_segments() {
+        let segments = vec![
+            segment(0, 20, " Yes."),
+            segment(20, 45, " No."),
+            segment(45, 60, " Maybe."),
+            segment(60, 70, " Well,"),
+            segment(70, 400, " that is the question."),
+        ];
+
+        let cues = WhisperTranscriber::merge_short_segments(&segments, 300);
+        assert_eq!(
+            cues,
+            vec![
+                segment(0, 45, " Yes. No."),
+                segment(45, 400, " Maybe. Well, that is the question."),
+            ]
+        );
+
+        // 0 disables merging
+        assert_eq!(
+            WhisperTranscriber::merge_short_segments(&segments, 0),
+            segments
+        );
+    }
 
-        Ok(result)
+    #[test]
+    fn test_wrap_subtitle_text_keeps_short_segment() {
+        let text = " Hello world.";
+        assert_eq!(WhisperTranscriber::wrap_subtitle_text(text, 42), text);
     }
 }
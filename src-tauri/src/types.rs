@@ -1,17 +1,235 @@
+use crate::config::SETTINGS_VERSION;
+
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TranscriptionSettings {
+    /// Schema version of the persisted settings; files without one are version 0.
+    #[serde(default)]
+    pub version: u32,
     pub language: Option<String>,
     pub model: String,
-    pub output_format: OutputFormat,
+    /// When `model` isn't downloaded, run the best downloaded model instead of
+    /// failing the batch.
+    pub fallback_to_downloaded_model: bool,
+    /// Re-run a file with the next larger downloaded model when its average
+    /// token log probability is below `confidence_threshold`, keeping
+    /// whichever result scores higher.
+    pub auto_upgrade_on_low_confidence: bool,
+    pub confidence_threshold: f32,
+    /// Every format is written; the first is the job's output, which is
+    /// streamed, edited and muxed.
+    pub output_formats: Vec<OutputFormat>,
     pub keep_wav: bool,
     pub output_dir: Option<PathBuf>,
-    pub parallel_jobs: usize,
+    /// Where outputs are written. `FixedDir` writes to `output_dir`.
+    pub output_mode: OutputMode,
+    /// Folder per output format, taking precedence over `output_mode`. A
+    /// relative folder is resolved against the input's folder, so `.` keeps
+    /// that format next to the input.
+    pub format_output_dirs: HashMap<OutputFormat, PathBuf>,
+    /// Once a batch finishes, also write its transcripts as one file, in queue
+    /// order, each file's times shifted by the length of the files before it.
+    pub combine_output: bool,
+    /// Where the combined file goes; next to the first output when unset.
+    pub combined_output_path: Option<PathBuf>,
+    /// Keep each file's own output when combining.
+    pub keep_individual_outputs: bool,
+    /// Once a batch finishes, open the folder its outputs went to.
+    pub open_folder_on_complete: bool,
+    /// Files converted to WAV at once.
+    pub conversion_jobs: usize,
+    /// Files transcribed at once.
+    pub transcription_jobs: usize,
+    /// Transcribe with fewer threads and, on Linux, at a lower priority, so the
+    /// machine stays responsive.
+    pub background_mode: bool,
+    pub include_metadata_header: bool,
+    /// Write the input's chapter markers as headings in TXT and VTT output and
+    /// as a `chapters` list in JSON.
+    pub include_chapters: bool,
+    /// Write the transcript's language into JSON output and as a VTT note.
+    pub include_language_in_output: bool,
+    /// Add the SRT/VTT output to a copy of video inputs as a soft subtitle track.
+    pub mux_subtitles: bool,
+    /// Longest input accepted for transcription, 0 for no limit.
+    pub max_input_duration_secs: u64,
+    /// Longest SRT/VTT cue line before wrapping onto a second line, 0 to disable.
+    pub max_line_chars: usize,
+    /// SRT/VTT cues shorter than this are merged into the next one, 0 to disable.
+    pub min_cue_duration_ms: u64,
+    /// SRT/VTT cues longer than this are split into evenly timed cues, 0 to
+    /// disable. Subtitle guidelines usually cap cues at around 7 seconds.
+    pub max_cue_duration_ms: u64,
+    /// WebVTT `align:` cue setting, e.g. `center`. Cues are left plain when unset.
+    pub vtt_align: Option<String>,
+    /// WebVTT `line:` cue setting, e.g. `90%` or `-1`.
+    pub vtt_line: Option<String>,
+    /// Condition each decoding window on the text before it.
+    pub carry_context: bool,
+    /// Collapse whisper repetition loops into a single segment.
+    pub anti_repetition: bool,
+    /// Encoder context size in frames, up to 1500 (30 s). Smaller values speed
+    /// up short clips at some accuracy cost. Unset or 0 uses the full context.
+    pub audio_ctx: Option<i32>,
+    /// Advance the progress bar between whisper's progress updates, based on
+    /// the expected transcription time.
+    pub smooth_progress: bool,
+    /// Conversion, transcription and download progress is reported at most
+    /// this often. 100% is always reported.
+    pub progress_update_interval_ms: u64,
+    /// Gain applied while converting, in dB within -20..=20.
+    pub pre_gain_db: f32,
+    /// Resampler used while converting to 16 kHz. `High` makes conversion slower.
+    pub resample_quality: ResampleQuality,
+    /// How multi-channel audio is reduced to mono while converting.
+    pub downmix: DownmixMode,
+    /// A conversion that decodes no audio for this long fails, 0 to disable.
+    pub conversion_timeout_secs: u64,
+    /// Pause between one round of jobs finishing and the next starting, so a
+    /// long batch runs at sustained clock speeds. 0 to disable.
+    pub cooldown_secs_between_jobs: u64,
+    /// Experimental: detect the language of every 30 s of audio separately,
+    /// for recordings that switch languages. Only applies when no language is
+    /// set, and slows transcription down.
+    pub multilang: bool,
+    /// Skip leading and trailing silence before transcribing. Timestamps still
+    /// refer to the original media.
+    pub trim_silence: bool,
+    /// Also write every segment and token with timings and probabilities to
+    /// `<stem>.raw.json`, whatever the output format.
+    pub emit_raw_json_sidecar: bool,
+    /// Extra whisper parameters by name, see `ADVANCED_PARAMS` in the
+    /// transcriber. Integer parameters are rounded.
+    pub advanced_params: HashMap<String, f32>,
+    /// Shell command run after each transcription, with `{input}`, `{output}`
+    /// and `{status}` substituted.
+    pub on_complete_command: Option<String>,
+    /// URL sent a JSON POST describing each finished transcription.
+    pub on_complete_webhook: Option<String>,
+    /// Proxy for model downloads, e.g. `http://proxy:8080`. The environment's
+    /// `HTTPS_PROXY`/`HTTP_PROXY` are used when unset.
+    pub download_proxy: Option<String>,
+    /// JSON list of `CatalogModel`s offered alongside the built-in models, so
+    /// new models show up without an app update. Not fetched when unset.
+    pub model_catalog_url: Option<String>,
+    /// A download with no data for this long fails and is retried.
+    pub download_timeout_secs: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for TranscriptionSettings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            language: None,
+            model: "base".to_string(),
+            fallback_to_downloaded_model: true,
+            auto_upgrade_on_low_confidence: false,
+            confidence_threshold: -1.0,
+            output_formats: vec![OutputFormat::Srt],
+            keep_wav: false,
+            output_dir: None,
+            output_mode: OutputMode::SameAsInput,
+            format_output_dirs: HashMap::new(),
+            combine_output: false,
+            combined_output_path: None,
+            keep_individual_outputs: true,
+            open_folder_on_complete: false,
+            conversion_jobs: 1,
+            transcription_jobs: 1,
+            background_mode: false,
+            include_metadata_header: false,
+            include_chapters: false,
+            include_language_in_output: false,
+            mux_subtitles: false,
+            max_input_duration_secs: 3 * 60 * 60,
+            max_line_chars: 42,
+            min_cue_duration_ms: 0,
+            max_cue_duration_ms: 0,
+            vtt_align: None,
+            vtt_line: None,
+            carry_context: true,
+            anti_repetition: true,
+            audio_ctx: None,
+            smooth_progress: true,
+            progress_update_interval_ms: 250,
+            pre_gain_db: 0.0,
+            resample_quality: ResampleQuality::Fast,
+            downmix: DownmixMode::Average,
+            conversion_timeout_secs: 10 * 60,
+            cooldown_secs_between_jobs: 0,
+            multilang: false,
+            trim_silence: false,
+            emit_raw_json_sidecar: false,
+            advanced_params: HashMap::new(),
+            on_complete_command: None,
+            on_complete_webhook: None,
+            download_proxy: None,
+            model_catalog_url: None,
+            download_timeout_secs: 30,
+        }
+    }
+}
+
+impl TranscriptionSettings {
+    /// The first of `output_formats`, SRT when there are none.
+    pub fn output_format(&self) -> &OutputFormat {
+        self.output_formats.first().unwrap_or(&OutputFormat::Srt)
+    }
+
+    /// The rest of `output_formats`, without repeats.
+    pub fn extra_output_formats(&self) -> Vec<OutputFormat> {
+        let mut extra: Vec<OutputFormat> = Vec::new();
+        for format in self.output_formats.iter().skip(1) {
+            if format != self.output_format() && !extra.contains(format) {
+                extra.push(format.clone());
+            }
+        }
+        extra
+    }
+
+    pub fn progress_update_interval(&self) -> Duration {
+        Duration::from_millis(self.progress_update_interval_ms)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum OutputMode {
+    #[default]
+    SameAsInput,
+    FixedDir,
+    /// Recreates the input folder structure, relative to the batch's common
+    /// base folder, under `root`.
+    MirrorTree {
+        root: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ResampleQuality {
+    /// ffmpeg's default resampler.
+    #[default]
+    Fast,
+    /// soxr at high precision, for music and high-frequency content.
+    High,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum DownmixMode {
+    /// Mix all channels together.
+    #[default]
+    Average,
+    /// Keep only the first channel, e.g. when the speaker is on a separate track.
+    LeftOnly,
+    /// Keep only the second channel; mono input uses its only channel.
+    RightOnly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum OutputFormat {
     Srt,
     Txt,
@@ -40,15 +258,94 @@ pub struct FileEntry {
     pub progress: f32,
     pub error: Option<String>,
     pub output_path: Option<PathBuf>,
+    /// Container stream to transcribe; the best audio stream when unset.
+    #[serde(default)]
+    pub audio_stream_index: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioStreamInfo {
+    pub index: usize,
+    pub codec: String,
+    pub language: Option<String>,
+    pub channels: u16,
+    pub sample_rate: u32,
+    /// Bits per second, when the container reports it.
+    pub bit_rate: Option<usize>,
+}
+
+/// What the whisper.cpp and ffmpeg builds in use support.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capabilities {
+    pub gpu_available: bool,
+    pub cuda: bool,
+    pub metal: bool,
+    /// Common audio codecs ffmpeg can decode, by ffmpeg's codec name.
+    pub audio_decoders: Vec<String>,
+    /// whisper.cpp's report of the CPU features and backends it was built with.
+    pub whisper_system_info: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaInfo {
+    /// Container format, e.g. "QuickTime / MOV".
+    pub format: String,
+    pub duration_secs: Option<f64>,
+    /// Overall bits per second, when the container reports it.
+    pub bit_rate: Option<usize>,
+    pub audio_streams: Vec<AudioStreamInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Language {
+    pub code: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddFilesResult {
+    pub files: Vec<FileEntry>,
+    /// Files that were already queued.
+    pub duplicates: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryScanResult {
+    pub files: Vec<FileEntry>,
+    /// Entries that were not audio files, or subdirectories when not recursive.
+    pub skipped: usize,
+    /// Audio files that were already queued.
+    pub duplicates: usize,
+    /// Set when the scan stopped at the file limit.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchEstimate {
+    /// Summed duration of the files that could be probed.
+    pub total_duration_secs: f64,
+    /// `None` when there is no real-time factor for the selected model.
+    pub estimated_processing_secs: Option<f64>,
+    pub output_count: usize,
+    /// Files whose duration could not be read and are left out of the totals.
+    pub unknown_duration_count: usize,
+    pub rtf_from_history: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum FileStatus {
+    /// Added, but not submitted for transcription.
     Pending,
+    /// Submitted and waiting for a free slot.
+    Queued,
+    /// Held back; not started until resumed.
+    Paused,
     Converting,
     Transcribing,
     Completed,
     Error,
+    /// Stopped by the user; its partial files have been removed.
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +356,54 @@ pub struct WhisperModel {
     pub downloaded: bool,
     pub file_path: Option<PathBuf>,
     pub progress: Option<f32>,
+    /// False for the `.en` models, which only transcribe English.
+    #[serde(default)]
+    pub multilingual: bool,
+    /// Relative to the other built-in models; `High` is fastest.
+    #[serde(default)]
+    pub speed_tier: Option<ModelTier>,
+    #[serde(default)]
+    pub quality_tier: Option<ModelTier>,
+    /// Kept by any cleanup, see `ConfigManager::load_pinned_models`.
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// An entry of the catalog at `model_catalog_url`. `size` is written like the
+/// built-in models', e.g. `1.6 GB`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CatalogModel {
+    pub name: String,
+    pub size: String,
+    pub url: String,
+    #[serde(default)]
+    pub speed_tier: Option<ModelTier>,
+    #[serde(default)]
+    pub quality_tier: Option<ModelTier>,
+}
+
+/// Rough rating of a built-in model against the others.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ModelTier {
+    Low,
+    Medium,
+    High,
+}
+
+/// Result of probing a model's download URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelSourceStatus {
+    pub model: String,
+    pub reachable: bool,
+    /// Bytes reported by the server, when it sends a length.
+    pub size_bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadStatus {
+    pub model: String,
+    pub progress: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +412,44 @@ pub struct ProgressUpdate {
     pub status: FileStatus,
     pub progress: f32,
     pub message: Option<String>,
+    /// Set once the job has written its output.
+    pub output_path: Option<PathBuf>,
+    pub notice: Option<String>,
+}
+
+/// The whole queue at a glance, for summaries such as a tray tooltip.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueueStatus {
+    pub running: usize,
+    pub queued: usize,
+    pub completed: usize,
+    pub failed: usize,
+    /// Average progress of every job that isn't cancelled, rounded.
+    pub overall_percent: u32,
+    /// One line such as "3 running, 5 queued, 40%".
+    pub summary: String,
+}
+
+/// One model's transcript of a file, from `compare_models`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelTranscript {
+    pub model: String,
+    /// Segment texts joined with spaces.
+    pub text: String,
+    /// Times refer to the source.
+    pub segments: Vec<Segment>,
+    /// Time spent transcribing, not converting.
+    pub elapsed_secs: f64,
+}
+
+/// Two transcripts of the same file and how their words differ.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelComparison {
+    pub transcripts: Vec<ModelTranscript>,
+    /// Words only in the second transcript.
+    pub insertions: usize,
+    /// Words only in the first transcript.
+    pub deletions: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,4 +461,88 @@ pub struct TranscriptionJob {
     pub progress: f32,
     pub error: Option<String>,
     pub output_path: Option<PathBuf>,
+    pub temp_wav_path: Option<PathBuf>,
+    pub audio_stream_index: Option<usize>,
+    /// Deepest folder containing every input of the batch the job was started in.
+    pub source_root: Option<PathBuf>,
+    /// Incremented each time the job is queued again, so a superseded run can
+    /// tell that it no longer owns the job.
+    pub attempt: u32,
+    /// Segments of a completed job, kept so the transcript can be re-exported
+    /// without running whisper again.
+    #[serde(skip)]
+    pub segments: Vec<Segment>,
+    /// Where the transcribed audio starts in the source, when it was trimmed.
+    /// `segments` are relative to the trimmed audio.
+    #[serde(skip)]
+    pub timestamp_offset_ms: i64,
+    /// Code of the language a completed job was transcribed in.
+    pub detected_language: Option<String>,
+    /// Remark about a completed job that isn't an error, e.g. that no speech
+    /// was found.
+    pub notice: Option<String>,
+}
+
+/// Temp files left behind by killed or failed runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TempFilesInfo {
+    pub count: usize,
+    pub bytes: u64,
+}
+
+/// A whisper segment. Times are in centiseconds, as whisper reports them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: i64,
+    pub end: i64,
+    pub text: String,
+    pub tokens: Vec<Token>,
+    /// Language detected for the part of the audio the segment is in, set in
+    /// multi-language mode only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// A chapter marker from the input container. Times are in milliseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Token {
+    pub id: i32,
+    pub text: String,
+    pub start: i64,
+    pub end: i64,
+    pub probability: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveTranscriptUpdate {
+    pub text: String,
+    /// Set once the window's text will no longer change.
+    pub is_final: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SubtitleCue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParsedSubtitles {
+    pub cues: Vec<SubtitleCue>,
+    /// One message per block that couldn't be read as a cue.
+    pub errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    pub level: String,
+    pub message: String,
 }
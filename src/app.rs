@@ -3,6 +3,7 @@ use leptos_meta::provide_meta_context;
 use wasm_bindgen::prelude::*;
 
 use crate::view::debug_view::DebugView;
+use crate::view::live_view::LiveView;
 use crate::view::models_view::ModelsView;
 use crate::view::whisper_view::WhisperView;
 use crate::view::settings_view::SettingsView;
@@ -17,7 +18,7 @@ extern "C" {
 pub fn App() -> impl IntoView {
     provide_meta_context();
 
-    let tab_names = vec!["Whisper", "Models", "Settings", "Debug"];
+    let tab_names = vec!["Whisper", "Live", "Models", "Settings", "Debug"];
     let (tab, set_tab) = signal(String::from("Settings"));
     let change_tab = move |tab: String| {
         set_tab.set(tab);
@@ -68,6 +69,7 @@ pub fn App() -> impl IntoView {
             </div>
             {move || match tab.get().as_str() {
                 "Whisper" => view! { <WhisperView /> }.into_any(),
+                "Live" => view! { <LiveView /> }.into_any(),
                 "Models" => view! { <ModelsView /> }.into_any(),
                 "Settings" => view! { <SettingsView /> }.into_any(),
                 "Debug" => view! { <DebugView /> }.into_any(),
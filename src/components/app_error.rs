@@ -0,0 +1,58 @@
+use serde::Deserialize;
+use wasm_bindgen::JsValue;
+
+/// The backend's `AppError`, which commands reject with as
+/// `{"kind": "...", "message": "..."}`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "kind", content = "message")]
+pub enum AppError {
+    ModelNotDownloaded(String),
+    InsufficientDiskSpace(String),
+    NetworkError(String),
+    ConversionFailed(String),
+    TranscriptionFailed(String),
+    Other(String),
+}
+
+impl AppError {
+    /// Plain strings, such as plugin errors, become `Other`.
+    pub fn from_js(error: JsValue) -> Self {
+        serde_wasm_bindgen::from_value(error.clone()).unwrap_or_else(|_| {
+            AppError::Other(error.as_string().unwrap_or_else(|| format!("{:?}", error)))
+        })
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::ModelNotDownloaded(message)
+            | AppError::InsufficientDiskSpace(message)
+            | AppError::NetworkError(message)
+            | AppError::ConversionFailed(message)
+            | AppError::TranscriptionFailed(message)
+            | AppError::Other(message) => message,
+        }
+    }
+
+    /// What the user can do about it, if there is anything.
+    pub fn recovery_hint(&self) -> Option<&'static str> {
+        match self {
+            AppError::ModelNotDownloaded(_) => Some("Download it from the Models tab."),
+            AppError::InsufficientDiskSpace(_) => Some("Free up some disk space and try again."),
+            AppError::NetworkError(_) => Some("Check your internet connection and try again."),
+            AppError::ConversionFailed(_) => {
+                Some("The file may be damaged or in a format ffmpeg can't read.")
+            }
+            AppError::TranscriptionFailed(_) | AppError::Other(_) => None,
+        }
+    }
+}
+
+/// An error from a command or plugin call as text for the UI, followed by the
+/// recovery hint for its kind.
+pub fn js_error_message(error: JsValue) -> String {
+    let error = AppError::from_js(error);
+    match error.recovery_hint() {
+        Some(hint) => format!("{}. {}", error.message().trim_end_matches('.'), hint),
+        None => error.message().to_string(),
+    }
+}
@@ -1 +1,2 @@
+pub mod app_error;
 mod settings_model;
@@ -1,6 +1,5 @@
 mod app;
 mod components;
-mod constants;
 mod view;
 
 use app::*;
@@ -1,17 +1,536 @@
+use crate::components::app_error::js_error_message;
+use crate::view::settings_view::{TranscriptionSettings, WhisperModel};
 use leptos::ev::MouseEvent;
 use leptos::task::spawn_local;
-use leptos::{ev::SubmitEvent, prelude::*};
-use leptos_meta::provide_meta_context;
+use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use wasm_bindgen::prelude::*;
 
+/// Lines requested from the backend on each poll.
+const LOG_LINES: usize = 500;
+const LOG_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
 #[wasm_bindgen]
 extern "C" {
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
-    async fn invoke(cmd: &str, args: JsValue) -> JsValue;
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
+    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
+    async fn open(options: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "clipboardManager"], js_name = writeText, catch)]
+    async fn write_text(text: &str) -> Result<JsValue, JsValue>;
+}
+
+#[derive(Serialize)]
+struct FfmpegCommandArgs {
+    input_path: String,
+}
+
+#[derive(Serialize)]
+struct RecentLogsArgs {
+    limit: usize,
+}
+
+#[derive(Serialize)]
+struct CompareModelsArgs {
+    file_path: String,
+    models: Vec<String>,
+    settings: TranscriptionSettings,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ModelTranscript {
+    model: String,
+    text: String,
+    elapsed_secs: f64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ModelComparison {
+    transcripts: Vec<ModelTranscript>,
+    insertions: usize,
+    deletions: usize,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct TempFilesInfo {
+    count: usize,
+    bytes: u64,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct LogEntry {
+    level: String,
+    message: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Capabilities {
+    gpu_available: bool,
+    cuda: bool,
+    metal: bool,
+    audio_decoders: Vec<String>,
+    whisper_system_info: String,
+}
+
+#[derive(Serialize)]
+struct DialogOptions {
+    directory: bool,
+    multiple: bool,
 }
 
 #[component]
 pub fn DebugView() -> impl IntoView {
-    view! { <div>Debug</div> }
+    let (ffmpeg_command, set_ffmpeg_command) = signal(None::<String>);
+    let (message, set_message) = signal(None::<String>);
+    let (logs, set_logs) = signal(Vec::<LogEntry>::new());
+    let (min_level, set_min_level) = signal("INFO".to_string());
+    let (capabilities, set_capabilities) = signal(None::<Capabilities>);
+    let (downloaded_models, set_downloaded_models) = signal(Vec::<String>::new());
+    let (compare_file, set_compare_file) = signal(None::<String>);
+    let (model_a, set_model_a) = signal(String::new());
+    let (model_b, set_model_b) = signal(String::new());
+    let (comparing, set_comparing) = signal(false);
+    let (comparison, set_comparison) = signal(None::<ModelComparison>);
+    let (compare_error, set_compare_error) = signal(None::<String>);
+    let (temp_files, set_temp_files) = signal(None::<TempFilesInfo>);
+    let (temp_message, set_temp_message) = signal(None::<String>);
+
+    spawn_local(async move {
+        match get_downloaded_models().await {
+            Ok(models) => {
+                set_model_a.set(models.first().cloned().unwrap_or_default());
+                set_model_b.set(models.get(1).cloned().unwrap_or_default());
+                set_downloaded_models.set(models);
+            }
+            Err(e) => log::error!("{}", e),
+        }
+    });
+
+    spawn_local(async move {
+        match temp_files_command("get_temp_files_info").await {
+            Ok(info) => set_temp_files.set(Some(info)),
+            Err(e) => log::error!("{}", e),
+        }
+    });
+
+    let on_clear_temp_files = move |_: MouseEvent| {
+        spawn_local(async move {
+            set_temp_message.set(None);
+            match temp_files_command("clear_temp_files").await {
+                Ok(freed) => {
+                    set_temp_message.set(Some(format!(
+                        "Removed {} files, freeing {}",
+                        freed.count,
+                        format_size(freed.bytes)
+                    )));
+                    set_temp_files.set(Some(TempFilesInfo { count: 0, bytes: 0 }));
+                }
+                Err(e) => set_temp_message.set(Some(e)),
+            }
+        });
+    };
+
+    spawn_local(async move {
+        match invoke("get_capabilities", JsValue::NULL).await {
+            Ok(result) => match serde_wasm_bindgen::from_value::<Capabilities>(result) {
+                Ok(caps) => set_capabilities.set(Some(caps)),
+                Err(e) => log::error!("Failed to parse capabilities: {:?}", e),
+            },
+            Err(e) => log::error!("Failed to get capabilities: {:?}", e),
+        }
+    });
+
+    let refresh_logs = move || {
+        spawn_local(async move {
+            let args = serde_wasm_bindgen::to_value(&RecentLogsArgs { limit: LOG_LINES }).unwrap();
+            match invoke("get_recent_logs", args).await {
+                Ok(result) => match serde_wasm_bindgen::from_value::<Vec<LogEntry>>(result) {
+                    Ok(entries) => set_logs.set(entries),
+                    Err(e) => log::error!("Failed to parse logs: {:?}", e),
+                },
+                Err(e) => log::error!("Failed to get logs: {:?}", e),
+            }
+        });
+    };
+    refresh_logs();
+    if let Ok(handle) = set_interval_with_handle(refresh_logs, LOG_POLL_INTERVAL) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let visible_logs = move || {
+        let min_rank = level_rank(&min_level.get());
+        logs.get()
+            .into_iter()
+            .filter(|entry| level_rank(&entry.level) <= min_rank)
+            .collect::<Vec<_>>()
+    };
+
+    let on_select_file = move |_: MouseEvent| {
+        spawn_local(async move {
+            set_message.set(None);
+            let options = serde_wasm_bindgen::to_value(&DialogOptions {
+                directory: false,
+                multiple: false,
+            })
+            .unwrap();
+            let Some(input_path) = open(options).await.as_string() else {
+                return;
+            };
+
+            let args = serde_wasm_bindgen::to_value(&FfmpegCommandArgs { input_path }).unwrap();
+            match invoke("get_ffmpeg_command", args).await {
+                Ok(command) => set_ffmpeg_command.set(command.as_string()),
+                Err(e) => set_message.set(Some(format!("Failed to build ffmpeg command: {:?}", e))),
+            }
+        });
+    };
+
+    let on_copy = move |_: MouseEvent| {
+        let Some(command) = ffmpeg_command.get_untracked() else {
+            return;
+        };
+        spawn_local(async move {
+            match write_text(&command).await {
+                Ok(_) => set_message.set(Some("Copied to clipboard".to_string())),
+                Err(e) => set_message.set(Some(format!("Failed to copy: {:?}", e))),
+            }
+        });
+    };
+
+    let on_select_compare_file = move |_: MouseEvent| {
+        spawn_local(async move {
+            let options = serde_wasm_bindgen::to_value(&DialogOptions {
+                directory: false,
+                multiple: false,
+            })
+            .unwrap();
+            if let Some(path) = open(options).await.as_string() {
+                set_compare_file.set(Some(path));
+                set_comparison.set(None);
+                set_compare_error.set(None);
+            }
+        });
+    };
+
+    let can_compare = move || {
+        compare_file.get().is_some()
+            && !model_a.get().is_empty()
+            && !model_b.get().is_empty()
+            && model_a.get() != model_b.get()
+            && !comparing.get()
+    };
+
+    let on_compare = move |_: MouseEvent| {
+        let Some(file_path) = compare_file.get_untracked() else {
+            return;
+        };
+        let models = vec![model_a.get_untracked(), model_b.get_untracked()];
+        set_comparing.set(true);
+        set_comparison.set(None);
+        set_compare_error.set(None);
+        spawn_local(async move {
+            match compare_models(file_path, models).await {
+                Ok(result) => set_comparison.set(Some(result)),
+                Err(e) => set_compare_error.set(Some(e)),
+            }
+            set_comparing.set(false);
+        });
+    };
+
+    let model_options = move || {
+        downloaded_models
+            .get()
+            .into_iter()
+            .map(|name| view! { <option value=name.clone()>{name.clone()}</option> })
+            .collect_view()
+    };
+
+    let on_copy_logs = move |_: MouseEvent| {
+        let text = visible_logs()
+            .iter()
+            .map(|entry| entry.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+        spawn_local(async move {
+            match write_text(&text).await {
+                Ok(_) => set_message.set(Some("Logs copied to clipboard".to_string())),
+                Err(e) => set_message.set(Some(format!("Failed to copy: {:?}", e))),
+            }
+        });
+    };
+
+    view! {
+        <div class="p-6">
+            <h2 class="mb-2 text-xl font-semibold text-gray-900">"ffmpeg Command"</h2>
+            <p class="mb-4 text-sm text-gray-500">
+                "The ffmpeg command equivalent to the app's WAV conversion, for reproducing conversion problems outside the app."
+            </p>
+            <div class="flex mb-4 space-x-2">
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-blue-600 rounded-md shadow-sm hover:bg-blue-700"
+                    on:click=on_select_file
+                >
+                    "Select File"
+                </button>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-gray-700 bg-white rounded-md border border-gray-300 hover:bg-gray-50 disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || ffmpeg_command.get().is_none()
+                    on:click=on_copy
+                >
+                    "Copy"
+                </button>
+            </div>
+            <Show when=move || ffmpeg_command.get().is_some()>
+                <pre class="overflow-x-auto p-3 text-xs text-gray-800 whitespace-pre-wrap break-all bg-gray-100 rounded-md">
+                    {move || ffmpeg_command.get().unwrap_or_default()}
+                </pre>
+            </Show>
+            <p class="mt-2 text-sm text-gray-600">{move || message.get().unwrap_or_default()}</p>
+
+            <h2 class="mt-8 mb-2 text-xl font-semibold text-gray-900">"Compare Models"</h2>
+            <p class="mb-4 text-sm text-gray-500">
+                "Transcribe one file with two downloaded models, using the current settings, to see whether a larger model is worth the time."
+            </p>
+            <div class="flex items-center mb-4 space-x-2">
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-gray-700 bg-white rounded-md border border-gray-300 hover:bg-gray-50"
+                    on:click=on_select_compare_file
+                >
+                    "Select File"
+                </button>
+                <select
+                    class="py-2 px-2 text-sm rounded-md border border-gray-300"
+                    prop:value=move || model_a.get()
+                    on:change=move |ev| set_model_a.set(event_target_value(&ev))
+                >
+                    {model_options}
+                </select>
+                <span class="text-sm text-gray-500">"vs"</span>
+                <select
+                    class="py-2 px-2 text-sm rounded-md border border-gray-300"
+                    prop:value=move || model_b.get()
+                    on:change=move |ev| set_model_b.set(event_target_value(&ev))
+                >
+                    {model_options}
+                </select>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-blue-600 rounded-md shadow-sm hover:bg-blue-700 disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || !can_compare()
+                    on:click=on_compare
+                >
+                    {move || if comparing.get() { "Comparing..." } else { "Compare" }}
+                </button>
+            </div>
+            <p class="mb-2 text-sm text-gray-600 break-all">
+                {move || compare_file.get().unwrap_or_else(|| "No file selected".to_string())}
+            </p>
+            <Show when=move || downloaded_models.get().len() < 2>
+                <p class="mb-2 text-sm text-gray-500">"Download at least two models to compare them."</p>
+            </Show>
+            <p class="mb-2 text-sm text-red-600">{move || compare_error.get().unwrap_or_default()}</p>
+            {move || {
+                comparison
+                    .get()
+                    .map(|result| {
+                        view! {
+                            <p class="mb-2 text-sm text-gray-700">
+                                {format!(
+                                    "{} words added, {} words removed going from {} to {}",
+                                    result.insertions,
+                                    result.deletions,
+                                    result.transcripts[0].model,
+                                    result.transcripts[1].model,
+                                )}
+                            </p>
+                            <div class="grid grid-cols-2 gap-4">
+                                {result
+                                    .transcripts
+                                    .into_iter()
+                                    .map(|transcript| {
+                                        view! {
+                                            <div>
+                                                <h3 class="mb-1 text-sm font-medium text-gray-900">
+                                                    {format!(
+                                                        "{} ({:.1}s)",
+                                                        transcript.model,
+                                                        transcript.elapsed_secs,
+                                                    )}
+                                                </h3>
+                                                <pre class="overflow-y-auto p-3 h-64 text-xs text-gray-800 whitespace-pre-wrap bg-gray-100 rounded-md">
+                                                    {transcript.text}
+                                                </pre>
+                                            </div>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </div>
+                        }
+                    })
+            }}
+
+            <h2 class="mt-8 mb-2 text-xl font-semibold text-gray-900">"Temp Files"</h2>
+            <p class="mb-4 text-sm text-gray-500">
+                "Converted WAVs left behind by failed or interrupted runs. Files of running jobs and kept WAVs are left alone."
+            </p>
+            <div class="flex items-center mb-2 space-x-4">
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-gray-700 bg-white rounded-md border border-gray-300 hover:bg-gray-50 disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || !temp_files.get().is_some_and(|info| info.count > 0)
+                    on:click=on_clear_temp_files
+                >
+                    "Clean temp files"
+                </button>
+                <span class="text-sm text-gray-700">
+                    {move || {
+                        temp_files
+                            .get()
+                            .map(|info| {
+                                format!("{} files, {} reclaimable", info.count, format_size(info.bytes))
+                            })
+                            .unwrap_or_default()
+                    }}
+                </span>
+            </div>
+            <p class="text-sm text-gray-600">{move || temp_message.get().unwrap_or_default()}</p>
+
+            <h2 class="mt-8 mb-2 text-xl font-semibold text-gray-900">"Capabilities"</h2>
+            {move || {
+                capabilities
+                    .get()
+                    .map(|caps| {
+                        let gpu = match (caps.gpu_available, caps.cuda, caps.metal) {
+                            (false, _, _) => "Not available, transcription runs on the CPU",
+                            (true, true, _) => "CUDA",
+                            (true, _, true) => "Metal",
+                            (true, false, false) => "Available",
+                        };
+                        let decoders = if caps.audio_decoders.is_empty() {
+                            "None found".to_string()
+                        } else {
+                            caps.audio_decoders.join(", ")
+                        };
+                        view! {
+                            <dl class="grid grid-cols-4 gap-2 mb-2 text-sm">
+                                <dt class="font-medium text-gray-700">"GPU backend"</dt>
+                                <dd class="col-span-3 text-gray-900">{gpu}</dd>
+                                <dt class="font-medium text-gray-700">"Audio decoders"</dt>
+                                <dd class="col-span-3 text-gray-900">{decoders}</dd>
+                            </dl>
+                            <pre class="overflow-x-auto p-3 text-xs text-gray-800 whitespace-pre-wrap break-all bg-gray-100 rounded-md">
+                                {caps.whisper_system_info}
+                            </pre>
+                        }
+                    })
+            }}
+
+            <div class="flex justify-between items-center mt-8 mb-2">
+                <h2 class="text-xl font-semibold text-gray-900">"Log"</h2>
+                <div class="flex space-x-2">
+                    <select
+                        class="py-1 px-2 text-sm rounded-md border border-gray-300"
+                        prop:value=move || min_level.get()
+                        on:change=move |ev| set_min_level.set(event_target_value(&ev))
+                    >
+                        <option value="ERROR">"Errors"</option>
+                        <option value="WARN">"Warnings and above"</option>
+                        <option value="INFO">"Info and above"</option>
+                        <option value="DEBUG">"Debug and above"</option>
+                        <option value="TRACE">"Everything"</option>
+                    </select>
+                    <button
+                        type="button"
+                        class="py-1 px-3 text-sm font-medium text-gray-700 bg-white rounded-md border border-gray-300 hover:bg-gray-50"
+                        on:click=on_copy_logs
+                    >
+                        "Copy Log"
+                    </button>
+                </div>
+            </div>
+            <div class="overflow-y-auto p-3 h-96 font-mono text-xs bg-gray-900 rounded-md">
+                <For
+                    each=move || visible_logs().into_iter().enumerate()
+                    key=|(index, entry)| (*index, entry.message.clone())
+                    children=move |(_, entry)| {
+                        let class = match entry.level.as_str() {
+                            "ERROR" => "text-red-400",
+                            "WARN" => "text-yellow-300",
+                            _ => "text-gray-200",
+                        };
+                        view! { <p class=class>{entry.message}</p> }
+                    }
+                />
+            </div>
+        </div>
+    }
+}
+
+async fn get_downloaded_models() -> Result<Vec<String>, String> {
+    let result = invoke("get_available_models", JsValue::NULL)
+        .await
+        .map_err(|e| format!("Failed to load models: {}", js_error_message(e)))?;
+    let models: Vec<WhisperModel> = serde_wasm_bindgen::from_value(result)
+        .map_err(|e| format!("Failed to parse models: {}", e))?;
+    Ok(models
+        .into_iter()
+        .filter(|model| model.downloaded)
+        .map(|model| model.name)
+        .collect())
+}
+
+async fn compare_models(file_path: String, models: Vec<String>) -> Result<ModelComparison, String> {
+    let settings = invoke("load_settings", JsValue::NULL)
+        .await
+        .map_err(js_error_message)?;
+    let settings: TranscriptionSettings = serde_wasm_bindgen::from_value(settings)
+        .map_err(|e| format!("Failed to parse settings: {}", e))?;
+    let args = CompareModelsArgs {
+        file_path,
+        models,
+        settings,
+    }
+    .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = invoke("compare_models", args)
+        .await
+        .map_err(|e| format!("Comparison failed: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse comparison: {}", e))
+}
+
+/// `get_temp_files_info` and `clear_temp_files` take no arguments and return
+/// the same summary.
+async fn temp_files_command(command: &str) -> Result<TempFilesInfo, String> {
+    let result = invoke(command, JsValue::NULL)
+        .await
+        .map_err(|e| format!("Failed to check temp files: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse temp files: {}", e))
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Lower is more severe, matching `log::Level` ordering.
+fn level_rank(level: &str) -> u8 {
+    match level {
+        "ERROR" => 1,
+        "WARN" => 2,
+        "INFO" => 3,
+        "DEBUG" => 4,
+        _ => 5,
+    }
 }
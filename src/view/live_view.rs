@@ -0,0 +1,153 @@
+use leptos::ev::MouseEvent;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+use crate::components::app_error::js_error_message;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
+    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &js_sys::Function) -> JsValue;
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct LiveTranscriptUpdate {
+    text: String,
+    is_final: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct TauriEvent<T> {
+    payload: T,
+}
+
+#[component]
+pub fn LiveView() -> impl IntoView {
+    let (running, set_running) = signal(false);
+    let (busy, set_busy) = signal(false);
+    let (final_lines, set_final_lines) = signal(Vec::<String>::new());
+    let (partial, set_partial) = signal(String::new());
+    let (error_message, set_error_message) = signal(None::<String>);
+
+    // Live transcription keeps running while other tabs are open
+    spawn_local(async move {
+        if let Ok(result) = invoke("get_live_transcription_status", JsValue::NULL).await {
+            set_running.set(result.as_bool().unwrap_or(false));
+        }
+    });
+
+    spawn_local(async move {
+        let closure = Closure::<dyn FnMut(_)>::new(move |s: JsValue| {
+            match serde_wasm_bindgen::from_value::<TauriEvent<LiveTranscriptUpdate>>(s) {
+                Ok(event) => {
+                    let update = event.payload;
+                    if update.is_final {
+                        if !update.text.is_empty() {
+                            set_final_lines.update(|lines| lines.push(update.text));
+                        }
+                        set_partial.set(String::new());
+                    } else {
+                        set_partial.set(update.text);
+                    }
+                }
+                Err(e) => log::error!("Failed to parse live transcript event: {:?}", e),
+            }
+        });
+        listen("live-transcript", closure.as_ref().unchecked_ref()).await;
+        closure.forget();
+    });
+
+    let on_start = move |_: MouseEvent| {
+        set_busy.set(true);
+        set_error_message.set(None);
+        spawn_local(async move {
+            match invoke("start_live_transcription", JsValue::NULL).await {
+                Ok(_) => set_running.set(true),
+                Err(e) => set_error_message.set(Some(format!(
+                    "Failed to start live transcription: {}",
+                    js_error_message(e)
+                ))),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let on_stop = move |_: MouseEvent| {
+        set_busy.set(true);
+        spawn_local(async move {
+            if let Err(e) = invoke("stop_live_transcription", JsValue::NULL).await {
+                set_error_message.set(Some(format!(
+                    "Failed to stop live transcription: {}",
+                    js_error_message(e)
+                )));
+            }
+            set_running.set(false);
+            set_busy.set(false);
+        });
+    };
+
+    let on_clear = move |_: MouseEvent| {
+        set_final_lines.set(Vec::new());
+        set_partial.set(String::new());
+    };
+
+    view! {
+        <div class="p-6 mx-auto max-w-4xl bg-white">
+            <h1 class="mb-2 text-3xl font-bold text-gray-900">Live Transcription</h1>
+            <p class="mb-6 text-sm text-gray-500">
+                Transcribes the default microphone using the model and language from Settings.
+            </p>
+
+            <div class="flex mb-6 space-x-2">
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-blue-600 rounded-md hover:bg-blue-700 disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || running.get() || busy.get()
+                    on:click=on_start
+                >
+                    "Start"
+                </button>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-red-600 rounded-md hover:bg-red-700 disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || !running.get() || busy.get()
+                    on:click=on_stop
+                >
+                    "Stop"
+                </button>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-gray-700 bg-white rounded-md border border-gray-300 hover:bg-gray-50"
+                    on:click=on_clear
+                >
+                    "Clear"
+                </button>
+                <Show when=move || running.get()>
+                    <span class="self-center text-sm text-red-600">"● Listening"</span>
+                </Show>
+            </div>
+
+            <Show when=move || error_message.get().is_some()>
+                <div class="p-4 mb-4 text-sm text-red-700 bg-red-50 rounded-md border border-red-200">
+                    {move || error_message.get().unwrap_or_default()}
+                </div>
+            </Show>
+
+            <div class="overflow-y-auto p-4 space-y-2 h-96 bg-gray-50 rounded-lg">
+                <For
+                    each=move || final_lines.get().into_iter().enumerate()
+                    key=|(index, _)| *index
+                    children=move |(_, line)| {
+                        view! { <p class="text-gray-900">{line}</p> }
+                    }
+                />
+                <p class="italic text-gray-500">{move || partial.get()}</p>
+            </div>
+        </div>
+    }
+}
@@ -1,4 +1,5 @@
 pub mod debug_view;
+pub mod live_view;
 pub mod models_view;
 pub mod settings_view;
 pub mod whisper_view;
@@ -1,3 +1,4 @@
+use crate::components::app_error::js_error_message;
 use leptos::task::spawn_local;
 use leptos::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -8,6 +9,9 @@ extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
     async fn invoke(cmd: &str, args: JsValue) -> JsValue;
 
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn try_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
     async fn listen(event: &str, handler: &js_sys::Function) -> JsValue;
 }
@@ -20,7 +24,30 @@ struct WhisperModel {
     url: String,
     downloaded: bool,
     file_path: Option<String>,
-    progress: Option<i32>
+    progress: Option<i32>,
+    #[serde(default)]
+    multilingual: Option<bool>,
+    #[serde(default)]
+    speed_tier: Option<ModelTier>,
+    #[serde(default)]
+    quality_tier: Option<ModelTier>,
+    #[serde(default)]
+    pinned: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+enum ModelTier {
+    Low,
+    Medium,
+    High,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct ModelSourceStatus {
+    model: String,
+    reachable: bool,
+    size_bytes: Option<u64>,
+    error: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -28,12 +55,28 @@ struct DownloadModelArgs<'a> {
     model_name: &'a str,
 }
 
+#[derive(Serialize)]
+struct SetModelPinnedArgs<'a> {
+    model_name: &'a str,
+    pinned: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct DownloadModelClosure {
     model: String,
     progress: i32
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct TotalProgress {
+    progress: i32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TauriEventTotal {
+    payload: TotalProgress,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct TauriEvent {
     payload: DownloadModelClosure,
@@ -51,15 +94,91 @@ pub fn ModelsView() -> impl IntoView {
         WriteSignal<Vec<WhisperModel>>,
     ) = signal(Vec::new());
 
-    let get_available_models = move || {
+    // The view is remounted whenever its tab is selected, so this also picks up
+    // models dropped into the models directory while the app was running
+    let refresh_models = move || {
+        spawn_local(async move {
+            let result = invoke("refresh_models", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<WhisperModel>>(result) {
+                Ok(models) => set_available_models.set(models),
+                Err(e) => log::error!("Failed to refresh models: {:?}", e),
+            }
+        });
+    };
+
+    let (total_progress, set_total_progress) = signal(None::<i32>);
+    let (source_status, set_source_status) = signal(None::<Vec<ModelSourceStatus>>);
+    let (checking_sources, set_checking_sources) = signal(false);
+
+    let check_sources = move |_| {
+        set_checking_sources.set(true);
+        spawn_local(async move {
+            let result = invoke("check_model_sources", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Vec<ModelSourceStatus>>(result) {
+                Ok(statuses) => set_source_status.set(Some(statuses)),
+                Err(e) => log::error!("Failed to check model sources: {:?}", e),
+            }
+            set_checking_sources.set(false);
+        });
+    };
+
+    // Per model: the estimate in seconds, or why there isn't one
+    let (estimates, set_estimates) = signal(Vec::<(String, Result<u64, String>)>::new());
+    let (estimating, set_estimating) = signal(None::<String>);
+
+    let estimate_for = move |model_name: &str| {
+        estimates
+            .get()
+            .into_iter()
+            .find(|(name, _)| name == model_name)
+            .map(|(_, estimate)| estimate)
+    };
+
+    let estimate_download = move |model_name: String| {
+        set_estimating.set(Some(model_name.clone()));
+        spawn_local(async move {
+            let estimate = estimate_download_time(&model_name).await;
+            set_estimates.update(|estimates| {
+                estimates.retain(|(name, _)| *name != model_name);
+                estimates.push((model_name, estimate));
+            });
+            set_estimating.set(None);
+        });
+    };
+
+    let toggle_pinned = move |model_name: String, pinned: bool| {
         spawn_local(async move {
-            let args = serde_wasm_bindgen::to_value(&()).unwrap();
-            let result = invoke("get_available_models", args).await;
-            let models: Vec<WhisperModel> = serde_wasm_bindgen::from_value(result).unwrap();
-            set_available_models.set(models);
+            match set_model_pinned(&model_name, pinned).await {
+                Ok(models) => set_available_models.set(models),
+                Err(e) => log::error!("Failed to pin {}: {}", model_name, e),
+            }
         });
     };
 
+    let status_for = move |model_name: &str| {
+        source_status
+            .get()
+            .and_then(|statuses| statuses.into_iter().find(|status| status.model == model_name))
+    };
+
+    let missing_size = move || {
+        available_models
+            .get()
+            .iter()
+            .filter(|model| !model.downloaded && model.progress.is_none())
+            .filter_map(|model| parse_size(&model.size))
+            .sum::<f64>()
+    };
+
+    let download_missing = move |_| {
+        set_total_progress.set(Some(0));
+        spawn_local(async move {
+            invoke("download_missing_models", JsValue::NULL).await;
+            set_total_progress.set(None);
+            refresh_models();
+        })
+    };
+
     let download_models = move |model_name: String| {
         spawn_local(async move {
             let download_model_args = DownloadModelArgs { model_name: &model_name };
@@ -68,7 +187,15 @@ pub fn ModelsView() -> impl IntoView {
         })
     };
 
-    get_available_models();
+    refresh_models();
+
+    // Then add any new models from the catalog, which may need a request
+    spawn_local(async move {
+        match fetch_model_catalog().await {
+            Ok(models) => set_available_models.set(models),
+            Err(e) => log::error!("{}", e),
+        }
+    });
 
     spawn_local(async move {
         let closure = Closure::<dyn FnMut(_)>::new(move |s: JsValue| {
@@ -116,6 +243,7 @@ pub fn ModelsView() -> impl IntoView {
                     });
 
                     log::info!("updated model: {:?}", available_models.get());
+                    refresh_models();
                 }
                 Err(e) => {
                     log::error!("Failed to parse completion event: {:?}", e);
@@ -126,21 +254,172 @@ pub fn ModelsView() -> impl IntoView {
         completion_closure.forget();
     });
 
+    spawn_local(async move {
+        let closure = Closure::<dyn FnMut(_)>::new(move |s: JsValue| {
+            match serde_wasm_bindgen::from_value::<TauriEventTotal>(s) {
+                Ok(event) => set_total_progress.set(Some(event.payload.progress)),
+                Err(e) => log::error!("Failed to parse total progress event: {:?}", e),
+            }
+        });
+        listen("model-download-all-progress", closure.as_ref().unchecked_ref()).await;
+        closure.forget();
+    });
+
     view! {
         <div class="p-6">
+            <div class="flex items-center mb-4 space-x-4">
+                <button
+                    type="button"
+                    class="py-2 px-4 text-sm font-medium text-white bg-blue-700 rounded-lg hover:bg-blue-800 disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || total_progress.get().is_some() || missing_size() == 0.0
+                    on:click=download_missing
+                >
+                    {move || format!("Download all missing ({})", format_size(missing_size()))}
+                </button>
+                <button
+                    type="button"
+                    class="py-2 px-4 text-sm font-medium text-gray-700 bg-white rounded-lg border border-gray-300 hover:bg-gray-50 disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || checking_sources.get()
+                    on:click=check_sources
+                >
+                    {move || if checking_sources.get() { "Checking..." } else { "Check connectivity" }}
+                </button>
+                {move || {
+                    total_progress
+                        .get()
+                        .map(|progress| {
+                            view! {
+                                <div class="flex-1 h-2.5 bg-gray-200 rounded-full">
+                                    <div
+                                        class="h-2.5 bg-blue-600 rounded-full"
+                                        style:width=format!("{}%", progress)
+                                    ></div>
+                                </div>
+                                <span class="text-xs text-gray-500">{format!("{}%", progress)}</span>
+                            }
+                        })
+                }}
+            </div>
+            <Show when=move || {
+                source_status
+                    .get()
+                    .is_some_and(|statuses| !statuses.is_empty() && statuses.iter().all(|status| !status.reachable))
+            }>
+                <p class="mb-4 text-sm text-red-600">
+                    "No model source could be reached. Check your network connection or proxy."
+                </p>
+            </Show>
             <div class="bg-white rounded-lg divide-y divide-gray-100 shadow-sm dark:bg-gray-700 w-100">
                 <For
                     each=move || available_models.get()
-                    key=|model| format!("{:?}-{:?}", model.name.clone(), model.progress)
+                    key=|model| format!("{:?}-{:?}-{}", model.name.clone(), model.progress, model.pinned)
                     children=move |model| {
+                        let model_name = model.name.clone();
+                        let estimate_name = model.name.clone();
+                        let estimate_button_name = model.name.clone();
                         view! {
                             <ul class="py-2 text-sm text-gray-700 dark:text-gray-200">
                                 <li class="flex justify-between">
                                     <div class="mx-6">
                                         <a class="block py-2 text-lg">
                                             <strong>{model.name.clone()}</strong>
+                                            <button
+                                                type="button"
+                                                class="ml-2 text-base"
+                                                class=("opacity-30", !model.pinned)
+                                                title=if model.pinned {
+                                                    "Pinned: cleanup never deletes this model. Click to unpin."
+                                                } else {
+                                                    "Pin this model so cleanup never deletes it"
+                                                }
+                                                on:click={
+                                                    let model_name = model.name.clone();
+                                                    let pinned = model.pinned;
+                                                    move |_| toggle_pinned(model_name.clone(), !pinned)
+                                                }
+                                            >
+                                                "📌"
+                                            </button>
+                                            {move || {
+                                                status_for(&model_name)
+                                                    .map(|status| {
+                                                        let (class, title) = if status.reachable {
+                                                            let size = status
+                                                                .size_bytes
+                                                                .map(|bytes| format_size(bytes as f64))
+                                                                .unwrap_or_else(|| "size unknown".to_string());
+                                                            ("bg-green-500", format!("Reachable ({})", size))
+                                                        } else {
+                                                            (
+                                                                "bg-red-500",
+                                                                status.error.unwrap_or_else(|| "Unreachable".to_string()),
+                                                            )
+                                                        };
+                                                        view! {
+                                                            <span
+                                                                class=format!("inline-block ml-2 w-2.5 h-2.5 rounded-full {}", class)
+                                                                title=title
+                                                            ></span>
+                                                        }
+                                                    })
+                                            }}
                                         </a>
-                                        <a class="block py-2 text-xs">Size: {model.size.clone()}</a>
+                                        <a class="block py-2 text-xs">
+                                            Size: {model.size.clone()}
+                                            <Show when=move || !model.downloaded>
+                                                <button
+                                                    type="button"
+                                                    class="ml-2 text-blue-600 hover:underline disabled:opacity-50"
+                                                    disabled=move || estimating.get().is_some()
+                                                    on:click={
+                                                        let model_name = estimate_button_name.clone();
+                                                        move |_| estimate_download(model_name.clone())
+                                                    }
+                                                >
+                                                    {
+                                                        let model_name = estimate_button_name.clone();
+                                                        move || {
+                                                            if estimating.get().as_deref() == Some(model_name.as_str()) {
+                                                                "Measuring speed..."
+                                                            } else {
+                                                                "Estimate time"
+                                                            }
+                                                        }
+                                                    }
+                                                </button>
+                                                {
+                                                    let model_name = estimate_name.clone();
+                                                    move || {
+                                                        estimate_for(&model_name)
+                                                            .map(|estimate| match estimate {
+                                                                Ok(secs) => {
+                                                                    view! {
+                                                                        <span class="ml-2 text-gray-500">
+                                                                            {format!("{} at current speed", format_eta(secs))}
+                                                                        </span>
+                                                                    }
+                                                                        .into_any()
+                                                                }
+                                                                Err(e) => {
+                                                                    view! { <span class="ml-2 text-red-600">{e}</span> }.into_any()
+                                                                }
+                                                            })
+                                                    }
+                                                }
+                                            </Show>
+                                        </a>
+                                        <div class="flex space-x-1 text-xs">
+                                            {model_badges(&model)
+                                                .into_iter()
+                                                .map(|badge| {
+                                                    view! {
+                                                        <span class="py-0.5 px-2 text-gray-700 bg-gray-100 rounded dark:text-gray-200 dark:bg-gray-700">
+                                                            {badge}
+                                                        </span>
+                                                    }
+                                                })
+                                                .collect_view()}
+                                        </div>
                                         {move || {
                                             if let Some(progress) = model.progress {
                                                 if progress < 100 {
@@ -218,3 +497,83 @@ pub fn ModelsView() -> impl IntoView {
         </div>
     }
 }
+
+/// Bytes in a size label such as "142 MB" or "1.5 GB".
+fn parse_size(size: &str) -> Option<f64> {
+    let (value, unit) = size.trim().split_once(' ')?;
+    let value: f64 = value.parse().ok()?;
+    match unit {
+        "KB" => Some(value * 1e3),
+        "MB" => Some(value * 1e6),
+        "GB" => Some(value * 1e9),
+        _ => None,
+    }
+}
+
+fn format_size(bytes: f64) -> String {
+    if bytes >= 1e9 {
+        format!("{:.1} GB", bytes / 1e9)
+    } else {
+        format!("{:.0} MB", bytes / 1e6)
+    }
+}
+
+/// Rounded for display, e.g. "~45 s", "~8 min" or "~1 h 20 min".
+fn format_eta(secs: u64) -> String {
+    if secs < 60 {
+        format!("~{} s", secs.max(1))
+    } else if secs < 3600 {
+        format!("~{} min", (secs + 30) / 60)
+    } else {
+        let minutes = (secs + 30) / 60;
+        format!("~{} h {} min", minutes / 60, minutes % 60)
+    }
+}
+
+async fn set_model_pinned(model_name: &str, pinned: bool) -> Result<Vec<WhisperModel>, String> {
+    let args = serde_wasm_bindgen::to_value(&SetModelPinnedArgs { model_name, pinned })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = try_invoke("set_model_pinned", args)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse models: {}", e))
+}
+
+async fn fetch_model_catalog() -> Result<Vec<WhisperModel>, String> {
+    let result = try_invoke("fetch_model_catalog", JsValue::NULL)
+        .await
+        .map_err(|e| format!("Failed to fetch model catalog: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse models: {}", e))
+}
+
+async fn estimate_download_time(model_name: &str) -> Result<u64, String> {
+    let args = serde_wasm_bindgen::to_value(&DownloadModelArgs { model_name })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = try_invoke("estimate_download_time", args)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse estimate: {}", e))
+}
+
+/// Short labels for the model's language support, speed and accuracy.
+fn model_badges(model: &WhisperModel) -> Vec<&'static str> {
+    let mut badges = Vec::new();
+    match model.multilingual {
+        Some(true) => badges.push("Multilingual"),
+        Some(false) => badges.push("English only"),
+        None => {}
+    }
+    match model.speed_tier {
+        Some(ModelTier::High) => badges.push("Fast"),
+        Some(ModelTier::Medium) => badges.push("Balanced"),
+        Some(ModelTier::Low) => badges.push("Slow"),
+        None => {}
+    }
+    match model.quality_tier {
+        Some(ModelTier::High) => badges.push("Best accuracy"),
+        Some(ModelTier::Medium) => badges.push("Good accuracy"),
+        Some(ModelTier::Low) => badges.push("Basic accuracy"),
+        None => {}
+    }
+    badges
+}
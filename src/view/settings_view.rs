@@ -4,20 +4,33 @@ use leptos::{ev::SubmitEvent, prelude::*};
 use serde::{Deserialize, Serialize};
 use serde_wasm_bindgen;
 use wasm_bindgen::prelude::*;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
-use crate::constants::LANGUAGES;
+use crate::components::app_error::js_error_message;
 
 #[wasm_bindgen]
 extern "C" {
     #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
     async fn invoke(cmd: &str, args: JsValue) -> JsValue;
 
-    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"])]
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], js_name = invoke, catch)]
+    async fn try_invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
     async fn open(obj: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
+    async fn save(obj: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "fs"], js_name = readTextFile, catch)]
+    async fn read_text_file(path: &str) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "fs"], js_name = writeTextFile, catch)]
+    async fn write_text_file(path: &str, contents: &str) -> Result<JsValue, JsValue>;
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum OutputFormat {
     Srt,
     Txt,
@@ -45,16 +58,143 @@ impl OutputFormat {
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ResampleQuality {
+    #[default]
+    Fast,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum DownmixMode {
+    #[default]
+    Average,
+    LeftOnly,
+    RightOnly,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub enum OutputMode {
+    #[default]
+    SameAsInput,
+    FixedDir,
+    MirrorTree { root: PathBuf },
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TranscriptionSettings {
+    #[serde(default)]
+    pub version: u32,
     pub language: Option<String>,
     pub model: String,
-    pub output_format: OutputFormat,
+    #[serde(default)]
+    pub fallback_to_downloaded_model: bool,
+    #[serde(default)]
+    pub auto_upgrade_on_low_confidence: bool,
+    #[serde(default)]
+    pub confidence_threshold: f32,
+    #[serde(default)]
+    pub output_formats: Vec<OutputFormat>,
     pub keep_wav: bool,
     pub output_dir: Option<PathBuf>,
-    pub parallel_jobs: usize,
+    #[serde(default)]
+    pub output_mode: OutputMode,
+    #[serde(default)]
+    pub format_output_dirs: BTreeMap<OutputFormat, PathBuf>,
+    #[serde(default)]
+    pub combine_output: bool,
+    #[serde(default)]
+    pub combined_output_path: Option<PathBuf>,
+    #[serde(default)]
+    pub keep_individual_outputs: bool,
+    #[serde(default)]
+    pub open_folder_on_complete: bool,
+    #[serde(default)]
+    pub conversion_jobs: usize,
+    #[serde(default)]
+    pub transcription_jobs: usize,
+    #[serde(default)]
+    pub background_mode: bool,
+    #[serde(default)]
+    pub include_metadata_header: bool,
+    #[serde(default)]
+    pub include_chapters: bool,
+    #[serde(default)]
+    pub include_language_in_output: bool,
+    #[serde(default)]
+    pub mux_subtitles: bool,
+    #[serde(default)]
+    pub max_input_duration_secs: u64,
+    #[serde(default)]
+    pub max_line_chars: usize,
+    #[serde(default)]
+    pub min_cue_duration_ms: u64,
+    #[serde(default)]
+    pub max_cue_duration_ms: u64,
+    #[serde(default)]
+    pub vtt_align: Option<String>,
+    #[serde(default)]
+    pub vtt_line: Option<String>,
+    #[serde(default)]
+    pub carry_context: bool,
+    #[serde(default)]
+    pub anti_repetition: bool,
+    #[serde(default)]
+    pub audio_ctx: Option<i32>,
+    #[serde(default)]
+    pub smooth_progress: bool,
+    #[serde(default)]
+    pub progress_update_interval_ms: u64,
+    #[serde(default)]
+    pub pre_gain_db: f32,
+    #[serde(default)]
+    pub resample_quality: ResampleQuality,
+    #[serde(default)]
+    pub downmix: DownmixMode,
+    #[serde(default)]
+    pub conversion_timeout_secs: u64,
+    #[serde(default)]
+    pub cooldown_secs_between_jobs: u64,
+    #[serde(default)]
+    pub trim_silence: bool,
+    #[serde(default)]
+    pub multilang: bool,
+    #[serde(default)]
+    pub emit_raw_json_sidecar: bool,
+    #[serde(default)]
+    pub advanced_params: BTreeMap<String, f32>,
+    #[serde(default)]
+    pub on_complete_command: Option<String>,
+    #[serde(default)]
+    pub on_complete_webhook: Option<String>,
+    #[serde(default)]
+    pub download_proxy: Option<String>,
+    #[serde(default)]
+    pub model_catalog_url: Option<String>,
+    #[serde(default)]
+    pub download_timeout_secs: u64,
 }
 
+/// Whisper parameters the backend accepts in `advanced_params`.
+const ADVANCED_PARAMS: &[&str] = &[
+    "n_threads",
+    "n_max_text_ctx",
+    "offset_ms",
+    "duration_ms",
+    "max_len",
+    "max_tokens",
+    "audio_ctx",
+    "thold_pt",
+    "thold_ptsum",
+    "temperature",
+    "temperature_inc",
+    "max_initial_ts",
+    "length_penalty",
+    "entropy_thold",
+    "logprob_thold",
+    "no_speech_thold",
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WhisperModel {
     pub name: String,
@@ -63,19 +203,79 @@ pub struct WhisperModel {
     pub downloaded: bool,
     pub file_path: Option<PathBuf>,
     pub progress: Option<f32>,
+    #[serde(default)]
+    pub multilingual: Option<bool>,
 }
 
-// Common languages for Whisper
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Language {
+    pub code: String,
+    pub name: String,
+}
 
+#[derive(Serialize)]
+struct SupportedLanguagesArgs {
+    model_name: String,
+}
 
 #[component]
 pub fn SettingsView() -> impl IntoView {
     let (settings, set_settings) = signal(None::<TranscriptionSettings>);
     let (models, set_models) = signal(Vec::<WhisperModel>::new());
+    let (recommended_model, set_recommended_model) = signal(None::<String>);
     let (loading, set_loading) = signal(true);
     let (saving, set_saving) = signal(false);
     let (error_message, set_error_message) = signal(None::<String>);
     let (success_message, set_success_message) = signal(None::<String>);
+    let (languages, set_languages) = signal(Vec::<Language>::new());
+    let (new_param_key, set_new_param_key) = signal(String::new());
+    let (new_param_value, set_new_param_value) = signal(String::new());
+
+    let selected_model = Memo::new(move |_| {
+        settings.with(|settings| settings.as_ref().map(|settings| settings.model.clone()))
+    });
+    // Models without metadata fall back to the language list, where
+    // English-only models come back with just English
+    let english_only = Memo::new(move |_| {
+        let multilingual = selected_model.get().and_then(|name| {
+            models.with(|models| {
+                models
+                    .iter()
+                    .find(|model| model.name == name)
+                    .and_then(|model| model.multilingual)
+            })
+        });
+        match multilingual {
+            Some(multilingual) => !multilingual,
+            None => languages.with(|languages| languages.len() == 1),
+        }
+    });
+
+    // The language list depends on the selected model
+    Effect::new(move |_| {
+        let Some(model_name) = selected_model.get() else {
+            return;
+        };
+        spawn_local(async move {
+            match get_supported_languages_from_backend(model_name).await {
+                Ok(supported) => {
+                    // Keep the saved language consistent with what the model accepts
+                    if supported.len() == 1 {
+                        let code = supported[0].code.clone();
+                        set_settings.update(|settings| {
+                            if let Some(settings) = settings {
+                                settings.language = Some(code);
+                            }
+                        });
+                    }
+                    set_languages.set(supported);
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("Failed to load languages: {}", e)));
+                }
+            }
+        });
+    });
 
     // Load settings and models on component mount
     Effect::new(move |_| {
@@ -103,6 +303,12 @@ pub fn SettingsView() -> impl IntoView {
             }
             
             set_loading.set(false);
+
+            let result = invoke("recommend_model", JsValue::NULL).await;
+            match serde_wasm_bindgen::from_value::<Option<String>>(result) {
+                Ok(model) => set_recommended_model.set(model),
+                Err(e) => log::error!("Failed to get model recommendation: {:?}", e),
+            }
         });
     });
 
@@ -128,17 +334,32 @@ pub fn SettingsView() -> impl IntoView {
         }
     };
 
-    let on_select_output_dir = move |_: MouseEvent| {
+    // Picks the fixed output folder, or the root of the mirrored tree
+    let pick_output_dir = move |mirror: bool| {
         spawn_local(async move {
             match select_directory().await {
                 Ok(Some(dir)) => {
+                    // Catch read-only or unmounted folders now rather than mid-batch
+                    set_error_message.set(None);
+                    if let Err(e) = check_directory_writable(dir.clone()).await {
+                        set_error_message.set(Some(e));
+                        set_settings.update(|_| {});
+                        return;
+                    }
                     if let Some(mut current_settings) = settings.get() {
-                        current_settings.output_dir = Some(PathBuf::from(dir));
+                        let dir = PathBuf::from(dir);
+                        if mirror {
+                            current_settings.output_mode = OutputMode::MirrorTree { root: dir };
+                        } else {
+                            current_settings.output_dir = Some(dir);
+                            current_settings.output_mode = OutputMode::FixedDir;
+                        }
                         set_settings.set(Some(current_settings));
                     }
                 }
                 Ok(None) => {
-                    // User cancelled
+                    // User cancelled; re-render so the mode select shows the unchanged mode
+                    set_settings.update(|_| {});
                 }
                 Err(e) => {
                     set_error_message.set(Some(format!("Failed to select directory: {}", e)));
@@ -147,9 +368,93 @@ pub fn SettingsView() -> impl IntoView {
         });
     };
 
+    let on_select_output_dir = move |_: MouseEvent| {
+        let mirror = settings.with_untracked(|settings| {
+            matches!(
+                settings.as_ref().map(|settings| &settings.output_mode),
+                Some(OutputMode::MirrorTree { .. })
+            )
+        });
+        pick_output_dir(mirror);
+    };
+
+    let on_export = move |_: MouseEvent| {
+        set_error_message.set(None);
+        set_success_message.set(None);
+        spawn_local(async move {
+            match export_settings_to_file().await {
+                Ok(true) => {
+                    set_success_message.set(Some("Settings exported successfully!".to_string()));
+                }
+                Ok(false) => {
+                    // User cancelled
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("Failed to export settings: {}", e)));
+                }
+            }
+        });
+    };
+
+    let on_import = move |_: MouseEvent| {
+        set_error_message.set(None);
+        set_success_message.set(None);
+        spawn_local(async move {
+            match import_settings_from_file().await {
+                Ok(Some(imported)) => {
+                    set_settings.set(Some(imported));
+                    set_success_message.set(Some("Settings imported successfully!".to_string()));
+                }
+                Ok(None) => {
+                    // User cancelled
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("Failed to import settings: {}", e)));
+                }
+            }
+        });
+    };
+
+    let on_save_profile = move |_: MouseEvent| {
+        let Some(current_settings) = settings.get() else {
+            return;
+        };
+        set_error_message.set(None);
+        set_success_message.set(None);
+        spawn_local(async move {
+            let model = current_settings.model.clone();
+            match save_model_profile_to_backend(current_settings).await {
+                Ok(()) => {
+                    set_success_message.set(Some(format!("Saved as the defaults for {}", model)));
+                }
+                Err(e) => {
+                    set_error_message.set(Some(format!("Failed to save model settings: {}", e)));
+                }
+            }
+        });
+    };
+
     view! {
         <div class="p-6 mx-auto max-w-4xl bg-white">
-            <h1 class="mb-8 text-3xl font-bold text-gray-900">Settings</h1>
+            <div class="flex justify-between items-center mb-8">
+                <h1 class="text-3xl font-bold text-gray-900">Settings</h1>
+                <div class="flex space-x-2">
+                    <button
+                        type="button"
+                        class="py-2 px-4 text-sm font-medium text-gray-700 bg-white rounded-md border border-gray-300 shadow-sm hover:bg-gray-50 focus:ring-2 focus:ring-blue-500 focus:outline-none"
+                        on:click=on_import
+                    >
+                        "Import"
+                    </button>
+                    <button
+                        type="button"
+                        class="py-2 px-4 text-sm font-medium text-gray-700 bg-white rounded-md border border-gray-300 shadow-sm hover:bg-gray-50 focus:ring-2 focus:ring-blue-500 focus:outline-none"
+                        on:click=on_export
+                    >
+                        "Export"
+                    </button>
+                </div>
+            </div>
 
             <Show
                 when=move || loading.get()
@@ -169,8 +474,26 @@ pub fn SettingsView() -> impl IntoView {
                                 let current_settings = settings.get().unwrap();
                                 let settings_clone = current_settings.clone();
                                 let lang_value = settings_clone.language.as_deref().unwrap_or("auto").to_owned();
-                                let output_dir_clone = settings_clone.output_dir.clone();
-                                let has_output_dir = output_dir_clone.is_some();
+                                let output_mode_value = match settings_clone.output_mode {
+                                    OutputMode::SameAsInput => "same",
+                                    OutputMode::FixedDir => "fixed",
+                                    OutputMode::MirrorTree { .. } => "mirror",
+                                };
+                                let output_location = match &settings_clone.output_mode {
+                                    OutputMode::SameAsInput => None,
+                                    OutputMode::FixedDir => settings_clone.output_dir.clone(),
+                                    OutputMode::MirrorTree { root } => Some(root.clone()),
+                                }
+                                    .map(|p| p.to_string_lossy().to_string())
+                                    .unwrap_or_else(|| "Same as input file".to_string());
+                                let advanced_params = settings_clone.advanced_params.clone();
+                                let format_output_dirs = settings_clone.format_output_dirs.clone();
+                                let output_formats = settings_clone.output_formats.clone();
+                                let unused_params: Vec<&'static str> = ADVANCED_PARAMS
+                                    .iter()
+                                    .copied()
+                                    .filter(|key| !advanced_params.contains_key(*key))
+                                    .collect();
                                 view! {
                                     <form on:submit=on_save class="space-y-8">
                                         // Model Selection
@@ -193,9 +516,17 @@ pub fn SettingsView() -> impl IntoView {
                                                             let value = event_target_value(&ev);
                                                             log::info!("selected {}", value);
                                                             if let Some(mut settings) = settings.get() {
-                                                                settings.model = value;
+                                                                settings.model = value.clone();
                                                                 set_settings.set(Some(settings));
                                                             }
+                                                            // Switch to the defaults saved for this model, if any
+                                                            spawn_local(async move {
+                                                                match load_model_profile_from_backend(value).await {
+                                                                    Ok(Some(profile)) => set_settings.set(Some(profile)),
+                                                                    Ok(None) => {}
+                                                                    Err(e) => set_error_message.set(Some(format!("Failed to load model settings: {}", e))),
+                                                                }
+                                                            });
                                                         }
                                                     >
                                                         <For
@@ -216,6 +547,44 @@ pub fn SettingsView() -> impl IntoView {
                                                     <p class="mt-1 text-xs text-gray-500">
                                                         Choose the Whisper model. Larger models are more accurate but slower.
                                                     </p>
+                                                    {move || {
+                                                        recommended_model
+                                                            .get()
+                                                            .map(|model| {
+                                                                view! {
+                                                                    <p class="mt-1 text-xs text-green-700">
+                                                                        {format!("Recommended for this computer: {}", model)}
+                                                                    </p>
+                                                                }
+                                                            })
+                                                    }}
+                                                </div>
+
+                                                <div>
+                                                    <div class="flex items-center">
+                                                        <input
+                                                            type="checkbox"
+                                                            id="fallback_to_downloaded_model"
+                                                            class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                            prop:checked=move || current_settings.fallback_to_downloaded_model
+                                                            on:change=move |ev| {
+                                                                let checked = event_target_checked(&ev);
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.fallback_to_downloaded_model = checked;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        />
+                                                        <label
+                                                            for="fallback_to_downloaded_model"
+                                                            class="block ml-2 text-sm text-gray-900"
+                                                        >
+                                                            Fall back to a downloaded model
+                                                        </label>
+                                                    </div>
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        When the chosen model isn't downloaded, the best downloaded model is used instead.
+                                                    </p>
                                                 </div>
                                             </div>
                                         </div>
@@ -235,7 +604,10 @@ pub fn SettingsView() -> impl IntoView {
                                                     <select
                                                         id="language"
                                                         class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
-                                                        prop:value=lang_value
+                                                        prop:value=move || {
+                                                            if english_only.get() { "en".to_string() } else { lang_value.clone() }
+                                                        }
+                                                        disabled=move || english_only.get()
                                                         on:change=move |ev| {
                                                             let value = event_target_value(&ev);
                                                             if let Some(mut settings) = settings.get() {
@@ -249,177 +621,1255 @@ pub fn SettingsView() -> impl IntoView {
                                                         }
                                                     >
                                                         <For
-                                                            each=|| LANGUAGES.iter().cloned()
-                                                            key=|(code, _)| code.to_string()
-                                                            children=|(code, name)| {
-                                                                view! { <option value=code>{name}</option> }
+                                                            each=move || {
+                                                                let mut options = languages.get();
+                                                                if !english_only.get() {
+                                                                    options.insert(0, Language {
+                                                                        code: "auto".to_string(),
+                                                                        name: "Auto-detect".to_string(),
+                                                                    });
+                                                                }
+                                                                options
+                                                            }
+                                                            key=|language| language.code.clone()
+                                                            children=|language| {
+                                                                view! { <option value=language.code>{language.name}</option> }
                                                             }
                                                         />
                                                     </select>
+                                                    <Show when=move || english_only.get()>
+                                                        <p class="mt-1 text-xs text-gray-500">
+                                                            This model only transcribes English.
+                                                        </p>
+                                                    </Show>
+                                                </div>
+
+                                                <div>
+                                                    <span class="block mb-2 text-sm font-medium text-gray-700">
+                                                        Output Formats
+                                                    </span>
+                                                    <div class="flex gap-4">
+                                                        {[OutputFormat::Srt, OutputFormat::Txt, OutputFormat::Json, OutputFormat::Vtt]
+                                                            .into_iter()
+                                                            .map(|format| {
+                                                                let id = format!("output_format_{}", format.extension());
+                                                                let checked = output_formats.contains(&format);
+                                                                view! {
+                                                                    <div class="flex items-center">
+                                                                        <input
+                                                                            type="checkbox"
+                                                                            id=id.clone()
+                                                                            class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                                            prop:checked=checked
+                                                                            on:change=move |ev| {
+                                                                                let checked = event_target_checked(&ev);
+                                                                                if let Some(mut settings) = settings.get() {
+                                                                                    if checked {
+                                                                                        if !settings.output_formats.contains(&format) {
+                                                                                            settings.output_formats.push(format);
+                                                                                        }
+                                                                                    } else if settings.output_formats.len() > 1 {
+                                                                                        // At least one format is always written
+                                                                                        settings.output_formats.retain(|f| *f != format);
+                                                                                    }
+                                                                                    set_settings.set(Some(settings));
+                                                                                }
+                                                                            }
+                                                                        />
+                                                                        <label for=id class="block ml-2 text-sm text-gray-900">
+                                                                            {format.display_name()}
+                                                                        </label>
+                                                                    </div>
+                                                                }
+                                                            })
+                                                            .collect_view()}
+                                                    </div>
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Each format gets its own file. The first one checked is the one streamed, edited and added to videos.
+                                                    </p>
                                                 </div>
 
                                                 <div>
                                                     <label
-                                                        for="output_format"
+                                                        for="max_line_chars"
                                                         class="block mb-2 text-sm font-medium text-gray-700"
                                                     >
-                                                        Output Format
+                                                        Max Characters per Subtitle Line
                                                     </label>
-                                                    <select
-                                                        id="output_format"
+                                                    <input
+                                                        type="number"
+                                                        id="max_line_chars"
+                                                        min="0"
                                                         class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
-                                                        on:change=move |ev| {
+                                                        prop:value=move || current_settings.max_line_chars.to_string()
+                                                        on:input=move |ev| {
                                                             let value = event_target_value(&ev);
-                                                            if let Some(mut settings) = settings.get() {
-                                                                settings.output_format = match value.as_str() {
-                                                                    "Srt" => OutputFormat::Srt,
-                                                                    "Txt" => OutputFormat::Txt,
-                                                                    "Json" => OutputFormat::Json,
-                                                                    "Vtt" => OutputFormat::Vtt,
-                                                                    _ => OutputFormat::Srt,
-                                                                };
-                                                                set_settings.set(Some(settings));
+                                                            if let Ok(chars) = value.parse::<usize>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.max_line_chars = chars;
+                                                                    set_settings.set(Some(settings));
+                                                                }
                                                             }
                                                         }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        SRT and VTT cues are wrapped onto two lines. 0 disables wrapping.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="min_cue_duration_ms"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
                                                     >
-                                                        <option
-                                                            value="Srt"
-                                                            selected=move || {
-                                                                current_settings.output_format == OutputFormat::Srt
-                                                            }
-                                                        >
-                                                            {OutputFormat::Srt.display_name()}
-                                                        </option>
-                                                        <option
-                                                            value="Txt"
-                                                            selected=move || {
-                                                                current_settings.output_format == OutputFormat::Txt
-                                                            }
-                                                        >
-                                                            {OutputFormat::Txt.display_name()}
-                                                        </option>
-                                                        <option
-                                                            value="Json"
-                                                            selected=move || {
-                                                                current_settings.output_format == OutputFormat::Json
-                                                            }
-                                                        >
-                                                            {OutputFormat::Json.display_name()}
-                                                        </option>
-                                                        <option
-                                                            value="Vtt"
-                                                            selected=move || {
-                                                                current_settings.output_format == OutputFormat::Vtt
+                                                        Minimum Subtitle Duration (ms)
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="min_cue_duration_ms"
+                                                        min="0"
+                                                        step="100"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.min_cue_duration_ms.to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(duration) = value.parse::<u64>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.min_cue_duration_ms = duration;
+                                                                    set_settings.set(Some(settings));
+                                                                }
                                                             }
-                                                        >
-                                                            {OutputFormat::Vtt.display_name()}
-                                                        </option>
-                                                    </select>
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Shorter SRT and VTT cues are merged into the next one. 0 disables merging.
+                                                    </p>
                                                 </div>
-                                            </div>
-                                        </div>
-
-                                        // Processing Settings
-                                        <div class="p-6 bg-gray-50 rounded-lg">
-                                            <h2 class="mb-4 text-xl font-semibold text-gray-900">
-                                                Processing Settings
-                                            </h2>
 
-                                            <div class="grid grid-cols-1 gap-4 md:grid-cols-2">
                                                 <div>
                                                     <label
-                                                        for="parallel_jobs"
+                                                        for="max_cue_duration_ms"
                                                         class="block mb-2 text-sm font-medium text-gray-700"
                                                     >
-                                                        Parallel Jobs
+                                                        Maximum Subtitle Duration (ms)
                                                     </label>
                                                     <input
                                                         type="number"
-                                                        id="parallel_jobs"
-                                                        min="1"
-                                                        max="8"
+                                                        id="max_cue_duration_ms"
+                                                        min="0"
+                                                        step="500"
                                                         class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
-                                                        prop:value=move || current_settings.parallel_jobs.to_string()
+                                                        prop:value=move || current_settings.max_cue_duration_ms.to_string()
                                                         on:input=move |ev| {
                                                             let value = event_target_value(&ev);
-                                                            if let Ok(num) = value.parse::<usize>() {
+                                                            if let Ok(duration) = value.parse::<u64>() {
                                                                 if let Some(mut settings) = settings.get() {
-                                                                    settings.parallel_jobs = num.max(1).min(8);
+                                                                    settings.max_cue_duration_ms = duration;
                                                                     set_settings.set(Some(settings));
                                                                 }
                                                             }
                                                         }
                                                     />
                                                     <p class="mt-1 text-xs text-gray-500">
-                                                        Number of files to process simultaneously (1-8)
+                                                        Longer SRT and VTT cues are split into several, with the text shared between them. 0 disables splitting.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="vtt_align"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        VTT Cue Alignment
+                                                    </label>
+                                                    <select
+                                                        id="vtt_align"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.vtt_align.clone().unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.vtt_align = Some(value).filter(|align| !align.is_empty());
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    >
+                                                        <option value="">"Player default"</option>
+                                                        <option value="start">"Start"</option>
+                                                        <option value="center">"Center"</option>
+                                                        <option value="end">"End"</option>
+                                                        <option value="left">"Left"</option>
+                                                        <option value="right">"Right"</option>
+                                                    </select>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="vtt_line"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        VTT Cue Line
+                                                    </label>
+                                                    <input
+                                                        type="text"
+                                                        id="vtt_line"
+                                                        placeholder="90%"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.vtt_line.clone().unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.vtt_line = Some(value.trim().to_string())
+                                                                    .filter(|line| !line.is_empty());
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        A percentage from the top such as 90%, or a line number such as -1. Invalid values are ignored.
                                                     </p>
                                                 </div>
 
                                                 <div class="flex items-center">
                                                     <input
                                                         type="checkbox"
-                                                        id="keep_wav"
+                                                        id="include_metadata_header"
                                                         class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
-                                                        prop:checked=move || current_settings.keep_wav
+                                                        prop:checked=move || current_settings.include_metadata_header
                                                         on:change=move |ev| {
                                                             let checked = event_target_checked(&ev);
                                                             if let Some(mut settings) = settings.get() {
-                                                                settings.keep_wav = checked;
+                                                                settings.include_metadata_header = checked;
                                                                 set_settings.set(Some(settings));
                                                             }
                                                         }
                                                     />
-                                                    <label for="keep_wav" class="block ml-2 text-sm text-gray-900">
-                                                        Keep WAV files after transcription
+                                                    <label
+                                                        for="include_metadata_header"
+                                                        class="block ml-2 text-sm text-gray-900"
+                                                    >
+                                                        Include metadata header (TXT, SRT, VTT)
                                                     </label>
                                                 </div>
-                                            </div>
-                                        </div>
-
-                                        // Output Directory
-                                        <div class="p-6 bg-gray-50 rounded-lg">
-                                            <h2 class="mb-4 text-xl font-semibold text-gray-900">Output Directory</h2>
 
-                                            <div class="flex items-center space-x-4">
-                                                <div class="flex-1">
+                                                <div class="flex items-center">
                                                     <input
-                                                        type="text"
-                                                        class="py-2 px-3 w-full bg-gray-100 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
-                                                        prop:value=move || {
-                                                            current_settings
-                                                                .output_dir
-                                                                .as_ref()
-                                                                .map(|p| p.to_string_lossy().to_string())
-                                                                .unwrap_or_else(|| "Same as input file".to_string())
+                                                        type="checkbox"
+                                                        id="include_chapters"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.include_chapters
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.include_chapters = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
                                                         }
-                                                        readonly
-                                                        placeholder="Same as input file"
                                                     />
+                                                    <label
+                                                        for="include_chapters"
+                                                        class="block ml-2 text-sm text-gray-900"
+                                                    >
+                                                        Include chapter markers (TXT, VTT, JSON)
+                                                    </label>
                                                 </div>
-                                                <button
-                                                    type="button"
-                                                    class="py-2 px-4 font-medium text-white bg-blue-600 rounded-md shadow-sm hover:bg-blue-700 focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 focus:outline-none"
-                                                    on:click=on_select_output_dir
-                                                >
-                                                    Browse
-                                                </button>
-                                                <button
-                                                    type="button"
-                                                    class="py-2 px-4 font-medium text-white bg-gray-600 rounded-md shadow-sm hover:bg-gray-700 focus:ring-2 focus:ring-gray-500 focus:ring-offset-2 focus:outline-none"
-                                                    on:click=move |_| {
-                                                        if let Some(mut settings) = settings.get() {
-                                                            settings.output_dir = None;
-                                                            set_settings.set(Some(settings));
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="include_language_in_output"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.include_language_in_output
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.include_language_in_output = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
                                                         }
-                                                    }
-                                                >
-                                                    Clear
-                                                </button>
-                                            </div>
-                                            <p class="mt-2 text-xs text-gray-500">
-                                                If not specified, transcription files will be saved in the same directory as the input files.
-                                            </p>
-                                        </div>
+                                                    />
+                                                    <label
+                                                        for="include_language_in_output"
+                                                        class="block ml-2 text-sm text-gray-900"
+                                                    >
+                                                        Include the detected language (JSON, VTT)
+                                                    </label>
+                                                </div>
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="mux_subtitles"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.mux_subtitles
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.mux_subtitles = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="mux_subtitles" class="block ml-2 text-sm text-gray-900">
+                                                        Embed subtitles into a copy of video files (SRT, VTT)
+                                                    </label>
+                                                </div>
+
+                                                <div>
+                                                    <div class="flex items-center">
+                                                        <input
+                                                            type="checkbox"
+                                                            id="carry_context"
+                                                            class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                            prop:checked=move || current_settings.carry_context
+                                                            on:change=move |ev| {
+                                                                let checked = event_target_checked(&ev);
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.carry_context = checked;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        />
+                                                        <label for="carry_context" class="block ml-2 text-sm text-gray-900">
+                                                            Carry context between segments
+                                                        </label>
+                                                    </div>
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Improves consistency on long recordings. Turn off if the output gets stuck repeating a phrase.
+                                                    </p>
+                                                </div>
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="anti_repetition"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.anti_repetition
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.anti_repetition = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="anti_repetition" class="block ml-2 text-sm text-gray-900">
+                                                        Collapse repeated segments
+                                                    </label>
+                                                </div>
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="smooth_progress"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.smooth_progress
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.smooth_progress = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="smooth_progress" class="block ml-2 text-sm text-gray-900">
+                                                        Smooth progress bar
+                                                    </label>
+                                                </div>
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="emit_raw_json_sidecar"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.emit_raw_json_sidecar
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.emit_raw_json_sidecar = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="emit_raw_json_sidecar" class="block ml-2 text-sm text-gray-900">
+                                                        Also write raw segments and tokens (.raw.json)
+                                                    </label>
+                                                </div>
+                                            </div>
+                                        </div>
+
+                                        // Processing Settings
+                                        <div class="p-6 bg-gray-50 rounded-lg">
+                                            <h2 class="mb-4 text-xl font-semibold text-gray-900">
+                                                Processing Settings
+                                            </h2>
+
+                                            <div class="grid grid-cols-1 gap-4 md:grid-cols-2">
+                                                <div>
+                                                    <label
+                                                        for="conversion_jobs"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Conversion Jobs
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="conversion_jobs"
+                                                        min="1"
+                                                        max="8"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.conversion_jobs.to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(num) = value.parse::<usize>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.conversion_jobs = num.max(1).min(8);
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Files converted to WAV at once (1-8)
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="transcription_jobs"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Transcription Jobs
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="transcription_jobs"
+                                                        min="1"
+                                                        max="8"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.transcription_jobs.to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(num) = value.parse::<usize>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.transcription_jobs = num.max(1).min(8);
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Files transcribed at once (1-8)
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <div class="flex items-center">
+                                                        <input
+                                                            type="checkbox"
+                                                            id="background_mode"
+                                                            class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                            prop:checked=move || current_settings.background_mode
+                                                            on:change=move |ev| {
+                                                                let checked = event_target_checked(&ev);
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.background_mode = checked;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        />
+                                                        <label
+                                                            for="background_mode"
+                                                            class="block ml-2 text-sm text-gray-900"
+                                                        >
+                                                            Background mode
+                                                        </label>
+                                                    </div>
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Uses a quarter of the CPU cores so the machine stays responsive. On Linux transcription also runs at a lower priority.
+                                                    </p>
+                                                </div>
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="keep_wav"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.keep_wav
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.keep_wav = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="keep_wav" class="block ml-2 text-sm text-gray-900">
+                                                        Keep WAV files after transcription
+                                                    </label>
+                                                </div>
+
+                                                <div>
+                                                    <div class="flex items-center">
+                                                        <input
+                                                            type="checkbox"
+                                                            id="trim_silence"
+                                                            class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                            prop:checked=move || current_settings.trim_silence
+                                                            on:change=move |ev| {
+                                                                let checked = event_target_checked(&ev);
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.trim_silence = checked;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        />
+                                                        <label for="trim_silence" class="block ml-2 text-sm text-gray-900">
+                                                            Trim leading and trailing silence
+                                                        </label>
+                                                    </div>
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Skips silent stretches at the start and end. Very quiet speech there may be cut, so leave this off for soft-spoken recordings.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="max_input_duration"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Maximum Input Duration (minutes)
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="max_input_duration"
+                                                        min="0"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || (current_settings.max_input_duration_secs / 60).to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(minutes) = value.parse::<u64>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.max_input_duration_secs = minutes * 60;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Longer files are refused to avoid running out of memory. 0 disables the limit.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="pre_gain_db"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        {move || format!("Input Gain: {:+.0} dB", current_settings.pre_gain_db)}
+                                                    </label>
+                                                    <input
+                                                        type="range"
+                                                        id="pre_gain_db"
+                                                        min="-20"
+                                                        max="20"
+                                                        step="1"
+                                                        class="w-full"
+                                                        prop:value=move || current_settings.pre_gain_db.to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(gain) = value.parse::<f32>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.pre_gain_db = gain.clamp(-20.0, 20.0);
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Boosts quiet recordings before transcription. Peaks are limited to avoid clipping.
+                                                    </p>
+                                                </div>
+                                            </div>
+                                        </div>
+
+                                        // Advanced
+                                        <div class="p-6 bg-gray-50 rounded-lg">
+                                            <h2 class="mb-2 text-xl font-semibold text-gray-900">Advanced</h2>
+                                            <p class="mb-4 text-xs text-gray-500">
+                                                Extra whisper parameters, passed through as-is. Integer parameters are rounded.
+                                            </p>
+
+                                            <div class="space-y-2">
+                                                {advanced_params
+                                                    .into_iter()
+                                                    .map(|(key, value)| {
+                                                        view! {
+                                                            <div class="flex items-center space-x-2">
+                                                                <span class="w-48 font-mono text-sm text-gray-700">
+                                                                    {key.clone()}
+                                                                </span>
+                                                                <input
+                                                                    type="number"
+                                                                    step="any"
+                                                                    class="py-1 px-2 w-32 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                                    prop:value=value.to_string()
+                                                                    on:change={
+                                                                        let key = key.clone();
+                                                                        move |ev| {
+                                                                            if let Ok(value) = event_target_value(&ev).parse::<f32>() {
+                                                                                if let Some(mut settings) = settings.get() {
+                                                                                    settings.advanced_params.insert(key.clone(), value);
+                                                                                    set_settings.set(Some(settings));
+                                                                                }
+                                                                            }
+                                                                        }
+                                                                    }
+                                                                />
+                                                                <button
+                                                                    type="button"
+                                                                    class="text-sm text-red-600 hover:underline"
+                                                                    on:click=move |_| {
+                                                                        if let Some(mut settings) = settings.get() {
+                                                                            settings.advanced_params.remove(&key);
+                                                                            set_settings.set(Some(settings));
+                                                                        }
+                                                                    }
+                                                                >
+                                                                    "Remove"
+                                                                </button>
+                                                            </div>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+
+                                                <div class="flex items-center space-x-2">
+                                                    <select
+                                                        class="py-1 px-2 w-48 font-mono text-sm rounded-md border border-gray-300"
+                                                        prop:value=move || new_param_key.get()
+                                                        on:change=move |ev| set_new_param_key.set(event_target_value(&ev))
+                                                    >
+                                                        <option value="">"Parameter..."</option>
+                                                        {unused_params
+                                                            .into_iter()
+                                                            .map(|key| view! { <option value=key>{key}</option> })
+                                                            .collect_view()}
+                                                    </select>
+                                                    <input
+                                                        type="number"
+                                                        step="any"
+                                                        class="py-1 px-2 w-32 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || new_param_value.get()
+                                                        on:input=move |ev| set_new_param_value.set(event_target_value(&ev))
+                                                    />
+                                                    <button
+                                                        type="button"
+                                                        class="py-1 px-3 text-sm font-medium text-gray-700 bg-white rounded-md border border-gray-300 hover:bg-gray-50 disabled:opacity-50 disabled:cursor-not-allowed"
+                                                        disabled=move || {
+                                                            new_param_key.get().is_empty()
+                                                                || new_param_value.get().parse::<f32>().is_err()
+                                                        }
+                                                        on:click=move |_| {
+                                                            let Ok(value) = new_param_value.get().parse::<f32>() else {
+                                                                return;
+                                                            };
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.advanced_params.insert(new_param_key.get(), value);
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                            set_new_param_key.set(String::new());
+                                                            set_new_param_value.set(String::new());
+                                                        }
+                                                    >
+                                                        "Add"
+                                                    </button>
+                                                </div>
+                                            </div>
+
+                                            <h3 class="mt-6 mb-1 text-sm font-medium text-gray-700">Progress Update Interval</h3>
+                                            <p class="mb-2 text-xs text-gray-500">
+                                                How often conversion, transcription and download progress is reported, in milliseconds. Shorter is more responsive, longer is quieter on slow machines.
+                                            </p>
+                                            <input
+                                                type="number"
+                                                id="progress_update_interval_ms"
+                                                min="0"
+                                                step="50"
+                                                class="py-1 px-2 w-32 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                prop:value=move || current_settings.progress_update_interval_ms.to_string()
+                                                on:change=move |ev| {
+                                                    if let Ok(interval) = event_target_value(&ev).parse::<u64>() {
+                                                        if let Some(mut settings) = settings.get() {
+                                                            settings.progress_update_interval_ms = interval;
+                                                            set_settings.set(Some(settings));
+                                                        }
+                                                    }
+                                                }
+                                            />
+
+                                            <h3 class="mt-6 mb-1 text-sm font-medium text-gray-700">Low-Confidence Retry</h3>
+                                            <p class="mb-2 text-xs text-gray-500">
+                                                Transcribes a file again with the next larger downloaded model when whisper's average log probability is below the threshold, keeping the better result.
+                                            </p>
+                                            <div class="flex items-center space-x-4">
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="auto_upgrade_on_low_confidence"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.auto_upgrade_on_low_confidence
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.auto_upgrade_on_low_confidence = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label
+                                                        for="auto_upgrade_on_low_confidence"
+                                                        class="block ml-2 text-sm text-gray-900"
+                                                    >
+                                                        Retry with a larger model
+                                                    </label>
+                                                </div>
+                                                <input
+                                                    type="number"
+                                                    id="confidence_threshold"
+                                                    step="0.1"
+                                                    max="0"
+                                                    class="py-1 px-2 w-32 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                    prop:value=move || current_settings.confidence_threshold.to_string()
+                                                    on:change=move |ev| {
+                                                        if let Ok(threshold) = event_target_value(&ev).parse::<f32>() {
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.confidence_threshold = threshold.min(0.0);
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    }
+                                                />
+                                            </div>
+
+                                            <h3 class="mt-6 mb-1 text-sm font-medium text-gray-700">Per-Format Output Locations</h3>
+                                            <p class="mb-2 text-xs text-gray-500">
+                                                Overrides the output location for one format. Relative folders are inside the input's folder, so "." keeps that format next to the input.
+                                            </p>
+                                            <table class="text-sm">
+                                                <tbody>
+                                                    {[OutputFormat::Srt, OutputFormat::Vtt, OutputFormat::Txt, OutputFormat::Json]
+                                                        .into_iter()
+                                                        .map(|format| {
+                                                            let dir = format_output_dirs
+                                                                .get(&format)
+                                                                .map(|dir| dir.to_string_lossy().to_string())
+                                                                .unwrap_or_default();
+                                                            view! {
+                                                                <tr>
+                                                                    <td class="py-1 pr-4 text-gray-700">{format.display_name()}</td>
+                                                                    <td class="py-1">
+                                                                        <input
+                                                                            type="text"
+                                                                            placeholder="Default location"
+                                                                            class="py-1 px-2 w-80 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                                            prop:value=dir
+                                                                            on:change=move |ev| {
+                                                                                let dir = event_target_value(&ev).trim().to_string();
+                                                                                if let Some(mut settings) = settings.get() {
+                                                                                    if dir.is_empty() {
+                                                                                        settings.format_output_dirs.remove(&format);
+                                                                                    } else {
+                                                                                        settings.format_output_dirs.insert(format, PathBuf::from(dir));
+                                                                                    }
+                                                                                    set_settings.set(Some(settings));
+                                                                                }
+                                                                            }
+                                                                        />
+                                                                    </td>
+                                                                </tr>
+                                                            }
+                                                        })
+                                                        .collect_view()}
+                                                </tbody>
+                                            </table>
+
+                                            <div class="mt-6 space-y-4">
+                                                <div>
+                                                    <label
+                                                        for="on_complete_command"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Run Command When a File Finishes
+                                                    </label>
+                                                    <input
+                                                        type="text"
+                                                        id="on_complete_command"
+                                                        placeholder="notify-send Done {output}"
+                                                        class="py-2 px-3 w-full font-mono text-sm rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.on_complete_command.clone().unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.on_complete_command = Some(value.trim().to_string())
+                                                                    .filter(|command| !command.is_empty());
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        "{input}, {output} and {status} are replaced with the file paths and result."
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="on_complete_webhook"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Webhook URL
+                                                    </label>
+                                                    <input
+                                                        type="url"
+                                                        id="on_complete_webhook"
+                                                        placeholder="https://example.com/hook"
+                                                        class="py-2 px-3 w-full font-mono text-sm rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.on_complete_webhook.clone().unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.on_complete_webhook = Some(value.trim().to_string())
+                                                                    .filter(|url| !url.is_empty());
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Receives a JSON POST with the input, output, status and error of each finished file.
+                                                    </p>
+                                                </div>
+
+                                                <p class="text-xs text-amber-700">
+                                                    The command runs with your user's permissions and the webhook receives your file paths. Only use commands and URLs you trust.
+                                                </p>
+                                            </div>
+
+                                            <div class="grid grid-cols-1 gap-4 mt-6 md:grid-cols-2">
+                                                <div>
+                                                    <label
+                                                        for="download_proxy"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Download Proxy
+                                                    </label>
+                                                    <input
+                                                        type="text"
+                                                        id="download_proxy"
+                                                        placeholder="http://proxy.example.com:8080"
+                                                        class="py-2 px-3 w-full font-mono text-sm rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.download_proxy.clone().unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.download_proxy = Some(value.trim().to_string())
+                                                                    .filter(|proxy| !proxy.is_empty());
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Used for model downloads. When empty, the HTTPS_PROXY environment variable is used if set.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="model_catalog_url"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Model Catalog URL
+                                                    </label>
+                                                    <input
+                                                        type="text"
+                                                        id="model_catalog_url"
+                                                        placeholder="https://example.com/whisper-models.json"
+                                                        class="py-2 px-3 w-full font-mono text-sm rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.model_catalog_url.clone().unwrap_or_default()
+                                                        on:change=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.model_catalog_url = Some(value.trim().to_string())
+                                                                    .filter(|url| !url.is_empty());
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        A JSON list of models with name, size and HTTPS url, shown alongside the built-in models. Checked at most once a day; the last list is kept when offline.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="download_timeout_secs"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Download Stall Timeout (seconds)
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="download_timeout_secs"
+                                                        min="1"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.download_timeout_secs.to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(secs) = value.parse::<u64>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.download_timeout_secs = secs.max(1);
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        A download that receives no data for this long is retried.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="resample_quality"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Resample Quality
+                                                    </label>
+                                                    <select
+                                                        id="resample_quality"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || format!("{:?}", current_settings.resample_quality)
+                                                        on:change=move |ev| {
+                                                            let quality = match event_target_value(&ev).as_str() {
+                                                                "High" => ResampleQuality::High,
+                                                                _ => ResampleQuality::Fast,
+                                                            };
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.resample_quality = quality;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    >
+                                                        <option value="Fast">"Fast"</option>
+                                                        <option value="High">"High"</option>
+                                                    </select>
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        High uses the soxr resampler, which can help with music and high-pitched audio but makes conversion slower.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="downmix"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Stereo Downmix
+                                                    </label>
+                                                    <select
+                                                        id="downmix"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || format!("{:?}", current_settings.downmix)
+                                                        on:change=move |ev| {
+                                                            let downmix = match event_target_value(&ev).as_str() {
+                                                                "LeftOnly" => DownmixMode::LeftOnly,
+                                                                "RightOnly" => DownmixMode::RightOnly,
+                                                                _ => DownmixMode::Average,
+                                                            };
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.downmix = downmix;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    >
+                                                        <option value="Average">"Average all channels"</option>
+                                                        <option value="LeftOnly">"Left channel only"</option>
+                                                        <option value="RightOnly">"Right channel only"</option>
+                                                    </select>
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Use a single channel when it carries the speech, e.g. an interview with each person on their own side.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="conversion_timeout_secs"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Conversion Stall Timeout (seconds)
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="conversion_timeout_secs"
+                                                        min="0"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.conversion_timeout_secs.to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(secs) = value.parse::<u64>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.conversion_timeout_secs = secs;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        A file that produces no audio for this long while converting fails instead of holding up the batch. 0 disables the timeout.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="cooldown_secs_between_jobs"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Cooldown Between Jobs (seconds)
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="cooldown_secs_between_jobs"
+                                                        min="0"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.cooldown_secs_between_jobs.to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(secs) = value.parse::<u64>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.cooldown_secs_between_jobs = secs;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Pause between jobs so a laptop can cool down during long batches, trading speed for steady performance and quieter fans. 0 starts the next job at once.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <div class="flex items-center">
+                                                        <input
+                                                            type="checkbox"
+                                                            id="multilang"
+                                                            class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                            prop:checked=move || current_settings.multilang
+                                                            on:change=move |ev| {
+                                                                let checked = event_target_checked(&ev);
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.multilang = checked;
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        />
+                                                        <label for="multilang" class="block ml-2 text-sm text-gray-900">
+                                                            Detect language per 30 seconds (experimental)
+                                                        </label>
+                                                    </div>
+                                                    <p class="mt-1 text-xs text-amber-600">
+                                                        For recordings that switch languages. Only used when the language is set to auto-detect, makes transcription noticeably slower, and tags each segment's language in JSON output.
+                                                    </p>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="audio_ctx"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Audio Context Size
+                                                    </label>
+                                                    <input
+                                                        type="number"
+                                                        id="audio_ctx"
+                                                        min="0"
+                                                        max="1500"
+                                                        class="py-2 px-3 w-full rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=move || current_settings.audio_ctx.unwrap_or(0).to_string()
+                                                        on:input=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Ok(frames) = value.parse::<i32>() {
+                                                                if let Some(mut settings) = settings.get() {
+                                                                    settings.audio_ctx = (frames > 0).then_some(frames.min(1500));
+                                                                    set_settings.set(Some(settings));
+                                                                }
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Lower values speed up transcription of short clips at some cost in accuracy. 1500 covers 30 seconds of audio; 0 uses the full context.
+                                                    </p>
+                                                </div>
+                                            </div>
+                                        </div>
+
+                                        // Output Directory
+                                        <div class="p-6 bg-gray-50 rounded-lg">
+                                            <h2 class="mb-4 text-xl font-semibold text-gray-900">Output Directory</h2>
+
+                                            <div class="flex items-center space-x-4">
+                                                <select
+                                                    id="output_mode"
+                                                    class="py-2 px-3 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                    prop:value=output_mode_value
+                                                    on:change=move |ev| {
+                                                        let value = event_target_value(&ev);
+                                                        let Some(mut settings) = settings.get() else {
+                                                            return;
+                                                        };
+                                                        match value.as_str() {
+                                                            "fixed" if settings.output_dir.is_some() => {
+                                                                settings.output_mode = OutputMode::FixedDir;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                            "fixed" => pick_output_dir(false),
+                                                            "mirror" => pick_output_dir(true),
+                                                            _ => {
+                                                                settings.output_mode = OutputMode::SameAsInput;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    }
+                                                >
+                                                    <option value="same">"Same as input"</option>
+                                                    <option value="fixed">"Fixed folder"</option>
+                                                    <option value="mirror">"Mirror folder tree"</option>
+                                                </select>
+                                                <div class="flex-1">
+                                                    <input
+                                                        type="text"
+                                                        class="py-2 px-3 w-full bg-gray-100 rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                                                        prop:value=output_location
+                                                        readonly
+                                                        placeholder="Same as input file"
+                                                    />
+                                                </div>
+                                                <button
+                                                    type="button"
+                                                    class="py-2 px-4 font-medium text-white bg-blue-600 rounded-md shadow-sm hover:bg-blue-700 focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 focus:outline-none"
+                                                    on:click=on_select_output_dir
+                                                >
+                                                    Browse
+                                                </button>
+                                                <button
+                                                    type="button"
+                                                    class="py-2 px-4 font-medium text-white bg-gray-600 rounded-md shadow-sm hover:bg-gray-700 focus:ring-2 focus:ring-gray-500 focus:ring-offset-2 focus:outline-none"
+                                                    on:click=move |_| {
+                                                        if let Some(mut settings) = settings.get() {
+                                                            settings.output_dir = None;
+                                                            settings.output_mode = OutputMode::SameAsInput;
+                                                            set_settings.set(Some(settings));
+                                                        }
+                                                    }
+                                                >
+                                                    Clear
+                                                </button>
+                                            </div>
+                                            <p class="mt-2 text-xs text-gray-500">
+                                                If not specified, transcription files will be saved in the same directory as the input files. Mirror folder tree recreates the input subfolders under the chosen folder.
+                                            </p>
+
+                                            <div class="mt-6 space-y-4">
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="combine_output"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.combine_output
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.combine_output = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="combine_output" class="block ml-2 text-sm text-gray-900">
+                                                        Also combine each batch into one file
+                                                    </label>
+                                                </div>
+
+                                                <div>
+                                                    <label
+                                                        for="combined_output_path"
+                                                        class="block mb-2 text-sm font-medium text-gray-700"
+                                                    >
+                                                        Combined File
+                                                    </label>
+                                                    <input
+                                                        type="text"
+                                                        id="combined_output_path"
+                                                        disabled=!current_settings.combine_output
+                                                        placeholder="Next to the first file's output"
+                                                        class="py-2 px-3 w-full font-mono text-sm rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none disabled:bg-gray-100"
+                                                        prop:value=move || {
+                                                            current_settings
+                                                                .combined_output_path
+                                                                .as_ref()
+                                                                .map(|path| path.to_string_lossy().to_string())
+                                                                .unwrap_or_default()
+                                                        }
+                                                        on:change=move |ev| {
+                                                            let value = event_target_value(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.combined_output_path = Some(value.trim().to_string())
+                                                                    .filter(|path| !path.is_empty())
+                                                                    .map(PathBuf::from);
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <p class="mt-1 text-xs text-gray-500">
+                                                        Files are joined in queue order, each one's timestamps continuing from the end of the one before. The batch is only combined if every file completes.
+                                                    </p>
+                                                </div>
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="keep_individual_outputs"
+                                                        disabled=!current_settings.combine_output
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.keep_individual_outputs
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.keep_individual_outputs = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="keep_individual_outputs" class="block ml-2 text-sm text-gray-900">
+                                                        Keep each file's own output
+                                                    </label>
+                                                </div>
+
+                                                <div class="flex items-center">
+                                                    <input
+                                                        type="checkbox"
+                                                        id="open_folder_on_complete"
+                                                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                                                        prop:checked=move || current_settings.open_folder_on_complete
+                                                        on:change=move |ev| {
+                                                            let checked = event_target_checked(&ev);
+                                                            if let Some(mut settings) = settings.get() {
+                                                                settings.open_folder_on_complete = checked;
+                                                                set_settings.set(Some(settings));
+                                                            }
+                                                        }
+                                                    />
+                                                    <label for="open_folder_on_complete" class="block ml-2 text-sm text-gray-900">
+                                                        Open the output folder when a batch finishes
+                                                    </label>
+                                                </div>
+                                            </div>
+                                        </div>
 
                                         // Messages
                                         <Show when=move || error_message.get().is_some()>
@@ -449,7 +1899,14 @@ pub fn SettingsView() -> impl IntoView {
                                         </Show>
 
                                         // Save Button
-                                        <div class="flex justify-end pt-6 border-t border-gray-200">
+                                        <div class="flex justify-end pt-6 space-x-2 border-t border-gray-200">
+                                            <button
+                                                type="button"
+                                                class="py-3 px-6 font-medium text-gray-700 bg-white rounded-md border border-gray-300 shadow-sm hover:bg-gray-50 focus:ring-2 focus:ring-blue-500 focus:outline-none"
+                                                on:click=on_save_profile
+                                            >
+                                                "Save for This Model"
+                                            </button>
                                             <button
                                                 type="submit"
                                                 class="py-3 px-6 font-medium text-white bg-blue-600 rounded-md shadow-sm hover:bg-blue-700 focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 focus:outline-none disabled:opacity-50 disabled:cursor-not-allowed"
@@ -487,19 +1944,58 @@ async fn load_settings_from_backend() -> Result<TranscriptionSettings, String> {
 }
 
 async fn save_settings_to_backend(settings: TranscriptionSettings) -> Result<(), String> {
-    let args = serde_wasm_bindgen::to_value(&settings)
+    // Maps must arrive as JSON objects, not JS `Map`s
+    let args = SaveSettingsArgs { settings }
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
         .map_err(|e| format!("Failed to serialize settings: {}", e))?;
-    
-    let result = invoke("save_settings", args).await;
-    
-    if result.is_truthy() {
-        Ok(())
-    } else {
-        match serde_wasm_bindgen::from_value(result) {
-            Ok(error_msg) => Err(error_msg),
-            Err(_) => Err("Failed to save settings".to_string())
-        }
+    try_invoke("save_settings", args)
+        .await
+        .map(|_| ())
+        .map_err(js_error_message)
+}
+
+/// The settings saved for `model`, if a profile was saved for it.
+async fn load_model_profile_from_backend(
+    model: String,
+) -> Result<Option<TranscriptionSettings>, String> {
+    let args = serde_wasm_bindgen::to_value(&ModelProfileArgs { model })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = try_invoke("load_model_profile", args)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse settings: {}", e))
+}
+
+async fn save_model_profile_to_backend(settings: TranscriptionSettings) -> Result<(), String> {
+    let args = SaveModelProfileArgs {
+        model: settings.model.clone(),
+        settings,
     }
+    .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+    .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    try_invoke("save_model_profile", args)
+        .await
+        .map(|_| ())
+        .map_err(js_error_message)
+}
+
+async fn check_directory_writable(path: String) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&DirectoryArgs { path })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    try_invoke("check_directory_writable", args)
+        .await
+        .map(|_| ())
+        .map_err(js_error_message)
+}
+
+async fn get_supported_languages_from_backend(model_name: String) -> Result<Vec<Language>, String> {
+    let args = serde_wasm_bindgen::to_value(&SupportedLanguagesArgs { model_name })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = try_invoke("get_supported_languages", args)
+        .await
+        .map_err(js_error_message)?;
+
+    serde_wasm_bindgen::from_value(result).map_err(|e| e.to_string())
 }
 
 async fn get_available_models_from_backend() -> Result<Vec<WhisperModel>, String> {
@@ -512,6 +2008,109 @@ async fn get_available_models_from_backend() -> Result<Vec<WhisperModel>, String
     }
 }
 
+async fn export_settings_to_file() -> Result<bool, String> {
+    let options = FileDialogOptions {
+        default_path: Some(SETTINGS_EXPORT_FILE_NAME.to_string()),
+        filters: vec![json_filter()],
+        multiple: false,
+    };
+    let options_js = serde_wasm_bindgen::to_value(&options)
+        .map_err(|e| format!("Failed to create dialog options: {}", e))?;
+
+    let result = save(options_js).await;
+    let Some(path) = result.as_string() else {
+        return Ok(false);
+    };
+
+    let json = try_invoke("export_settings", JsValue::NULL)
+        .await
+        .map_err(js_error_message)?
+        .as_string()
+        .ok_or_else(|| "Unexpected export result".to_string())?;
+    write_text_file(&path, &json).await.map_err(js_error_message)?;
+    Ok(true)
+}
+
+async fn import_settings_from_file() -> Result<Option<TranscriptionSettings>, String> {
+    let options = FileDialogOptions {
+        default_path: None,
+        filters: vec![json_filter()],
+        multiple: false,
+    };
+    let options_js = serde_wasm_bindgen::to_value(&options)
+        .map_err(|e| format!("Failed to create dialog options: {}", e))?;
+
+    let result = open(options_js).await;
+    let Some(path) = result.as_string() else {
+        return Ok(None);
+    };
+
+    let json = read_text_file(&path)
+        .await
+        .map_err(js_error_message)?
+        .as_string()
+        .ok_or_else(|| "Settings file is not text".to_string())?;
+    let args = serde_wasm_bindgen::to_value(&ImportSettingsArgs { json })
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    let result = try_invoke("import_settings", args)
+        .await
+        .map_err(js_error_message)?;
+
+    serde_wasm_bindgen::from_value(result)
+        .map(Some)
+        .map_err(|e| format!("Failed to deserialize settings: {}", e))
+}
+
+fn json_filter() -> DialogFilter {
+    DialogFilter {
+        name: "JSON".to_string(),
+        extensions: vec!["json".to_string()],
+    }
+}
+
+const SETTINGS_EXPORT_FILE_NAME: &str = "whisper-tauri-settings.json";
+
+#[derive(Serialize)]
+struct ImportSettingsArgs {
+    json: String,
+}
+
+#[derive(Serialize)]
+struct SaveSettingsArgs {
+    settings: TranscriptionSettings,
+}
+
+#[derive(Serialize)]
+struct ModelProfileArgs {
+    model: String,
+}
+
+#[derive(Serialize)]
+struct SaveModelProfileArgs {
+    model: String,
+    settings: TranscriptionSettings,
+}
+
+#[derive(Serialize)]
+struct DirectoryArgs {
+    path: String,
+}
+
+#[derive(Serialize)]
+struct DialogFilter {
+    name: String,
+    extensions: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FileDialogOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_path: Option<String>,
+    filters: Vec<DialogFilter>,
+    multiple: bool,
+}
+
 #[derive(Serialize)]
 struct DialogOptions {
     directory: bool,
@@ -1,11 +1,1653 @@
+use leptos::ev::MouseEvent;
 use leptos::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use wasm_bindgen::prelude::*;
+
+use crate::components::app_error::js_error_message;
+use crate::view::settings_view::{OutputFormat, TranscriptionSettings, WhisperModel};
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "core"], catch)]
+    async fn invoke(cmd: &str, args: JsValue) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
+    async fn open(options: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "dialog"])]
+    async fn save(options: JsValue) -> JsValue;
+
+    #[wasm_bindgen(js_namespace = ["window", "__TAURI__", "event"])]
+    async fn listen(event: &str, handler: &js_sys::Function) -> JsValue;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum FileStatus {
+    Pending,
+    Queued,
+    Paused,
+    Converting,
+    Transcribing,
+    Completed,
+    Error,
+    Cancelled,
+}
+
+impl FileStatus {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            FileStatus::Pending => "Pending",
+            FileStatus::Queued => "Queued",
+            FileStatus::Paused => "Paused",
+            FileStatus::Converting => "Converting",
+            FileStatus::Transcribing => "Transcribing",
+            FileStatus::Completed => "Completed",
+            FileStatus::Error => "Error",
+            FileStatus::Cancelled => "Cancelled",
+        }
+    }
+
+    fn text_class(&self) -> &'static str {
+        match self {
+            FileStatus::Pending => "text-gray-700",
+            FileStatus::Queued | FileStatus::Cancelled => "text-gray-500",
+            FileStatus::Paused => "text-amber-600",
+            FileStatus::Converting | FileStatus::Transcribing => "text-blue-600",
+            FileStatus::Completed => "text-green-700",
+            FileStatus::Error => "text-red-600",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FileEntry {
+    pub id: String,
+    pub path: PathBuf,
+    pub name: String,
+    pub size: u64,
+    pub status: FileStatus,
+    pub progress: f32,
+    pub error: Option<String>,
+    pub output_path: Option<PathBuf>,
+    #[serde(default)]
+    pub audio_stream_index: Option<usize>,
+    #[serde(default)]
+    pub notice: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AudioStreamInfo {
+    index: usize,
+    codec: String,
+    language: Option<String>,
+    channels: u16,
+    sample_rate: u32,
+    bit_rate: Option<usize>,
+}
+
+impl AudioStreamInfo {
+    fn label(&self) -> String {
+        format!(
+            "#{} {} {}ch{}",
+            self.index,
+            self.codec,
+            self.channels,
+            self.language
+                .as_ref()
+                .map(|language| format!(" ({})", language))
+                .unwrap_or_default()
+        )
+    }
+
+    fn details(&self) -> String {
+        let mut text = format!("{}, {} Hz", self.label(), self.sample_rate);
+        if let Some(bit_rate) = self.bit_rate {
+            text.push_str(&format!(", {} kb/s", bit_rate / 1000));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct MediaInfo {
+    format: String,
+    duration_secs: Option<f64>,
+    bit_rate: Option<usize>,
+    audio_streams: Vec<AudioStreamInfo>,
+}
+
+impl MediaInfo {
+    fn summary(&self) -> String {
+        let mut text = self.format.clone();
+        if let Some(secs) = self.duration_secs {
+            let secs = secs.round() as u64;
+            text.push_str(&format!(
+                ", {}:{:02}:{:02}",
+                secs / 3600,
+                secs / 60 % 60,
+                secs % 60
+            ));
+        }
+        if let Some(bit_rate) = self.bit_rate {
+            text.push_str(&format!(", {} kb/s", bit_rate / 1000));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct SubtitleCue {
+    start_ms: u64,
+    end_ms: u64,
+    text: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ParsedSubtitles {
+    cues: Vec<SubtitleCue>,
+    errors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct DirectoryScanResult {
+    files: Vec<FileEntry>,
+    skipped: usize,
+    duplicates: usize,
+    truncated: bool,
+}
+
+/// A whisper segment as the backend sends it. Times are in centiseconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Segment {
+    start: i64,
+    end: i64,
+    text: String,
+    tokens: Vec<Token>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Token {
+    id: i32,
+    text: String,
+    start: i64,
+    end: i64,
+    probability: f32,
+}
+
+/// Segments from `refine_range`, waiting for the user to accept them.
+#[derive(Debug, Clone)]
+struct RefinedRange {
+    start_ms: u64,
+    end_ms: u64,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct AddFilesResult {
+    files: Vec<FileEntry>,
+    duplicates: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BatchEstimate {
+    total_duration_secs: f64,
+    estimated_processing_secs: Option<f64>,
+    output_count: usize,
+    unknown_duration_count: usize,
+}
+
+/// The parts of a backend job the file list shows.
+#[derive(Debug, Clone, Deserialize)]
+struct JobSnapshot {
+    id: String,
+    status: FileStatus,
+    progress: f32,
+    error: Option<String>,
+}
+
+/// The backend's `queue-status` event; only the summary line is shown.
+#[derive(Debug, Clone, Deserialize)]
+struct QueueStatus {
+    summary: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProgressUpdate {
+    file_id: String,
+    status: FileStatus,
+    progress: f32,
+    message: Option<String>,
+    #[serde(default)]
+    output_path: Option<PathBuf>,
+    #[serde(default)]
+    notice: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TranscriptionArgs {
+    files: Vec<FileEntry>,
+    settings: TranscriptionSettings,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AddFilesArgs {
+    file_paths: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct JobArgs {
+    job_id: String,
+}
+
+#[derive(Serialize)]
+struct MoveJobArgs {
+    job_id: String,
+    new_index: usize,
+}
+
+#[derive(Serialize)]
+struct ReexportArgs {
+    job_id: String,
+    format: OutputFormat,
+}
+
+#[derive(Serialize)]
+struct RefineRangeArgs {
+    job_id: String,
+    start_secs: f64,
+    end_secs: f64,
+    model: String,
+}
+
+#[derive(Serialize)]
+struct ApplyRefinedRangeArgs {
+    job_id: String,
+    start_secs: f64,
+    end_secs: f64,
+    segments: Vec<Segment>,
+}
+
+#[derive(Serialize)]
+struct SaveTranscriptArgs {
+    job_id: String,
+    target_path: String,
+}
+
+#[derive(Serialize)]
+struct SaveEditedTranscriptArgs {
+    job_id: String,
+    content: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SaveDialogOptions {
+    default_path: Option<String>,
+}
+
+#[derive(Serialize)]
+struct FilePathArgs {
+    file_path: String,
+}
+
+#[derive(Serialize)]
+struct AddUrlArgs {
+    url: String,
+}
+
+#[derive(Serialize)]
+struct AddDirectoryArgs {
+    dir_path: String,
+    recursive: bool,
+}
+
+#[derive(Serialize)]
+struct DialogOptions {
+    directory: bool,
+    multiple: bool,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScanProgress {
+    scanned: usize,
+}
+
+#[derive(Deserialize, Debug)]
+struct TauriEvent<T> {
+    payload: T,
+}
 
 #[component]
 pub fn WhisperView() -> impl IntoView {
+    let (files, set_files) = signal(Vec::<FileEntry>::new());
+    let (recursive, set_recursive) = signal(true);
+    let (scanning, set_scanning) = signal(false);
+    let (scanned, set_scanned) = signal(0usize);
+    let (url, set_url) = signal(String::new());
+    let (downloading, set_downloading) = signal(false);
+    let (error_message, set_error_message) = signal(None::<String>);
+    let (info_message, set_info_message) = signal(None::<String>);
+    let (settings, set_settings) = signal(None::<TranscriptionSettings>);
+    let (estimate, set_estimate) = signal(None::<BatchEstimate>);
+    let (downloaded_models, set_downloaded_models) = signal(Vec::<String>::new());
+    let (queue_status, set_queue_status) = signal(None::<QueueStatus>);
+
+    let pending_files = Memo::new(move |_| {
+        files
+            .get()
+            .into_iter()
+            .filter(|file| file.status == FileStatus::Pending)
+            .collect::<Vec<_>>()
+    });
+
+    spawn_local(async move {
+        match load_settings_from_backend().await {
+            Ok(loaded_settings) => {
+                // Say up front when a batch would run a different model
+                match get_effective_settings_in_backend(loaded_settings.clone()).await {
+                    Ok(effective) if effective.model != loaded_settings.model => {
+                        set_info_message.set(Some(format!(
+                            "{} isn't downloaded, {} will be used instead.",
+                            loaded_settings.model, effective.model
+                        )))
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::error!("Failed to check the model: {}", e),
+                }
+                set_settings.set(Some(loaded_settings));
+            }
+            Err(e) => set_error_message.set(Some(format!("Failed to load settings: {}", e))),
+        }
+    });
+
+    // Models a stretch of a finished transcript can be redone with
+    spawn_local(async move {
+        match get_downloaded_models_in_backend().await {
+            Ok(models) => set_downloaded_models.set(models),
+            Err(e) => log::error!("Failed to load models: {}", e),
+        }
+    });
+
+    // Re-estimate whenever the set of files waiting to run changes
+    Effect::new(move |_| {
+        let pending = pending_files.get();
+        let Some(current_settings) = settings.get() else {
+            return;
+        };
+        if pending.is_empty() {
+            set_estimate.set(None);
+            return;
+        }
+        spawn_local(async move {
+            match estimate_batch_in_backend(pending, current_settings).await {
+                Ok(batch_estimate) => set_estimate.set(Some(batch_estimate)),
+                Err(e) => {
+                    log::error!("Failed to estimate batch: {}", e);
+                    set_estimate.set(None);
+                }
+            }
+        });
+    });
+
+    spawn_local(async move {
+        let closure = Closure::<dyn FnMut(_)>::new(move |s: JsValue| {
+            match serde_wasm_bindgen::from_value::<TauriEvent<ProgressUpdate>>(s) {
+                Ok(event) => {
+                    let update = event.payload;
+                    set_files.update(|files| {
+                        if let Some(file) = files.iter_mut().find(|f| f.id == update.file_id) {
+                            file.status = update.status;
+                            file.progress = update.progress;
+                            file.error = update.message;
+                            file.output_path = update.output_path;
+                            file.notice = update.notice;
+                        }
+                    });
+                }
+                Err(e) => log::error!("Failed to parse transcription progress event: {:?}", e),
+            }
+        });
+        listen("transcription-progress", closure.as_ref().unchecked_ref()).await;
+        closure.forget();
+    });
+
+    spawn_local(async move {
+        let closure = Closure::<dyn FnMut(_)>::new(move |s: JsValue| {
+            match serde_wasm_bindgen::from_value::<TauriEvent<QueueStatus>>(s) {
+                Ok(event) => set_queue_status.set(Some(event.payload)),
+                Err(e) => log::error!("Failed to parse queue status event: {:?}", e),
+            }
+        });
+        listen("queue-status", closure.as_ref().unchecked_ref()).await;
+        closure.forget();
+    });
+
+    spawn_local(async move {
+        let closure = Closure::<dyn FnMut(_)>::new(move |s: JsValue| {
+            match serde_wasm_bindgen::from_value::<TauriEvent<ScanProgress>>(s) {
+                Ok(event) => set_scanned.set(event.payload.scanned),
+                Err(e) => log::error!("Failed to parse scan progress event: {:?}", e),
+            }
+        });
+        listen("add-directory-progress", closure.as_ref().unchecked_ref()).await;
+        closure.forget();
+    });
+
+    let on_add_files = move |_: MouseEvent| {
+        spawn_local(async move {
+            set_error_message.set(None);
+            set_info_message.set(None);
+            match select_files().await {
+                Ok(paths) if !paths.is_empty() => match add_files_to_backend(paths).await {
+                    Ok(added) => {
+                        if added.duplicates > 0 {
+                            set_info_message.set(Some(format!(
+                                "Skipped {} files that were already added",
+                                added.duplicates
+                            )));
+                        }
+                        set_files.update(|files| files.extend(added.files));
+                    }
+                    Err(e) => set_error_message.set(Some(e)),
+                },
+                Ok(_) => {
+                    // User cancelled
+                }
+                Err(e) => set_error_message.set(Some(e)),
+            }
+        });
+    };
+
+    let on_add_folder = move |_: MouseEvent| {
+        spawn_local(async move {
+            set_error_message.set(None);
+            set_info_message.set(None);
+            match select_directory().await {
+                Ok(Some(dir)) => {
+                    set_scanning.set(true);
+                    set_scanned.set(0);
+                    match add_directory_to_backend(dir, recursive.get_untracked()).await {
+                        Ok(result) => {
+                            let mut message = format!(
+                                "Added {} files, skipped {} items",
+                                result.files.len(),
+                                result.skipped
+                            );
+                            if result.duplicates > 0 {
+                                message.push_str(&format!(
+                                    ", {} already added",
+                                    result.duplicates
+                                ));
+                            }
+                            if result.truncated {
+                                message.push_str(" (stopped at the file limit)");
+                            }
+                            set_info_message.set(Some(message));
+                            set_files.update(|files| files.extend(result.files));
+                        }
+                        Err(e) => set_error_message.set(Some(e)),
+                    }
+                    set_scanning.set(false);
+                }
+                Ok(None) => {
+                    // User cancelled
+                }
+                Err(e) => set_error_message.set(Some(e)),
+            }
+        });
+    };
+
+    let on_add_url = move |_: MouseEvent| {
+        let url = url.get_untracked();
+        if url.trim().is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            set_error_message.set(None);
+            set_info_message.set(None);
+            set_downloading.set(true);
+            match add_url_to_backend(url).await {
+                Ok(file) => {
+                    set_url.set(String::new());
+                    set_files.update(|files| files.push(file));
+                }
+                Err(e) => set_error_message.set(Some(e)),
+            }
+            set_downloading.set(false);
+        });
+    };
+
+    // Reorders the list, and the backend queue once the file has been started
+    let move_file = move |job_id: String, offset: isize| {
+        let mut new_index = None;
+        set_files.update(|files| {
+            let Some(index) = files.iter().position(|f| f.id == job_id) else {
+                return;
+            };
+            let Some(target) = index.checked_add_signed(offset).filter(|t| *t < files.len()) else {
+                return;
+            };
+            let file = files.remove(index);
+            files.insert(target, file);
+            new_index = Some(target);
+        });
+        if let Some(new_index) = new_index {
+            spawn_local(async move {
+                if let Err(e) = move_job_in_backend(job_id, new_index).await {
+                    // Files that haven't been started aren't in the queue yet
+                    log::debug!("{}", e);
+                }
+            });
+        }
+    };
+
+    let on_start = move |_: MouseEvent| {
+        let Some(current_settings) = settings.get_untracked() else {
+            return;
+        };
+        let pending = pending_files.get_untracked();
+        if pending.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            set_error_message.set(None);
+            set_info_message.set(None);
+            match start_transcription_in_backend(pending, current_settings).await {
+                // Format warnings are informational; the batch has started
+                Ok(warnings) if !warnings.is_empty() => {
+                    set_info_message.set(Some(format!("{}.", warnings.join(". "))))
+                }
+                Ok(_) => {}
+                Err(e) => set_error_message.set(Some(e)),
+            }
+        });
+    };
+
+    let has_active_jobs = Memo::new(move |_| {
+        files.get().iter().any(|file| {
+            matches!(
+                file.status,
+                FileStatus::Queued | FileStatus::Paused | FileStatus::Converting | FileStatus::Transcribing
+            )
+        })
+    });
+
+    let on_stop_all = move |_: MouseEvent| {
+        spawn_local(async move {
+            set_error_message.set(None);
+            match cancel_all_jobs_in_backend().await {
+                Ok(jobs) => set_files.update(|files| {
+                    for job in jobs {
+                        if let Some(file) = files.iter_mut().find(|f| f.id == job.id) {
+                            file.status = job.status;
+                            file.progress = job.progress;
+                            file.error = job.error;
+                        }
+                    }
+                }),
+                Err(e) => set_error_message.set(Some(e)),
+            }
+        });
+    };
+
+    let has_failed_jobs = Memo::new(move |_| {
+        files
+            .get()
+            .iter()
+            .any(|file| matches!(file.status, FileStatus::Error))
+    });
+
+    let on_retry_failed = move |_: MouseEvent| {
+        spawn_local(async move {
+            set_error_message.set(None);
+            match retry_all_failed_in_backend().await {
+                Ok(jobs) => set_files.update(|files| {
+                    for job in jobs {
+                        if let Some(file) = files.iter_mut().find(|f| f.id == job.id) {
+                            file.status = job.status;
+                            file.progress = job.progress;
+                            file.error = job.error;
+                        }
+                    }
+                }),
+                Err(e) => set_error_message.set(Some(e)),
+            }
+        });
+    };
+
     view! {
         <div class="p-6">
-            <h2 class="mb-4 text-2xl font-bold text-gray-900">"Whisper Transcription"</h2>
-            <p class="text-gray-600">"Whisper view functionality coming soon..."</p>
+            <div class="flex items-baseline mb-4 space-x-3">
+                <h2 class="text-2xl font-bold text-gray-900">"Whisper Transcription"</h2>
+                <span class="text-sm text-gray-500">
+                    {move || queue_status.get().map(|status| status.summary).unwrap_or_default()}
+                </span>
+            </div>
+
+            <div class="flex items-center mb-4 space-x-4">
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-blue-600 rounded-md shadow-sm hover:bg-blue-700 focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 focus:outline-none disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || scanning.get()
+                    on:click=on_add_files
+                >
+                    "Add Files"
+                </button>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-blue-600 rounded-md shadow-sm hover:bg-blue-700 focus:ring-2 focus:ring-blue-500 focus:ring-offset-2 focus:outline-none disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || scanning.get()
+                    on:click=on_add_folder
+                >
+                    "Add Folder"
+                </button>
+                <div class="flex items-center">
+                    <input
+                        type="checkbox"
+                        id="recursive"
+                        class="w-4 h-4 text-blue-600 rounded border-gray-300 focus:ring-blue-500"
+                        prop:checked=move || recursive.get()
+                        on:change=move |ev| set_recursive.set(event_target_checked(&ev))
+                    />
+                    <label for="recursive" class="block ml-2 text-sm text-gray-900">
+                        "Include subfolders"
+                    </label>
+                </div>
+                <div class="flex-1"></div>
+                <span class="text-sm text-gray-600">
+                    {move || estimate.get().map(|e| format_estimate(&e)).unwrap_or_default()}
+                </span>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-green-600 rounded-md shadow-sm hover:bg-green-700 focus:ring-2 focus:ring-green-500 focus:ring-offset-2 focus:outline-none disabled:opacity-50 disabled:cursor-not-allowed"
+                    disabled=move || {
+                        scanning.get() || settings.get().is_none() || pending_files.get().is_empty()
+                    }
+                    on:click=on_start
+                >
+                    "Start"
+                </button>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-white bg-red-600 rounded-md shadow-sm hover:bg-red-700 focus:ring-2 focus:ring-red-500 focus:ring-offset-2 focus:outline-none disabled:opacity-50 disabled:cursor-not-allowed"
+                    title="Cancels every running and waiting file. Completed files are kept."
+                    disabled=move || !has_active_jobs.get()
+                    on:click=on_stop_all
+                >
+                    "Stop All"
+                </button>
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-gray-700 bg-white rounded-md border border-gray-300 shadow-sm hover:bg-gray-50 disabled:opacity-50 disabled:cursor-not-allowed"
+                    title="Queues every failed file again"
+                    disabled=move || !has_failed_jobs.get()
+                    on:click=on_retry_failed
+                >
+                    "Retry Failed"
+                </button>
+            </div>
+
+            <div class="flex items-center mb-4 space-x-2">
+                <input
+                    type="url"
+                    placeholder="https://example.com/episode.mp3"
+                    class="flex-1 py-2 px-3 text-sm rounded-md border border-gray-300 shadow-sm focus:border-blue-500 focus:ring-blue-500 focus:outline-none"
+                    prop:value=move || url.get()
+                    on:input=move |ev| set_url.set(event_target_value(&ev))
+                />
+                <button
+                    type="button"
+                    class="py-2 px-4 font-medium text-gray-700 bg-white rounded-md border border-gray-300 shadow-sm hover:bg-gray-50 disabled:opacity-50 disabled:cursor-not-allowed"
+                    title="Downloads the media to your Downloads folder and adds it"
+                    disabled=move || downloading.get() || url.get().trim().is_empty()
+                    on:click=on_add_url
+                >
+                    {move || if downloading.get() { "Downloading..." } else { "Add URL" }}
+                </button>
+            </div>
+
+            <Show when=move || scanning.get()>
+                <p class="mb-4 text-sm text-gray-600">
+                    {move || format!("Scanning folder... {} items checked", scanned.get())}
+                </p>
+            </Show>
+
+            <Show when=move || info_message.get().is_some()>
+                <p class="mb-4 text-sm text-gray-600">
+                    {move || info_message.get().unwrap_or_default()}
+                </p>
+            </Show>
+
+            <Show when=move || error_message.get().is_some()>
+                <div class="p-4 mb-4 bg-red-50 rounded-md border border-red-200">
+                    <div class="text-sm text-red-700">
+                        {move || error_message.get().unwrap_or_default()}
+                    </div>
+                </div>
+            </Show>
+
+            <Show
+                when=move || !files.get().is_empty()
+                fallback=|| view! { <p class="text-gray-600">"No files added yet."</p> }
+            >
+                <div class="bg-white rounded-lg divide-y divide-gray-100 shadow-sm">
+                    <For
+                        each=move || files.get()
+                        key=|file| format!("{}-{:?}-{}", file.id, file.status, file.progress)
+                        children=move |file| {
+                            let is_completed = file.status == FileStatus::Completed;
+                            let is_pending = file.status == FileStatus::Pending;
+                            let is_waiting = matches!(file.status, FileStatus::Pending | FileStatus::Queued);
+                            let job_id = file.id.clone();
+                            let file_name = file.name.clone();
+                            let output_name = file
+                                .output_path
+                                .as_ref()
+                                .and_then(|path| path.file_name())
+                                .map(|name| name.to_string_lossy().to_string());
+                            let has_cues = file
+                                .output_path
+                                .as_ref()
+                                .and_then(|path| path.extension())
+                                .is_some_and(|ext| ext == "srt" || ext == "vtt");
+                            let subtitles = RwSignal::new(None::<ParsedSubtitles>);
+                            let selected_cue = RwSignal::new(None::<usize>);
+                            let edited_text = RwSignal::new(None::<String>);
+                            let refine_model = RwSignal::new(String::new());
+                            let refining = RwSignal::new(false);
+                            let refined = RwSignal::new(None::<RefinedRange>);
+                            let refine_job_id = file.id.clone();
+                            let accept_job_id = file.id.clone();
+                            let edit_job_id = file.id.clone();
+                            let stream_job_id = file.id.clone();
+                            let queue_job_id = file.id.clone();
+                            let file_path = file.path.clone();
+                            let info_path = file.path.clone();
+                            let media_info = RwSignal::new(None::<MediaInfo>);
+                            let selected_stream = file.audio_stream_index;
+                            let audio_streams = RwSignal::new(None::<Vec<AudioStreamInfo>>);
+                            let export_choice = RwSignal::new(String::new());
+                            view! {
+                                <div class="flex justify-between items-center py-3 px-4">
+                                    <div>
+                                        <p class="text-sm font-medium text-gray-900">
+                                            {file.name.clone()}
+                                        </p>
+                                        <p class="text-xs text-gray-500">
+                                            {file.path.to_string_lossy().to_string()}
+                                            <button
+                                                type="button"
+                                                class="ml-2 text-blue-600 hover:underline"
+                                                on:click=move |_| {
+                                                    if media_info.get_untracked().is_some() {
+                                                        media_info.set(None);
+                                                        return;
+                                                    }
+                                                    let file_path = info_path.clone();
+                                                    spawn_local(async move {
+                                                        match get_media_info_in_backend(file_path).await {
+                                                            Ok(info) => media_info.set(Some(info)),
+                                                            Err(e) => set_error_message.set(Some(e)),
+                                                        }
+                                                    });
+                                                }
+                                            >
+                                                {move || if media_info.get().is_some() { "Hide info" } else { "Info" }}
+                                            </button>
+                                        </p>
+                                        {move || {
+                                            media_info
+                                                .get()
+                                                .map(|info| {
+                                                    view! {
+                                                        <div class="mt-1 text-xs text-gray-600">
+                                                            <p>{info.summary()}</p>
+                                                            {info
+                                                                .audio_streams
+                                                                .iter()
+                                                                .map(|stream| view! { <p>{stream.details()}</p> })
+                                                                .collect_view()}
+                                                        </div>
+                                                    }
+                                                })
+                                        }}
+                                        {move || {
+                                            subtitles
+                                                .get()
+                                                .map(|parsed| {
+                                                    let cues = parsed.cues.clone();
+                                                    view! {
+                                                        <div class="overflow-y-auto mt-2 max-h-64 text-xs rounded border border-gray-200 divide-y divide-gray-100">
+                                                            {parsed
+                                                                .cues
+                                                                .into_iter()
+                                                                .enumerate()
+                                                                .map(|(index, cue)| {
+                                                                    view! {
+                                                                        <div
+                                                                            class=move || {
+                                                                                if selected_cue.get() == Some(index) {
+                                                                                    "flex py-1 px-2 cursor-pointer bg-blue-50"
+                                                                                } else {
+                                                                                    "flex py-1 px-2 cursor-pointer hover:bg-gray-50"
+                                                                                }
+                                                                            }
+                                                                            on:click=move |_| selected_cue.set(Some(index))
+                                                                        >
+                                                                            <span class="mr-3 font-mono text-gray-500 whitespace-nowrap">
+                                                                                {format_cue_time(cue.start_ms)}
+                                                                                " → "
+                                                                                {format_cue_time(cue.end_ms)}
+                                                                            </span>
+                                                                            <span class="text-gray-800 whitespace-pre-line">
+                                                                                {cue.text}
+                                                                            </span>
+                                                                        </div>
+                                                                    }
+                                                                })
+                                                                .collect_view()}
+                                                        </div>
+                                                        {(!parsed.errors.is_empty())
+                                                            .then(|| {
+                                                                view! {
+                                                                    <p
+                                                                        class="mt-1 text-xs text-amber-600"
+                                                                        title=parsed.errors.join("\n")
+                                                                    >
+                                                                        {format!("Skipped {} malformed cue(s)", parsed.errors.len())}
+                                                                    </p>
+                                                                }
+                                                            })}
+                                                        {
+                                                            let job_id = refine_job_id.clone();
+                                                            move || {
+                                                                let cue = selected_cue
+                                                                    .get()
+                                                                    .and_then(|index| cues.get(index).cloned())?;
+                                                                let job_id = job_id.clone();
+                                                                Some(view! {
+                                                                    <div class="flex items-center mt-2 space-x-2 text-xs">
+                                                                        <span class="text-gray-600">"Redo the selected cue with"</span>
+                                                                        <select
+                                                                            class="py-0.5 px-1 rounded border border-gray-300"
+                                                                            prop:value=move || refine_model.get()
+                                                                            on:change=move |ev| refine_model.set(event_target_value(&ev))
+                                                                        >
+                                                                            <option value="">"Choose a model"</option>
+                                                                            {downloaded_models
+                                                                                .get()
+                                                                                .into_iter()
+                                                                                .map(|name| view! { <option value=name.clone()>{name}</option> })
+                                                                                .collect_view()}
+                                                                        </select>
+                                                                        <button
+                                                                            type="button"
+                                                                            class="py-0.5 px-2 font-medium text-white bg-blue-600 rounded hover:bg-blue-700 disabled:opacity-50"
+                                                                            title="Transcribes just this stretch again; the transcript changes only if you accept"
+                                                                            disabled=move || refine_model.get().is_empty() || refining.get()
+                                                                            on:click=move |_| {
+                                                                                let job_id = job_id.clone();
+                                                                                let model = refine_model.get_untracked();
+                                                                                let (start_ms, end_ms) = (cue.start_ms, cue.end_ms);
+                                                                                refining.set(true);
+                                                                                spawn_local(async move {
+                                                                                    set_error_message.set(None);
+                                                                                    match refine_range_in_backend(job_id, start_ms, end_ms, model).await {
+                                                                                        Ok(segments) => refined.set(Some(RefinedRange {
+                                                                                            start_ms,
+                                                                                            end_ms,
+                                                                                            segments,
+                                                                                        })),
+                                                                                        Err(e) => set_error_message.set(Some(e)),
+                                                                                    }
+                                                                                    refining.set(false);
+                                                                                });
+                                                                            }
+                                                                        >
+                                                                            {move || if refining.get() { "Refining..." } else { "Refine" }}
+                                                                        </button>
+                                                                    </div>
+                                                                })
+                                                            }
+                                                        }
+                                                    }
+                                                })
+                                        }}
+                                        {move || {
+                                            let proposal = refined.get()?;
+                                            let job_id = accept_job_id.clone();
+                                            let range = format!(
+                                                "New text for {} → {}",
+                                                format_cue_time(proposal.start_ms),
+                                                format_cue_time(proposal.end_ms),
+                                            );
+                                            let text = proposal
+                                                .segments
+                                                .iter()
+                                                .map(|segment| segment.text.trim())
+                                                .collect::<Vec<_>>()
+                                                .join(" ");
+                                            Some(view! {
+                                                <div class="p-2 mt-2 text-xs bg-amber-50 rounded border border-amber-200">
+                                                    <p class="mb-1 text-gray-600">{range}</p>
+                                                    <p class="text-gray-800">{text}</p>
+                                                    <div class="flex mt-1 space-x-2">
+                                                        <button
+                                                            type="button"
+                                                            class="py-1 px-2 font-medium text-white bg-blue-600 rounded hover:bg-blue-700"
+                                                            on:click=move |_| {
+                                                                let job_id = job_id.clone();
+                                                                let proposal = proposal.clone();
+                                                                spawn_local(async move {
+                                                                    set_error_message.set(None);
+                                                                    match apply_refined_range_in_backend(job_id.clone(), proposal).await {
+                                                                        Ok(()) => {
+                                                                            refined.set(None);
+                                                                            set_info_message.set(Some("Replaced the refined cue".to_string()));
+                                                                            match get_job_cues_in_backend(job_id).await {
+                                                                                Ok(parsed) => subtitles.set(Some(parsed)),
+                                                                                Err(e) => set_error_message.set(Some(e)),
+                                                                            }
+                                                                        }
+                                                                        Err(e) => set_error_message.set(Some(e)),
+                                                                    }
+                                                                });
+                                                            }
+                                                        >
+                                                            "Accept"
+                                                        </button>
+                                                        <button
+                                                            type="button"
+                                                            class="py-1 px-2 font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50"
+                                                            on:click=move |_| refined.set(None)
+                                                        >
+                                                            "Discard"
+                                                        </button>
+                                                    </div>
+                                                </div>
+                                            })
+                                        }}
+                                        <Show when=move || edited_text.get().is_some()>
+                                            <textarea
+                                                class="p-2 mt-2 w-full h-64 font-mono text-xs rounded border border-gray-300"
+                                                prop:value=move || edited_text.get().unwrap_or_default()
+                                                on:input=move |ev| edited_text.set(Some(event_target_value(&ev)))
+                                            ></textarea>
+                                            <div class="flex mt-1 space-x-2">
+                                                <button
+                                                    type="button"
+                                                    class="py-1 px-2 text-xs font-medium text-white bg-blue-600 rounded hover:bg-blue-700"
+                                                    on:click={
+                                                        let job_id = edit_job_id.clone();
+                                                        move |_| {
+                                                            let job_id = job_id.clone();
+                                                            let content = edited_text.get_untracked().unwrap_or_default();
+                                                            spawn_local(async move {
+                                                                set_error_message.set(None);
+                                                                match save_edited_transcript_in_backend(job_id, content).await {
+                                                                    Ok(()) => {
+                                                                        edited_text.set(None);
+                                                                        subtitles.set(None);
+                                                                        set_info_message.set(Some("Saved transcript".to_string()));
+                                                                    }
+                                                                    Err(e) => set_error_message.set(Some(e)),
+                                                                }
+                                                            });
+                                                        }
+                                                    }
+                                                >
+                                                    "Save"
+                                                </button>
+                                                <button
+                                                    type="button"
+                                                    class="py-1 px-2 text-xs font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50"
+                                                    on:click=move |_| edited_text.set(None)
+                                                >
+                                                    "Discard"
+                                                </button>
+                                            </div>
+                                        </Show>
+                                        <Show when=move || is_pending>
+                                            {
+                                                move || match audio_streams.get() {
+                                                    None => {
+                                                        let file_path = file_path.clone();
+                                                        view! {
+                                                            <button
+                                                                type="button"
+                                                                class="mt-1 text-xs text-blue-600 hover:underline"
+                                                                on:click=move |_| {
+                                                                    let file_path = file_path.clone();
+                                                                    spawn_local(async move {
+                                                                        match list_audio_streams_in_backend(file_path).await {
+                                                                            Ok(streams) => audio_streams.set(Some(streams)),
+                                                                            Err(e) => set_error_message.set(Some(e)),
+                                                                        }
+                                                                    });
+                                                                }
+                                                            >
+                                                                "Choose audio track"
+                                                            </button>
+                                                        }
+                                                            .into_any()
+                                                    }
+                                                    Some(streams) if streams.len() <= 1 => {
+                                                        view! {
+                                                            <p class="mt-1 text-xs text-gray-500">"Single audio track"</p>
+                                                        }
+                                                            .into_any()
+                                                    }
+                                                    Some(streams) => {
+                                                        let job_id = stream_job_id.clone();
+                                                        view! {
+                                                            <select
+                                                                class="py-0.5 px-1 mt-1 text-xs rounded border border-gray-300"
+                                                                on:change=move |ev| {
+                                                                    let index = event_target_value(&ev).parse::<usize>().ok();
+                                                                    set_files
+                                                                        .update(|files| {
+                                                                            if let Some(file) = files
+                                                                                .iter_mut()
+                                                                                .find(|f| f.id == job_id)
+                                                                            {
+                                                                                file.audio_stream_index = index;
+                                                                            }
+                                                                        });
+                                                                }
+                                                            >
+                                                                <option value="">"Default audio track"</option>
+                                                                {streams
+                                                                    .into_iter()
+                                                                    .map(|stream| {
+                                                                        view! {
+                                                                            <option
+                                                                                value=stream.index.to_string()
+                                                                                selected=selected_stream == Some(stream.index)
+                                                                            >
+                                                                                {stream.label()}
+                                                                            </option>
+                                                                        }
+                                                                    })
+                                                                    .collect_view()}
+                                                            </select>
+                                                        }
+                                                            .into_any()
+                                                    }
+                                                }
+                                            }
+                                        </Show>
+                                    </div>
+                                    <div class="text-right">
+                                        <p class="text-xs text-gray-500">{format_size(file.size)}</p>
+                                        <p class=format!("text-xs {}", file.status.text_class())>
+                                            {if file.status == FileStatus::Converting
+                                                || file.status == FileStatus::Transcribing
+                                            {
+                                                format!("{} {:.0}%", file.status.display_name(), file.progress)
+                                            } else {
+                                                file.status.display_name().to_string()
+                                            }}
+                                        </p>
+                                        {file
+                                            .error
+                                            .clone()
+                                            .map(|error| {
+                                                view! { <p class="text-xs text-red-600">{error}</p> }
+                                            })}
+                                        {file
+                                            .notice
+                                            .clone()
+                                            .map(|notice| {
+                                                view! { <p class="text-xs text-amber-600">{notice}</p> }
+                                            })}
+                                        <Show when=move || is_waiting>
+                                            <button
+                                                type="button"
+                                                class="px-1 text-xs text-gray-500 hover:text-gray-900"
+                                                title="Move up"
+                                                on:click={
+                                                    let job_id = queue_job_id.clone();
+                                                    move |_| move_file(job_id.clone(), -1)
+                                                }
+                                            >
+                                                "▲"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class="px-1 text-xs text-gray-500 hover:text-gray-900"
+                                                title="Move down"
+                                                on:click={
+                                                    let job_id = queue_job_id.clone();
+                                                    move |_| move_file(job_id.clone(), 1)
+                                                }
+                                            >
+                                                "▼"
+                                            </button>
+                                        </Show>
+                                        <Show when=move || is_completed>
+                                            <button
+                                                type="button"
+                                                class="py-1 px-2 mt-1 text-xs font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50"
+                                                title="Copies the output file as-is, including JSON"
+                                                on:click={
+                                                    let job_id = job_id.clone();
+                                                    let file_name = file_name.clone();
+                                                    move |_| {
+                                                        let job_id = job_id.clone();
+                                                        let file_name = file_name.clone();
+                                                        spawn_local(async move {
+                                                            set_error_message.set(None);
+                                                            match copy_transcript_in_backend(job_id).await {
+                                                                Ok(()) => {
+                                                                    set_info_message
+                                                                        .set(Some(format!("Copied transcript of {}", file_name)))
+                                                                }
+                                                                Err(e) => set_error_message.set(Some(e)),
+                                                            }
+                                                        });
+                                                    }
+                                                }
+                                            >
+                                                "Copy"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class="py-1 px-2 mt-1 ml-1 text-xs font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50"
+                                                title="Copies the output file's path"
+                                                on:click={
+                                                    let job_id = job_id.clone();
+                                                    move |_| {
+                                                        let job_id = job_id.clone();
+                                                        spawn_local(async move {
+                                                            set_error_message.set(None);
+                                                            match copy_path_in_backend(job_id).await {
+                                                                Ok(()) => set_info_message.set(Some("Copied output path".to_string())),
+                                                                Err(e) => set_error_message.set(Some(e)),
+                                                            }
+                                                        });
+                                                    }
+                                                }
+                                            >
+                                                "Copy path"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class="py-1 px-2 mt-1 ml-1 text-xs font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50"
+                                                title="Opens the output in your default editor"
+                                                on:click={
+                                                    let job_id = job_id.clone();
+                                                    move |_| {
+                                                        let job_id = job_id.clone();
+                                                        spawn_local(async move {
+                                                            set_error_message.set(None);
+                                                            if let Err(e) = open_in_default_app(job_id).await {
+                                                                set_error_message.set(Some(e));
+                                                            }
+                                                        });
+                                                    }
+                                                }
+                                            >
+                                                "Open"
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class="py-1 px-2 mt-1 ml-1 text-xs font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50"
+                                                title="Saves the transcript to a location you choose"
+                                                on:click={
+                                                    let job_id = job_id.clone();
+                                                    let output_name = output_name.clone();
+                                                    move |_| {
+                                                        let job_id = job_id.clone();
+                                                        let output_name = output_name.clone();
+                                                        spawn_local(async move {
+                                                            set_error_message.set(None);
+                                                            let options = serde_wasm_bindgen::to_value(&SaveDialogOptions {
+                                                                default_path: output_name,
+                                                            })
+                                                            .unwrap();
+                                                            let Some(target_path) = save(options).await.as_string() else {
+                                                                return;
+                                                            };
+                                                            match save_transcript_in_backend(job_id, target_path.clone()).await {
+                                                                Ok(()) => set_info_message.set(Some(format!("Saved {}", target_path))),
+                                                                Err(e) => set_error_message.set(Some(e)),
+                                                            }
+                                                        });
+                                                    }
+                                                }
+                                            >
+                                                "Save as..."
+                                            </button>
+                                            <button
+                                                type="button"
+                                                class="py-1 px-2 mt-1 ml-1 text-xs font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50 disabled:opacity-50"
+                                                title="Corrects the transcript by hand"
+                                                disabled=move || edited_text.get().is_some()
+                                                on:click={
+                                                    let job_id = job_id.clone();
+                                                    move |_| {
+                                                        let job_id = job_id.clone();
+                                                        spawn_local(async move {
+                                                            set_error_message.set(None);
+                                                            match get_job_output_in_backend(job_id).await {
+                                                                Ok(text) => edited_text.set(Some(text)),
+                                                                Err(e) => set_error_message.set(Some(e)),
+                                                            }
+                                                        });
+                                                    }
+                                                }
+                                            >
+                                                "Edit"
+                                            </button>
+                                            <Show when=move || has_cues>
+                                                <button
+                                                    type="button"
+                                                    class="py-1 px-2 mt-1 ml-1 text-xs font-medium text-gray-700 bg-white rounded border border-gray-300 hover:bg-gray-50"
+                                                    on:click={
+                                                        let job_id = job_id.clone();
+                                                        move |_| {
+                                                            if subtitles.get_untracked().is_some() {
+                                                                subtitles.set(None);
+                                                                return;
+                                                            }
+                                                            let job_id = job_id.clone();
+                                                            spawn_local(async move {
+                                                                set_error_message.set(None);
+                                                                match get_job_cues_in_backend(job_id).await {
+                                                                    Ok(parsed) => subtitles.set(Some(parsed)),
+                                                                    Err(e) => set_error_message.set(Some(e)),
+                                                                }
+                                                            });
+                                                        }
+                                                    }
+                                                >
+                                                    {move || if subtitles.get().is_some() { "Hide preview" } else { "Preview" }}
+                                                </button>
+                                            </Show>
+                                            <select
+                                                class="py-1 px-1 mt-1 ml-1 text-xs text-gray-700 bg-white rounded border border-gray-300"
+                                                title="Writes the transcript again in another format, without re-running whisper"
+                                                prop:value=move || export_choice.get()
+                                                on:change={
+                                                    let job_id = job_id.clone();
+                                                    move |ev| {
+                                                        let format = match event_target_value(&ev).as_str() {
+                                                            "Srt" => OutputFormat::Srt,
+                                                            "Txt" => OutputFormat::Txt,
+                                                            "Json" => OutputFormat::Json,
+                                                            "Vtt" => OutputFormat::Vtt,
+                                                            _ => return,
+                                                        };
+                                                        export_choice.set(String::new());
+                                                        let job_id = job_id.clone();
+                                                        spawn_local(async move {
+                                                            set_error_message.set(None);
+                                                            match reexport_job_in_backend(job_id, format).await {
+                                                                Ok(path) => {
+                                                                    set_info_message
+                                                                        .set(Some(format!("Exported {}", path.to_string_lossy())))
+                                                                }
+                                                                Err(e) => set_error_message.set(Some(e)),
+                                                            }
+                                                        });
+                                                    }
+                                                }
+                                            >
+                                                <option value="">"Export as..."</option>
+                                                {[
+                                                    OutputFormat::Srt,
+                                                    OutputFormat::Txt,
+                                                    OutputFormat::Json,
+                                                    OutputFormat::Vtt,
+                                                ]
+                                                    .into_iter()
+                                                    .map(|format| {
+                                                        view! {
+                                                            <option value=format!(
+                                                                "{:?}",
+                                                                format,
+                                                            )>{format.display_name()}</option>
+                                                        }
+                                                    })
+                                                    .collect_view()}
+                                            </select>
+                                        </Show>
+                                    </div>
+                                </div>
+                            }
+                        }
+                    />
+                </div>
+            </Show>
         </div>
     }
 }
+
+async fn copy_transcript_in_backend(job_id: String) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&JobArgs { job_id })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    invoke("copy_transcript_to_clipboard", args)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy transcript: {}", js_error_message(e)))
+}
+
+async fn copy_path_in_backend(job_id: String) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&JobArgs { job_id })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    invoke("copy_path_to_clipboard", args)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to copy path: {}", js_error_message(e)))
+}
+
+async fn open_in_default_app(job_id: String) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&JobArgs { job_id })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    invoke("open_in_default_app", args)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open file: {}", js_error_message(e)))
+}
+
+async fn save_transcript_in_backend(job_id: String, target_path: String) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&SaveTranscriptArgs { job_id, target_path })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    invoke("save_transcript", args)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to save transcript: {}", js_error_message(e)))
+}
+
+async fn get_job_output_in_backend(job_id: String) -> Result<String, String> {
+    let args = serde_wasm_bindgen::to_value(&JobArgs { job_id })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = invoke("get_job_output", args)
+        .await
+        .map_err(|e| format!("Failed to read transcript: {}", js_error_message(e)))?;
+    result
+        .as_string()
+        .ok_or_else(|| "Failed to read transcript: not text".to_string())
+}
+
+async fn save_edited_transcript_in_backend(job_id: String, content: String) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&SaveEditedTranscriptArgs { job_id, content })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    invoke("save_edited_transcript", args)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to save transcript: {}", js_error_message(e)))
+}
+
+async fn get_job_cues_in_backend(job_id: String) -> Result<ParsedSubtitles, String> {
+    let args = serde_wasm_bindgen::to_value(&JobArgs { job_id })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = invoke("get_job_cues", args)
+        .await
+        .map_err(|e| format!("Failed to read subtitles: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse subtitles: {}", e))
+}
+
+async fn get_downloaded_models_in_backend() -> Result<Vec<String>, String> {
+    let result = invoke("get_available_models", JsValue::NULL)
+        .await
+        .map_err(|e| format!("Failed to load models: {}", js_error_message(e)))?;
+    let models: Vec<WhisperModel> = serde_wasm_bindgen::from_value(result)
+        .map_err(|e| format!("Failed to parse models: {}", e))?;
+    Ok(models
+        .into_iter()
+        .filter(|model| model.downloaded)
+        .map(|model| model.name)
+        .collect())
+}
+
+async fn refine_range_in_backend(
+    job_id: String,
+    start_ms: u64,
+    end_ms: u64,
+    model: String,
+) -> Result<Vec<Segment>, String> {
+    let args = serde_wasm_bindgen::to_value(&RefineRangeArgs {
+        job_id,
+        start_secs: start_ms as f64 / 1000.0,
+        end_secs: end_ms as f64 / 1000.0,
+        model,
+    })
+    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = invoke("refine_range", args)
+        .await
+        .map_err(|e| format!("Failed to refine transcript: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse segments: {}", e))
+}
+
+async fn apply_refined_range_in_backend(job_id: String, refined: RefinedRange) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&ApplyRefinedRangeArgs {
+        job_id,
+        start_secs: refined.start_ms as f64 / 1000.0,
+        end_secs: refined.end_ms as f64 / 1000.0,
+        segments: refined.segments,
+    })
+    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    invoke("apply_refined_range", args)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to apply refined text: {}", js_error_message(e)))
+}
+
+async fn cancel_all_jobs_in_backend() -> Result<Vec<JobSnapshot>, String> {
+    let result = invoke("cancel_all_jobs", JsValue::NULL)
+        .await
+        .map_err(|e| format!("Failed to stop jobs: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse jobs: {}", e))
+}
+
+async fn retry_all_failed_in_backend() -> Result<Vec<JobSnapshot>, String> {
+    let result = invoke("retry_all_failed", JsValue::NULL)
+        .await
+        .map_err(|e| format!("Failed to retry jobs: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse jobs: {}", e))
+}
+
+async fn move_job_in_backend(job_id: String, new_index: usize) -> Result<(), String> {
+    let args = serde_wasm_bindgen::to_value(&MoveJobArgs { job_id, new_index })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    invoke("move_job", args)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to move job: {}", js_error_message(e)))
+}
+
+async fn reexport_job_in_backend(job_id: String, format: OutputFormat) -> Result<PathBuf, String> {
+    let args = serde_wasm_bindgen::to_value(&ReexportArgs { job_id, format })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = invoke("reexport_job", args)
+        .await
+        .map_err(|e| format!("Failed to export transcript: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse export path: {}", e))
+}
+
+async fn list_audio_streams_in_backend(file_path: PathBuf) -> Result<Vec<AudioStreamInfo>, String> {
+    let args = serde_wasm_bindgen::to_value(&FilePathArgs {
+        file_path: file_path.to_string_lossy().to_string(),
+    })
+    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = invoke("list_audio_streams", args)
+        .await
+        .map_err(|e| format!("Failed to read audio tracks: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse audio tracks: {}", e))
+}
+
+async fn get_media_info_in_backend(file_path: PathBuf) -> Result<MediaInfo, String> {
+    let args = serde_wasm_bindgen::to_value(&FilePathArgs {
+        file_path: file_path.to_string_lossy().to_string(),
+    })
+    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+    let result = invoke("get_media_info", args)
+        .await
+        .map_err(|e| format!("Failed to read file info: {}", js_error_message(e)))?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse file info: {}", e))
+}
+
+fn format_estimate(estimate: &BatchEstimate) -> String {
+    let (label, secs) = match estimate.estimated_processing_secs {
+        Some(secs) => ("Estimated", secs),
+        None => ("Audio length", estimate.total_duration_secs),
+    };
+
+    let mut text = if secs < 60.0 {
+        format!("{} < 1 min", label)
+    } else {
+        format!("{} ~{} min", label, (secs / 60.0).ceil() as u64)
+    };
+    text.push_str(&format!(", {} outputs", estimate.output_count));
+    if estimate.unknown_duration_count > 0 {
+        text.push_str(&format!(
+            " ({} files of unknown length)",
+            estimate.unknown_duration_count
+        ));
+    }
+    text
+}
+
+/// `H:MM:SS.mmm`, or `MM:SS.mmm` under an hour.
+fn format_cue_time(ms: u64) -> String {
+    let secs = ms / 1000;
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}.{:03}", secs / 3600, secs / 60 % 60, secs % 60, ms % 1000)
+    } else {
+        format!("{:02}:{:02}.{:03}", secs / 60, secs % 60, ms % 1000)
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+async fn load_settings_from_backend() -> Result<TranscriptionSettings, String> {
+    let result = invoke("load_settings", JsValue::NULL)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse settings: {}", e))
+}
+
+/// The settings with the model a batch would actually run.
+async fn get_effective_settings_in_backend(
+    settings: TranscriptionSettings,
+) -> Result<TranscriptionSettings, String> {
+    let args = SettingsArgs { settings }
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+
+    let result = invoke("get_effective_settings", args)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse settings: {}", e))
+}
+
+async fn estimate_batch_in_backend(
+    files: Vec<FileEntry>,
+    settings: TranscriptionSettings,
+) -> Result<BatchEstimate, String> {
+    let args = TranscriptionArgs { files, settings }
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+
+    let result = invoke("estimate_batch", args)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse estimate: {}", e))
+}
+
+/// Starts the batch, returning warnings about the chosen output format.
+async fn start_transcription_in_backend(
+    files: Vec<FileEntry>,
+    settings: TranscriptionSettings,
+) -> Result<Vec<String>, String> {
+    let args = TranscriptionArgs { files, settings }
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+
+    let result = invoke("start_transcription", args)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse warnings: {}", e))
+}
+
+async fn add_files_to_backend(file_paths: Vec<String>) -> Result<AddFilesResult, String> {
+    let args = serde_wasm_bindgen::to_value(&AddFilesArgs { file_paths })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+
+    let result = invoke("add_files", args).await.map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse files: {}", e))
+}
+
+async fn add_url_to_backend(url: String) -> Result<FileEntry, String> {
+    let args = serde_wasm_bindgen::to_value(&AddUrlArgs { url })
+        .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+
+    let result = invoke("add_url", args).await.map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result).map_err(|e| format!("Failed to parse file: {}", e))
+}
+
+async fn add_directory_to_backend(
+    dir_path: String,
+    recursive: bool,
+) -> Result<DirectoryScanResult, String> {
+    let args = serde_wasm_bindgen::to_value(&AddDirectoryArgs {
+        dir_path,
+        recursive,
+    })
+    .map_err(|e| format!("Failed to serialize arguments: {}", e))?;
+
+    let result = invoke("add_directory", args)
+        .await
+        .map_err(js_error_message)?;
+    serde_wasm_bindgen::from_value(result)
+        .map_err(|e| format!("Failed to parse directory scan: {}", e))
+}
+
+async fn select_files() -> Result<Vec<String>, String> {
+    let options = serde_wasm_bindgen::to_value(&DialogOptions {
+        directory: false,
+        multiple: true,
+    })
+    .map_err(|e| format!("Failed to create dialog options: {}", e))?;
+
+    let result = open(options).await;
+
+    if result.is_null() || result.is_undefined() {
+        Ok(Vec::new())
+    } else {
+        serde_wasm_bindgen::from_value(result)
+            .map_err(|e| format!("Failed to parse selected files: {}", e))
+    }
+}
+
+async fn select_directory() -> Result<Option<String>, String> {
+    let options = serde_wasm_bindgen::to_value(&DialogOptions {
+        directory: true,
+        multiple: false,
+    })
+    .map_err(|e| format!("Failed to create dialog options: {}", e))?;
+
+    let result = open(options).await;
+
+    if result.is_null() || result.is_undefined() {
+        Ok(None)
+    } else {
+        serde_wasm_bindgen::from_value(result)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse selected directory: {}", e))
+    }
+}